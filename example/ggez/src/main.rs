@@ -7,7 +7,7 @@ use std::sync::{Arc, RwLock};
 
 use moecs::component::{Component, ComponentBundle};
 use moecs::entity::{EntityManager, Query};
-use moecs::system::{System, SystemGroup, SystemParam, SystemParamAccessor};
+use moecs::system::{System, SystemAccess, SystemGroup, SystemParam, SystemParamAccessor};
 use moecs::Engine;
 
 #[derive(Component)]
@@ -54,20 +54,16 @@ impl System for PhysicsSystem {
         entity_manager
             .read()
             .unwrap()
-            .filter(
-                Query::new()
-                    .with::<PositionComponent>()
-                    .with::<VelocityComponent>(),
-            )
-            .iter()
-            .for_each(|result| {
-                let position = result.get_component::<PositionComponent>().unwrap();
-                let velocity = result.get_component::<VelocityComponent>().unwrap();
-
-                position.write().unwrap().x += velocity.read().unwrap().x_vel;
-                position.write().unwrap().y += velocity.read().unwrap().y_vel;
+            .query(&Query::<(&mut PositionComponent, &VelocityComponent)>::default())
+            .for_each(|(position, velocity)| {
+                position.x += velocity.x_vel;
+                position.y += velocity.y_vel;
             });
     }
+
+    fn access() -> SystemAccess {
+        SystemAccess::new().add_query::<(&mut PositionComponent, &VelocityComponent)>()
+    }
 }
 
 #[derive(SystemParam)]
@@ -85,21 +81,14 @@ impl System for RenderSystem {
         entity_manager
             .read()
             .unwrap()
-            .filter(
-                Query::new()
-                    .with::<PositionComponent>()
-                    .with::<DrawComponent>(),
-            )
-            .iter()
-            .for_each(|result| {
-                let position = result.get_component::<PositionComponent>().unwrap();
-
+            .query(&Query::<&PositionComponent>::default().with::<DrawComponent>())
+            .for_each(|position| {
                 canvas.draw(
                     &graphics::Quad,
                     graphics::DrawParam::new()
                         .dest_rect(graphics::Rect {
-                            x: position.read().unwrap().x,
-                            y: position.read().unwrap().y,
+                            x: position.x,
+                            y: position.y,
                             w: 10.0,
                             h: 10.0,
                         })
@@ -148,11 +137,12 @@ impl event::EventHandler<ggez::GameError> for GameState {
             graphics::Canvas::from_frame(context, graphics::Color::from([0.1, 0.2, 0.3, 1.0]));
         self.engine.execute_group(
             self.render_systems,
-            SystemParamAccessor::new().add_param(CanvasParam {
+            SystemParamAccessor::new().add_local_param(CanvasParam {
                 canvas: &mut canvas,
             }),
         );
         canvas.finish(context)?;
+        self.engine.finish_frame();
         Ok(())
     }
 }
@@ -4,8 +4,8 @@
 use std::sync::{Arc, RwLock};
 
 use moecs::component::{Component, ComponentBundle};
-use moecs::entity::{EntityManager, Query};
-use moecs::system::{System, SystemGroup, SystemParamAccessor};
+use moecs::entity::{Entity, EntityManager, Query};
+use moecs::system::{System, SystemAccess, SystemGroup, SystemParamAccessor};
 use moecs::Engine;
 
 #[derive(Component, Debug)]
@@ -27,26 +27,18 @@ impl System for PhysicsSystem {
         entity_manager
             .read()
             .unwrap()
-            .filter(
-                Query::new()
-                    .with::<PositionComponent>()
-                    .with::<VelocityComponent>(),
-            )
-            .iter()
-            .for_each(|result| {
-                let position = result.get_component::<PositionComponent>().unwrap();
-                let velocity = result.get_component::<VelocityComponent>().unwrap();
+            .query(&Query::<(Entity, &mut PositionComponent, &VelocityComponent)>::default())
+            .for_each(|(entity, position, velocity)| {
+                position.x += velocity.x_vel;
+                position.y += velocity.y_vel;
 
-                position.write().unwrap().x += velocity.read().unwrap().x_vel;
-                position.write().unwrap().y += velocity.read().unwrap().y_vel;
-
-                println!(
-                    "Entity: {} has position: {:?}",
-                    result.entity_id(),
-                    position
-                );
+                println!("Entity: {} has position: {:?}", entity, position);
             });
     }
+
+    fn access() -> SystemAccess {
+        SystemAccess::new().add_query::<(&mut PositionComponent, &VelocityComponent)>()
+    }
 }
 
 #[derive(System)]
@@ -83,5 +75,6 @@ fn main() {
     );
     for i in 0..5 {
         engine.execute_group(update_systems, SystemParamAccessor::new());
+        engine.finish_frame();
     }
 }
@@ -31,9 +31,48 @@ pub fn derive_system_param(input: TokenStream) -> TokenStream {
 
     let ast = parse_macro_input!(input_clone as DeriveInput);
     let name_literal = &ast.ident;
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    // `Rebind<'p>` replaces every lifetime with `'p`, and the `TypeId` is taken of the
+    // instantiation with every lifetime replaced by `'static`.
+    let rebind_lifetime = syn::Lifetime::new("'__moecs_p", proc_macro2::Span::call_site());
+    let static_lifetime = syn::Lifetime::new("'static", proc_macro2::Span::call_site());
+    let rebind_args = substitute_lifetimes(&ast.generics, &rebind_lifetime);
+    let static_args = substitute_lifetimes(&ast.generics, &static_lifetime);
+
+    let mut generics = ast.generics.clone();
+    let type_idents: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    type_idents.iter().for_each(|ident| {
+        generics
+            .make_where_clause()
+            .predicates
+            .push(syn::parse_quote! { #ident: 'static });
+    });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
     let system_param_impl = TokenStream::from(quote! {
-        impl #impl_generics ::moecs::system::SystemParam for #name_literal #ty_generics #where_clause {}
+        unsafe impl #impl_generics ::moecs::system::SystemParam for #name_literal #ty_generics #where_clause {
+            type Rebind<#rebind_lifetime> = #name_literal<#(#rebind_args),*>
+            where
+                Self: #rebind_lifetime;
+
+            fn rebind<#rebind_lifetime>(self) -> Self::Rebind<#rebind_lifetime>
+            where
+                Self: #rebind_lifetime + Sized,
+            {
+                // Fails to compile if `Self` is not covariant in its lifetimes.
+                self
+            }
+
+            fn static_type_id() -> ::std::any::TypeId
+            where
+                Self: Sized,
+            {
+                ::std::any::TypeId::of::<#name_literal<#(#static_args),*>>()
+            }
+        }
     });
 
     let property_id_impl = derive_property_id(input.clone());
@@ -41,42 +80,101 @@ pub fn derive_system_param(input: TokenStream) -> TokenStream {
     TokenStream::from_iter(vec![system_param_impl, property_id_impl])
 }
 
+#[proc_macro_derive(Resource)]
+pub fn derive_resource(input: TokenStream) -> TokenStream {
+    let input_clone = input.clone();
+
+    let ast = parse_macro_input!(input_clone as DeriveInput);
+    let name_literal = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let resource_impl = TokenStream::from(quote! {
+        impl #impl_generics ::moecs::resource::Resource for #name_literal #ty_generics #where_clause {}
+    });
+
+    let property_id_impl = derive_property_id(input.clone());
+
+    TokenStream::from_iter(vec![resource_impl, property_id_impl])
+}
+
+#[proc_macro_derive(Event)]
+pub fn derive_event(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let name_literal = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    TokenStream::from(quote! {
+        impl #impl_generics ::moecs::event::Event for #name_literal #ty_generics #where_clause {}
+    })
+}
+
+/// Returns the generic arguments of a type with the provided generic parameters, with every lifetime
+/// replaced by `lifetime`.
+fn substitute_lifetimes(
+    generics: &syn::Generics,
+    lifetime: &syn::Lifetime,
+) -> Vec<proc_macro2::TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            syn::GenericParam::Lifetime(_) => quote! { #lifetime },
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+                quote! { #ident }
+            }
+        })
+        .collect()
+}
+
 fn derive_property_id(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
 
     let name_literal = &ast.ident;
-    let name_string = &ast.ident.to_string();
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
+    // Statics are shared between all instantiations of a generic type, so the id may only be
+    // cached in a static if there are no type or const parameters. Generic types use a shared
+    // cache instead.
+    let property_id_body = if ast.generics.type_params().next().is_none()
+        && ast.generics.const_params().next().is_none()
+    {
+        quote! {
+            static HASH: ::std::sync::OnceLock<u64> = ::std::sync::OnceLock::new();
+            *HASH.get_or_init(|| {
+                ::moecs::util::hash_property_string(
+                    <Self as ::moecs::util::PropertyId>::property_string(),
+                )
+            })
+        }
+    } else {
+        quote! {
+            ::moecs::util::cached_hash_property_string(
+                <Self as ::moecs::util::PropertyId>::property_string(),
+            )
+        }
+    };
+
     TokenStream::from(quote! {
         impl #impl_generics ::moecs::util::PropertyId for #name_literal #ty_generics #where_clause {
             fn property_string() -> &'static str
             where Self: Sized {
-                static PROPERTY_STRING: &'static str = concat!(module_path!(), "::", #name_string);
-                PROPERTY_STRING
+                ::std::any::type_name::<Self>()
             }
 
             fn property_id() -> u64
             where Self: Sized {
-                use ::std::collections::hash_map::DefaultHasher;
-                use ::std::hash::{Hash, Hasher};
-                use ::std::sync::OnceLock;
-
-                static HASH: OnceLock<u64> = OnceLock::new();
-                *HASH.get_or_init(|| {
-                    let key = #name_literal::property_string();
-                    let mut hasher = DefaultHasher::new();
-                    key.hash(&mut hasher);
-                    hasher.finish()
-                })
+                #property_id_body
             }
 
             fn self_property_id(&self) -> u64 {
-                #name_literal::property_id()
+                <Self as ::moecs::util::PropertyId>::property_id()
             }
 
             fn self_property_string(&self) -> &'static str {
-                #name_literal::property_string()
+                <Self as ::moecs::util::PropertyId>::property_string()
             }
         }
     })
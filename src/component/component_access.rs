@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use crate::component::Component;
+
+/// Describes which `Component` types are read and written by some process, e.g. a typed `Query`.
+/// Two processes with compatible access may safely run at the same time.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ComponentAccess {
+    reads: BTreeMap<u64, &'static str>,
+    writes: BTreeMap<u64, &'static str>,
+}
+
+impl ComponentAccess {
+    pub fn new() -> Self {
+        ComponentAccess {
+            reads: BTreeMap::new(),
+            writes: BTreeMap::new(),
+        }
+    }
+
+    /// Records shared access to `Component`s of type `T`.
+    pub fn add_read<T: 'static + Component>(&mut self) -> &mut ComponentAccess {
+        self.add_read_by_id(T::property_id(), T::property_string())
+    }
+
+    /// Records exclusive access to `Component`s of type `T`.
+    pub fn add_write<T: 'static + Component>(&mut self) -> &mut ComponentAccess {
+        self.add_write_by_id(T::property_id(), T::property_string())
+    }
+
+    /// Records shared access to some `PropertyId`, e.g. that of a `Resource`.
+    pub(crate) fn add_read_by_id(&mut self, id: u64, name: &'static str) -> &mut ComponentAccess {
+        self.reads.insert(id, name);
+        self
+    }
+
+    /// Records exclusive access to some `PropertyId`, e.g. that of a `Resource`.
+    pub(crate) fn add_write_by_id(&mut self, id: u64, name: &'static str) -> &mut ComponentAccess {
+        self.writes.insert(id, name);
+        self
+    }
+
+    /// Merges all access recorded in `other` into this one.
+    pub fn extend(&mut self, other: &ComponentAccess) -> &mut ComponentAccess {
+        self.reads.extend(other.reads.iter());
+        self.writes.extend(other.writes.iter());
+        self
+    }
+
+    pub fn reads(&self, component_id: &u64) -> bool {
+        self.reads.contains_key(component_id)
+    }
+
+    pub fn writes(&self, component_id: &u64) -> bool {
+        self.writes.contains_key(component_id)
+    }
+
+    /// Returns `true` if nothing is read or written.
+    pub fn is_empty(&self) -> bool {
+        self.reads.is_empty() && self.writes.is_empty()
+    }
+
+    /// Returns the names of all `Component` types that one side writes and the other reads or
+    /// writes. An empty result means both may run concurrently.
+    pub fn get_conflicts(&self, other: &ComponentAccess) -> Vec<&'static str> {
+        let mut conflicts: Vec<&'static str> = self
+            .writes
+            .iter()
+            .filter(|(component_id, _)| other.reads(component_id) || other.writes(component_id))
+            .chain(
+                other
+                    .writes
+                    .iter()
+                    .filter(|(component_id, _)| self.reads(component_id)),
+            )
+            .map(|(_, name)| *name)
+            .collect();
+        conflicts.sort_unstable();
+        conflicts.dedup();
+        conflicts
+    }
+
+    pub fn is_compatible(&self, other: &ComponentAccess) -> bool {
+        self.get_conflicts(other).is_empty()
+    }
+
+    /// Iterates over every accessed `Component` id in ascending order, alongside `true` if it is
+    /// written.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (u64, bool)> + '_ {
+        let mut accessed: BTreeMap<u64, bool> = self
+            .reads
+            .keys()
+            .map(|component_id| (*component_id, false))
+            .collect();
+        accessed.extend(self.writes.keys().map(|component_id| (*component_id, true)));
+        accessed.into_iter()
+    }
+}
@@ -1,13 +1,14 @@
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
 
-use crate::component::Component;
+use crate::component::{Component, ComponentColumn, ComponentTicks, TypedColumn};
+use crate::util::register_property;
+use crate::{Error, Result};
 
 /// Represents a collection of `Component`s. Generally used in relation to an Entity (i.e. creating
 /// an Entity using the provided `Component`s).
 #[derive(Default)]
 pub struct ComponentBundle {
-    components: HashMap<u64, Arc<RwLock<dyn 'static + Component>>>,
+    components: HashMap<u64, Box<dyn ComponentColumn>>,
 }
 
 impl ComponentBundle {
@@ -20,39 +21,39 @@ impl ComponentBundle {
     /// Adds a given `Component` to the bundle.
     ///
     /// Note: will panic if multiple `Component`s of the same time are registered.
-    pub fn add_component<T: 'static + Component>(mut self, component: T) -> ComponentBundle {
-        if self.components.contains_key(&T::property_id()) {
-            panic!(
-                "Component {} cannot be registered more than once per Entity!",
-                &T::property_string()
-            );
+    pub fn add_component<T: 'static + Component>(self, component: T) -> ComponentBundle {
+        self.try_add_component(component)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Adds a given `Component` to the bundle. Fails with `Error::DuplicateComponent` if the bundle
+    /// already contains a `Component` of the same type.
+    pub fn try_add_component<T: 'static + Component>(
+        mut self,
+        component: T,
+    ) -> Result<ComponentBundle> {
+        let component_id = register_property::<T>();
+        if self.components.contains_key(&component_id) {
+            return Err(Error::DuplicateComponent(T::property_string()));
         }
 
-        self.components
-            .insert(T::property_id(), Arc::new(RwLock::new(component)));
-        self
+        let mut column = TypedColumn::<T>::new();
+        column.push(component, ComponentTicks::default());
+        self.components.insert(component_id, Box::new(column));
+        Ok(self)
     }
 
     /// Retrieve a `Component` of the provided type. Returns `Some(component)` if available, `None`
     /// otherwise.
-    pub fn get_component<T: 'static + Component>(
-        &self,
-    ) -> Option<Arc<RwLock<dyn 'static + Component>>> {
-        self.components.get(&T::property_id()).cloned()
-    }
-
-    pub(crate) fn add_component_arc(
-        &mut self,
-        component: Arc<RwLock<dyn 'static + Component>>,
-    ) -> &ComponentBundle {
-        self.components.insert(
-            component.read().unwrap().self_property_id(),
-            component.clone(),
-        );
-        self
+    pub fn get_component<T: 'static + Component>(&self) -> Option<&T> {
+        self.components
+            .get(&T::property_id())
+            .map(|column| column.typed::<T>().get(0))
     }
 
-    pub(crate) fn get_components(&self) -> &HashMap<u64, Arc<RwLock<dyn 'static + Component>>> {
-        &self.components
+    /// Each `Component` in the bundle is stored as a single-row column, so that it can be moved
+    /// directly into archetype storage.
+    pub(crate) fn into_components(self) -> HashMap<u64, Box<dyn ComponentColumn>> {
+        self.components
     }
 }
@@ -0,0 +1,118 @@
+use std::any::Any;
+
+use crate::component::{Component, ComponentTicks};
+
+/// Type-erased, contiguous storage for `Component`s of a single type. Archetypes hold one
+/// `ComponentColumn` per `Component` type, where row `n` of every column belongs to the same
+/// Entity.
+pub(crate) trait ComponentColumn: Send + Sync {
+    fn component_id(&self) -> u64;
+    fn component_name(&self) -> &'static str;
+
+    /// Creates a new, empty column storing the same `Component` type.
+    fn new_empty(&self) -> Box<dyn ComponentColumn>;
+
+    /// Removes (and drops) the `Component` at `row`, replacing it with the last one.
+    fn swap_remove(&mut self, row: usize);
+
+    /// Removes the `Component` at `row`, replacing it with the last one, and pushes it onto the
+    /// end of `destination`, which must store the same `Component` type.
+    fn move_row(&mut self, row: usize, destination: &mut dyn ComponentColumn);
+
+    fn get_ticks(&self, row: usize) -> ComponentTicks;
+    fn set_ticks(&mut self, row: usize, ticks: ComponentTicks);
+
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl dyn ComponentColumn + '_ {
+    pub fn typed<T: 'static + Component>(&self) -> &TypedColumn<T> {
+        self.as_any()
+            .downcast_ref::<TypedColumn<T>>()
+            .unwrap_or_else(|| panic!("Column does not store {}!", T::property_string()))
+    }
+
+    pub fn typed_mut<T: 'static + Component>(&mut self) -> &mut TypedColumn<T> {
+        self.as_any_mut()
+            .downcast_mut::<TypedColumn<T>>()
+            .unwrap_or_else(|| panic!("Column does not store {}!", T::property_string()))
+    }
+}
+
+pub(crate) struct TypedColumn<T: Component> {
+    components: Vec<T>,
+    ticks: Vec<ComponentTicks>,
+}
+
+impl<T: 'static + Component> TypedColumn<T> {
+    pub fn new() -> Self {
+        TypedColumn {
+            components: Vec::new(),
+            ticks: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, component: T, ticks: ComponentTicks) {
+        self.components.push(component);
+        self.ticks.push(ticks);
+    }
+
+    pub fn get(&self, row: usize) -> &T {
+        &self.components[row]
+    }
+
+    pub fn get_mut(&mut self, row: usize) -> &mut T {
+        &mut self.components[row]
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.components
+    }
+
+    /// Returns all `Component`s alongside their `ComponentTicks`.
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [ComponentTicks]) {
+        (&mut self.components, &mut self.ticks)
+    }
+}
+
+impl<T: 'static + Component> ComponentColumn for TypedColumn<T> {
+    fn component_id(&self) -> u64 {
+        T::property_id()
+    }
+
+    fn component_name(&self) -> &'static str {
+        T::property_string()
+    }
+
+    fn new_empty(&self) -> Box<dyn ComponentColumn> {
+        Box::new(TypedColumn::<T>::new())
+    }
+
+    fn swap_remove(&mut self, row: usize) {
+        self.components.swap_remove(row);
+        self.ticks.swap_remove(row);
+    }
+
+    fn move_row(&mut self, row: usize, destination: &mut dyn ComponentColumn) {
+        let component = self.components.swap_remove(row);
+        let ticks = self.ticks.swap_remove(row);
+        destination.typed_mut::<T>().push(component, ticks);
+    }
+
+    fn get_ticks(&self, row: usize) -> ComponentTicks {
+        self.ticks[row]
+    }
+
+    fn set_ticks(&mut self, row: usize, ticks: ComponentTicks) {
+        self.ticks[row] = ticks;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
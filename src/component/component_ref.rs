@@ -0,0 +1,77 @@
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+use crate::component::{Component, ComponentColumn};
+
+/// Shared access to a single `Component` stored in an `EntityManager`.
+///
+/// Note: this holds a read lock over every `Component` of the same type that shares an archetype
+/// with the referenced one. Requesting a `ComponentMut` for one of those while this is alive (on
+/// the same thread) will deadlock.
+pub struct ComponentRef<'a, T: 'static + Component> {
+    column: RwLockReadGuard<'a, Box<dyn ComponentColumn>>,
+    row: usize,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: 'static + Component> ComponentRef<'a, T> {
+    pub(crate) fn new(column: RwLockReadGuard<'a, Box<dyn ComponentColumn>>, row: usize) -> Self {
+        ComponentRef {
+            column,
+            row,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static + Component> Deref for ComponentRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.column.typed::<T>().get(self.row)
+    }
+}
+
+/// Exclusive access to a single `Component` stored in an `EntityManager`. The `Component` is
+/// considered changed (see `Query::changed`) once this is created.
+///
+/// Note: this holds a write lock over every `Component` of the same type that shares an archetype
+/// with the referenced one. Requesting another `ComponentRef` or `ComponentMut` for one of those
+/// while this is alive (on the same thread) will deadlock.
+pub struct ComponentMut<'a, T: 'static + Component> {
+    column: RwLockWriteGuard<'a, Box<dyn ComponentColumn>>,
+    row: usize,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: 'static + Component> ComponentMut<'a, T> {
+    pub(crate) fn new(
+        mut column: RwLockWriteGuard<'a, Box<dyn ComponentColumn>>,
+        row: usize,
+        change_tick: u64,
+    ) -> Self {
+        let mut ticks = column.get_ticks(row);
+        ticks.set_changed(change_tick);
+        column.set_ticks(row, ticks);
+        ComponentMut {
+            column,
+            row,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static + Component> Deref for ComponentMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.column.typed::<T>().get(self.row)
+    }
+}
+
+impl<T: 'static + Component> DerefMut for ComponentMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.column.typed_mut::<T>().get_mut(self.row)
+    }
+}
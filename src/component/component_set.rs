@@ -0,0 +1,36 @@
+use crate::component::Component;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A set of `Component` types, e.g. `(PlayerComponent, NpcComponent)`. Used to describe OR groups
+/// in `Query::any_of`.
+///
+/// Implemented for tuples of up to 8 `Component` types.
+pub trait ComponentSet: sealed::Sealed {
+    /// Records the ids of all `Component` types in the set.
+    #[doc(hidden)]
+    fn add_component_ids(component_ids: &mut Vec<u64>);
+}
+
+macro_rules! impl_component_set_for_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: 'static + Component),+> sealed::Sealed for ($($name,)+) {}
+
+        impl<$($name: 'static + Component),+> ComponentSet for ($($name,)+) {
+            fn add_component_ids(component_ids: &mut Vec<u64>) {
+                $(component_ids.push($name::property_id());)+
+            }
+        }
+    };
+}
+
+impl_component_set_for_tuple!(A);
+impl_component_set_for_tuple!(A, B);
+impl_component_set_for_tuple!(A, B, C);
+impl_component_set_for_tuple!(A, B, C, D);
+impl_component_set_for_tuple!(A, B, C, D, E);
+impl_component_set_for_tuple!(A, B, C, D, E, F);
+impl_component_set_for_tuple!(A, B, C, D, E, F, G);
+impl_component_set_for_tuple!(A, B, C, D, E, F, G, H);
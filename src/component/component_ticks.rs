@@ -0,0 +1,30 @@
+/// Records when a single `Component` was added to its Entity, and when it was last mutably
+/// accessed, in terms of the `EntityManager`'s change tick.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ComponentTicks {
+    added: u64,
+    changed: u64,
+}
+
+impl ComponentTicks {
+    pub fn new(tick: u64) -> Self {
+        ComponentTicks {
+            added: tick,
+            changed: tick,
+        }
+    }
+
+    /// Returns `true` if the `Component` was added after `last_run`.
+    pub fn is_added(&self, last_run: u64) -> bool {
+        self.added > last_run
+    }
+
+    /// Returns `true` if the `Component` was added or mutably accessed after `last_run`.
+    pub fn is_changed(&self, last_run: u64) -> bool {
+        self.changed > last_run
+    }
+
+    pub fn set_changed(&mut self, tick: u64) {
+        self.changed = tick;
+    }
+}
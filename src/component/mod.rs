@@ -1,8 +1,17 @@
+#[allow(clippy::module_inception)]
 mod component;
+mod component_access;
 mod component_bundle;
-mod component_manager;
+mod component_column;
+mod component_ref;
+mod component_set;
+mod component_ticks;
 mod test;
 
 pub use self::component::*;
+pub use self::component_access::*;
 pub use self::component_bundle::*;
-pub(crate) use self::component_manager::*;
+pub(crate) use self::component_column::*;
+pub use self::component_ref::*;
+pub use self::component_set::*;
+pub(crate) use self::component_ticks::*;
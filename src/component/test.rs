@@ -1,26 +1,37 @@
 #![cfg(test)]
 
-use std::sync::{Arc, RwLock};
+use std::marker::PhantomData;
 
 use crate::component::*;
 use crate::util::PropertyId;
+use crate::Error;
 
 #[derive(Component)]
 struct TestComponent;
 
+#[derive(Component, Debug, PartialEq)]
+struct ValueComponent {
+    value: u32,
+}
+
 #[test]
 fn component_bundle_success() {
-    let bundle = ComponentBundle::new().add_component(TestComponent);
+    let bundle = ComponentBundle::new().add_component(ValueComponent { value: 1 });
 
-    assert_eq!(bundle.get_components().len(), 1);
+    assert_eq!(
+        bundle.get_component::<ValueComponent>(),
+        Some(&ValueComponent { value: 1 })
+    );
+    assert!(bundle.get_component::<TestComponent>().is_none());
 
-    let component = bundle
-        .get_components()
-        .get(&TestComponent::property_id())
-        .unwrap();
+    let components = bundle.into_components();
+    assert_eq!(components.len(), 1);
     assert_eq!(
-        component.read().unwrap().self_property_id(),
-        TestComponent::property_id()
+        components
+            .get(&ValueComponent::property_id())
+            .unwrap()
+            .component_id(),
+        ValueComponent::property_id()
     );
 }
 
@@ -33,17 +44,106 @@ fn component_bundle_multiple_same_component_panics() {
 }
 
 #[test]
-fn component_manager_success() {
-    let mut manager: ComponentManager<TestComponent> = ComponentManager::new();
-    let entity_id = 0;
-    let component = Arc::new(RwLock::new(TestComponent));
-
-    manager.register_entity(&entity_id, component.clone());
-    assert!(Arc::ptr_eq(
-        &manager.get_component_for_entity(&entity_id).unwrap(),
-        &component
+fn component_bundle_try_add_component_fails() {
+    let result = ComponentBundle::new()
+        .try_add_component(TestComponent)
+        .unwrap()
+        .try_add_component(TestComponent);
+    assert!(matches!(
+        result,
+        Err(Error::DuplicateComponent(name)) if name == TestComponent::property_string()
     ));
+}
+
+#[test]
+fn component_column_move_row_success() {
+    let mut source: Box<dyn ComponentColumn> = Box::new(TypedColumn::<ValueComponent>::new());
+    source
+        .typed_mut::<ValueComponent>()
+        .push(ValueComponent { value: 1 }, ComponentTicks::default());
+    source
+        .typed_mut::<ValueComponent>()
+        .push(ValueComponent { value: 2 }, ComponentTicks::default());
+    let mut destination = source.new_empty();
+
+    source.move_row(0, destination.as_mut());
+    assert_eq!(
+        source.typed::<ValueComponent>().get(0),
+        &ValueComponent { value: 2 }
+    );
+    assert_eq!(
+        destination.typed::<ValueComponent>().get(0),
+        &ValueComponent { value: 1 }
+    );
+
+    source.swap_remove(0);
+    destination.typed_mut::<ValueComponent>().get_mut(0).value = 3;
+    assert_eq!(
+        destination.typed::<ValueComponent>().get(0),
+        &ValueComponent { value: 3 }
+    );
+}
+
+#[test]
+#[should_panic]
+fn component_column_wrong_type_panics() {
+    let column: Box<dyn ComponentColumn> = Box::new(TypedColumn::<ValueComponent>::new());
+    column.typed::<TestComponent>();
+}
+
+#[test]
+fn component_access_conflicts_success() {
+    let mut reader = ComponentAccess::new();
+    reader.add_read::<ValueComponent>();
+    let mut other_reader = ComponentAccess::new();
+    other_reader
+        .add_read::<ValueComponent>()
+        .add_write::<TestComponent>();
+    let mut writer = ComponentAccess::new();
+    writer.add_write::<ValueComponent>();
+
+    assert!(reader.is_compatible(&other_reader));
+    assert!(!reader.is_compatible(&writer));
+    assert!(!writer.is_compatible(&other_reader));
+    assert_eq!(
+        writer.get_conflicts(&other_reader),
+        vec![ValueComponent::property_string()]
+    );
+}
+
+struct Player;
 
-    manager.deregister_entity(&entity_id);
-    assert!(manager.get_component_for_entity(&entity_id).is_none());
+struct Enemy;
+
+#[derive(Component)]
+struct MarkedComponent<T: 'static + Send + Sync> {
+    value: u32,
+    marker: PhantomData<T>,
+}
+
+#[test]
+fn component_bundle_generic_component_success() {
+    let bundle = ComponentBundle::new()
+        .add_component(MarkedComponent::<Player> {
+            value: 1,
+            marker: PhantomData,
+        })
+        .add_component(MarkedComponent::<Enemy> {
+            value: 2,
+            marker: PhantomData,
+        });
+    assert_eq!(
+        bundle
+            .get_component::<MarkedComponent<Player>>()
+            .unwrap()
+            .value,
+        1
+    );
+    assert_eq!(
+        bundle
+            .get_component::<MarkedComponent<Enemy>>()
+            .unwrap()
+            .value,
+        2
+    );
 }
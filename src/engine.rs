@@ -1,8 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
+#[cfg(feature = "serde")]
+use crate::entity::Entity;
 use crate::entity::EntityManager;
-use crate::system::{SystemGroup, SystemManager, SystemParamAccessor};
+use crate::event::{Event, Events};
+use crate::resource::Resource;
+#[cfg(feature = "scene")]
+use crate::scene::Scene;
+#[cfg(feature = "serde")]
+use crate::snapshot::TypeRegistry;
+use crate::system::{SystemFunction, SystemGroup, SystemManager, SystemParamAccessor};
+use crate::time::{RunControl, Time};
+use crate::util::PropertyId;
+use crate::{Error, Result};
 
 /// Stores and provides access to user-defined `SystemGroup`s.
 ///
@@ -11,23 +23,61 @@ use crate::system::{SystemGroup, SystemManager, SystemParamAccessor};
 /// consider if there are dependencies between Systems that would encourage one to run before or
 /// after another.
 ///
-/// Note: When processing a `SystemGroup`, `System`s are invoked sequentially in the order they are
-/// registered.
-#[derive(Default)]
+/// Note: When processing a sequential `SystemGroup`, `System`s are invoked in the order they are
+/// registered (see `SystemGroup::register_before` / `register_after` to adjust this).
+///
+/// Alternatively, `SystemGroup`s may be scheduled via `schedule_group`, and driven by the Engine
+/// through `update` (or `run`, when headless).
 pub struct Engine {
     entity_manager: Arc<RwLock<EntityManager>>,
     system_manager: SystemManager,
     next_group_id: u32,
     system_groups: HashMap<u32, SystemGroup>,
+    event_update_fns: Vec<fn(&EntityManager)>,
+    fixed_groups: Vec<u32>,
+    variable_groups: Vec<u32>,
+    fixed_timestep: Duration,
+    max_fixed_steps: u32,
+    accumulator: Duration,
+    last_frame_tick: u64,
+    frame_group_ids: HashSet<u32>,
+}
+
+/// The rate at which a `SystemGroup` scheduled via `Engine::schedule_group` is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateRate {
+    /// Executed once per fixed timestep (see `Engine::set_fixed_timestep`), i.e. zero or more
+    /// times per `Engine::update`.
+    Fixed,
+    /// Executed exactly once per `Engine::update`.
+    Variable,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Engine::new()
+    }
 }
 
 impl Engine {
     pub fn new() -> Self {
+        let fixed_timestep = Duration::from_secs_f64(1.0 / 60.0);
+        let mut entity_manager = EntityManager::new();
+        entity_manager.insert_resource(Time::new(fixed_timestep));
+        entity_manager.insert_resource(RunControl::new());
         Engine {
-            entity_manager: Arc::new(RwLock::new(EntityManager::new())),
+            entity_manager: Arc::new(RwLock::new(entity_manager)),
             system_manager: SystemManager::new(),
             next_group_id: 0,
             system_groups: HashMap::new(),
+            event_update_fns: Vec::new(),
+            fixed_groups: Vec::new(),
+            variable_groups: Vec::new(),
+            fixed_timestep,
+            max_fixed_steps: 5,
+            accumulator: Duration::ZERO,
+            last_frame_tick: 0,
+            frame_group_ids: HashSet::new(),
         }
     }
 
@@ -40,25 +90,369 @@ impl Engine {
         group_id
     }
 
-    /// Deregistered a `SystemGroup` given its `id`.
+    /// Returns the `SystemGroup` registered under `group_id`, so that its `System`s may be
+    /// reordered or removed after registration.
+    pub fn get_system_group_mut(&mut self, group_id: u32) -> Option<&mut SystemGroup> {
+        self.system_groups.get_mut(&group_id)
+    }
+
+    /// Enables or disables the `SystemGroup` registered under `group_id` (see
+    /// `SystemGroup::set_enabled`). A disabled group is skipped by `execute_group` until enabled
+    /// again.
+    ///
+    /// Will panic if no `SystemGroup` is registered under `group_id`.
+    pub fn set_group_enabled(&mut self, group_id: u32, enabled: bool) {
+        self.try_set_group_enabled(group_id, enabled)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Enables or disables the `SystemGroup` registered under `group_id`, like
+    /// `set_group_enabled`. Fails with `Error::SystemGroupNotRegistered` if no `SystemGroup` is
+    /// registered under `group_id`.
+    pub fn try_set_group_enabled(&mut self, group_id: u32, enabled: bool) -> Result<()> {
+        self.system_groups
+            .get_mut(&group_id)
+            .ok_or(Error::SystemGroupNotRegistered(group_id))?
+            .set_enabled(enabled);
+        Ok(())
+    }
+
+    /// Enables or disables every `System` of type `T` in every registered `SystemGroup` (see
+    /// `SystemGroup::set_system_enabled`). Returns `true` if any `System` of type `T` is
+    /// registered.
+    pub fn set_system_enabled<T: 'static + PropertyId>(&mut self, enabled: bool) -> bool {
+        let mut found = false;
+        for group in self.system_groups.values_mut() {
+            found |= group.set_system_enabled::<T>(enabled);
+        }
+        found
+    }
+
+    /// Enables or disables the function `System` `system` in every registered `SystemGroup` (see
+    /// `SystemGroup::set_function_enabled`). Returns `true` if `system` is registered.
+    pub fn set_function_enabled<M, F: SystemFunction<M> + Copy>(
+        &mut self,
+        system: F,
+        enabled: bool,
+    ) -> bool {
+        let mut found = false;
+        for group in self.system_groups.values_mut() {
+            found |= group.set_function_enabled(system, enabled);
+        }
+        found
+    }
+
+    /// Deregistered a `SystemGroup` given its `id`, removing it from the update schedule.
     pub fn deregister_system_group(&mut self, group_id: u32) {
         self.system_groups.remove(&group_id);
+        self.unschedule_group(group_id);
+    }
+
+    /// Schedules the `SystemGroup` registered under `group_id` to be executed by `update` at the
+    /// provided `rate`. Scheduled groups of the same rate are executed in the order they were
+    /// scheduled. Scheduling an already scheduled group moves it to the end of the new rate.
+    ///
+    /// Will panic if no `SystemGroup` is registered under `group_id`.
+    pub fn schedule_group(&mut self, group_id: u32, rate: UpdateRate) {
+        self.try_schedule_group(group_id, rate)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Schedules a `SystemGroup` like `schedule_group`. Fails with
+    /// `Error::SystemGroupNotRegistered` if no `SystemGroup` is registered under `group_id`.
+    pub fn try_schedule_group(&mut self, group_id: u32, rate: UpdateRate) -> Result<()> {
+        if !self.system_groups.contains_key(&group_id) {
+            return Err(Error::SystemGroupNotRegistered(group_id));
+        }
+        self.unschedule_group(group_id);
+        match rate {
+            UpdateRate::Fixed => self.fixed_groups.push(group_id),
+            UpdateRate::Variable => self.variable_groups.push(group_id),
+        }
+        Ok(())
+    }
+
+    /// Removes the `SystemGroup` registered under `group_id` from the update schedule. The group
+    /// remains registered, and may still be executed via `execute_group`.
+    pub fn unschedule_group(&mut self, group_id: u32) {
+        self.fixed_groups.retain(|id| *id != group_id);
+        self.variable_groups.retain(|id| *id != group_id);
+    }
+
+    /// Sets the duration of a single fixed step (60 Hz by default).
+    ///
+    /// Will panic if `timestep` is zero.
+    pub fn set_fixed_timestep(&mut self, timestep: Duration) {
+        if timestep.is_zero() {
+            panic!("Fixed timestep must be greater than zero!");
+        }
+        self.fixed_timestep = timestep;
+        if let Some(time) = self.get_resource::<Time>() {
+            time.write().unwrap().set_fixed_delta(timestep);
+        }
+    }
+
+    /// Sets the maximum number of fixed steps executed during a single `update` (5 by default).
+    /// Time that would require more steps to catch up on is dropped, so that a slow frame cannot
+    /// cause ever slower frames.
+    pub fn set_max_fixed_steps(&mut self, max_fixed_steps: u32) {
+        self.max_fixed_steps = max_fixed_steps;
     }
 
     /// Executes a `SystemGroup` registered under the provided `group_id`, passing the `SystemParams`
-    /// registered in the `SystemParamAccessor`.
+    /// registered in the `SystemParamAccessor`. Afterwards, all `Commands` recorded by the group's
+    /// `System`s are applied.
+    ///
+    /// Executing a group that already executed since the last call to `finish_frame` finishes the
+    /// frame first, so that a loop executing each group once per frame needn't call
+    /// `finish_frame` itself (though it may, e.g. to finish the frame right after rendering).
+    ///
+    /// Does nothing if the group is disabled, or one of its run conditions is not met (see
+    /// `SystemGroup::run_group_if`).
+    ///
+    /// Will panic if no `SystemGroup` is registered under `group_id`, or if applying a command
+    /// fails.
     pub fn execute_group(&mut self, group_id: u32, params: SystemParamAccessor) {
-        match self.system_groups.get(&group_id) {
-            None => panic!("SystemGroup with id: {} not registered!", group_id),
+        self.try_execute_group(group_id, params)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Executes a `SystemGroup` like `execute_group`. Fails with `Error::SystemGroupNotRegistered`
+    /// if no `SystemGroup` is registered under `group_id`, or with the first error encountered
+    /// while applying `Commands` (see `EntityManager::try_flush_commands`).
+    pub fn try_execute_group(&mut self, group_id: u32, params: SystemParamAccessor) -> Result<()> {
+        if !self.system_groups.contains_key(&group_id) {
+            return Err(Error::SystemGroupNotRegistered(group_id));
+        }
+        if self.frame_group_ids.contains(&group_id) {
+            self.finish_frame();
+        }
+        self.frame_group_ids.insert(group_id);
+        self.execute_group_with(group_id, Arc::new(params))
+    }
+
+    /// Advances the `Time` `Resource` by `delta` and executes all scheduled `SystemGroup`s (see
+    /// `schedule_group`): first every `UpdateRate::Fixed` group once per fixed timestep that has
+    /// accumulated, up to the maximum number of fixed steps, then every `UpdateRate::Variable`
+    /// group exactly once. The `SystemParams` registered in the `SystemParamAccessor` are passed
+    /// to every executed group.
+    ///
+    /// Hosts that render should call this once per frame with the frame's duration. Finishes the
+    /// frame afterwards (see `finish_frame`).
+    ///
+    /// Will panic if applying a command fails.
+    pub fn update(&mut self, delta: Duration, params: SystemParamAccessor) {
+        self.try_update(delta, params)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Executes all scheduled `SystemGroup`s like `update`. Fails with the first error
+    /// encountered while executing a group (see `try_execute_group`), skipping all remaining
+    /// groups of this update. The frame is finished either way.
+    pub fn try_update(&mut self, delta: Duration, params: SystemParamAccessor) -> Result<()> {
+        let result = self.execute_scheduled_groups(delta, Arc::new(params));
+        self.finish_frame();
+        result
+    }
+
+    /// Finishes the current frame: `Event`s sent and removed `Component`s and deleted Entities
+    /// logged before the previous frame finished are forgotten, so that every `System` executing
+    /// once per frame has the chance to observe them (see `Events` and
+    /// `EntityManager::get_removed_components`).
+    ///
+    /// Called by `update`, and by `execute_group` once a group executes a second time within the
+    /// same frame.
+    pub fn finish_frame(&mut self) {
+        self.frame_group_ids.clear();
+        let mut entity_manager = self.entity_manager.write().unwrap();
+        entity_manager.clear_removal_log(self.last_frame_tick);
+        self.event_update_fns
+            .iter()
+            .for_each(|update| update(&entity_manager));
+        // Removals outside of a `System`, e.g. by `Commands`, are logged one tick ahead.
+        self.last_frame_tick = entity_manager.get_change_tick() + 1;
+    }
+
+    /// Runs a headless loop, e.g. for servers: calls `update` with the real time passed since the
+    /// previous update, until a `System` requests an exit via the `RunControl` `Resource`. If a
+    /// `frame_time` is provided, sleeps between updates so that at most one update is executed
+    /// per `frame_time`; otherwise updates as fast as possible.
+    ///
+    /// Will panic if applying a command fails.
+    pub fn run(&mut self, frame_time: Option<Duration>) {
+        self.try_run(frame_time)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Runs a headless loop like `run`. Fails with the first error encountered during an update
+    /// (see `try_update`).
+    pub fn try_run(&mut self, frame_time: Option<Duration>) -> Result<()> {
+        let run_control = {
+            let mut entity_manager = self.entity_manager.write().unwrap();
+            if !entity_manager.has_resource::<RunControl>() {
+                entity_manager.insert_resource(RunControl::new());
+            }
+            entity_manager.get_resource::<RunControl>().unwrap()
+        };
+        run_control.write().unwrap().clear();
+
+        let mut last_update = Instant::now();
+        loop {
+            let frame_start = Instant::now();
+            self.try_update(frame_start - last_update, SystemParamAccessor::new())?;
+            last_update = frame_start;
+            if run_control.read().unwrap().is_exit_requested() {
+                return Ok(());
+            }
+            if let Some(frame_time) = frame_time {
+                if let Some(remaining) = frame_time.checked_sub(frame_start.elapsed()) {
+                    std::thread::sleep(remaining);
+                }
+            }
+        }
+    }
+
+    fn execute_scheduled_groups(
+        &mut self,
+        delta: Duration,
+        params: Arc<SystemParamAccessor>,
+    ) -> Result<()> {
+        let time = self.entity_manager.read().unwrap().get_resource::<Time>();
+        if let Some(time) = &time {
+            time.write().unwrap().advance(delta);
+        }
+
+        self.accumulator += delta;
+        let mut steps = 0;
+        while self.accumulator >= self.fixed_timestep && steps < self.max_fixed_steps {
+            self.accumulator -= self.fixed_timestep;
+            steps += 1;
+            if let Some(time) = &time {
+                time.write().unwrap().advance_fixed();
+            }
+            for group_id in self.fixed_groups.clone() {
+                self.execute_group_with(group_id, params.clone())?;
+            }
+        }
+        if self.accumulator >= self.fixed_timestep {
+            self.accumulator = Duration::from_nanos(
+                (self.accumulator.as_nanos() % self.fixed_timestep.as_nanos()) as u64,
+            );
+        }
+
+        if let Some(time) = &time {
+            let alpha = self.accumulator.as_secs_f32() / self.fixed_timestep.as_secs_f32();
+            time.write().unwrap().set_alpha(alpha);
+        }
+        for group_id in self.variable_groups.clone() {
+            self.execute_group_with(group_id, params.clone())?;
+        }
+        Ok(())
+    }
+
+    fn execute_group_with(
+        &mut self,
+        group_id: u32,
+        params: Arc<SystemParamAccessor>,
+    ) -> Result<()> {
+        let executed = match self.system_groups.get(&group_id) {
+            None => return Err(Error::SystemGroupNotRegistered(group_id)),
             Some(group) => {
                 self.system_manager
-                    .execute_group(group, self.entity_manager.clone(), Arc::new(params))
+                    .execute_group(group, self.entity_manager.clone(), params)
             }
+        };
+        if !executed {
+            return Ok(());
+        }
+        self.entity_manager.write().unwrap().try_flush_commands()
+    }
+
+    /// Registers `Event`s of type `T`, inserting an empty `Events<T>` `Resource` that is updated
+    /// once per frame (see `finish_frame`). Does nothing if `T` is already registered.
+    pub fn add_event<T: Event>(&mut self) {
+        let mut entity_manager = self.entity_manager.write().unwrap();
+        if entity_manager.has_resource::<Events<T>>() {
+            return;
         }
+        entity_manager.insert_resource(Events::<T>::new());
+        self.event_update_fns.push(|entity_manager| {
+            if let Some(events) = entity_manager.get_resource::<Events<T>>() {
+                events.write().unwrap().update();
+            }
+        });
+    }
+
+    /// Inserts a `Resource`, replacing any existing `Resource` of the same type. `Resource`s
+    /// persist between `SystemGroup` executions, and are accessible from `System`s via
+    /// `EntityManager::get_resource`.
+    pub fn insert_resource<T: Resource>(&mut self, resource: T) {
+        self.entity_manager
+            .write()
+            .unwrap()
+            .insert_resource(resource);
+    }
+
+    /// Removes the `Resource` of type `T`, returning it if it existed.
+    pub fn remove_resource<T: Resource>(&mut self) -> Option<Arc<RwLock<T>>> {
+        self.entity_manager.write().unwrap().remove_resource::<T>()
+    }
+
+    /// Retrieves access to the `Resource` of type `T`. Returns `None` if it does not exist.
+    pub fn get_resource<T: Resource>(&self) -> Option<Arc<RwLock<T>>> {
+        self.entity_manager.read().unwrap().get_resource::<T>()
+    }
+
+    /// Serializes all Entities and `Resource`s, as described by `EntityManager::save`.
+    #[cfg(feature = "serde")]
+    pub fn save<S: serde::Serializer>(
+        &self,
+        registry: &TypeRegistry,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.entity_manager
+            .read()
+            .unwrap()
+            .save(registry, serializer)
+    }
+
+    /// Loads a snapshot created via `save`, as described by `EntityManager::load`.
+    #[cfg(feature = "serde")]
+    pub fn load<'de, D: serde::Deserializer<'de>>(
+        &mut self,
+        registry: &TypeRegistry,
+        deserializer: D,
+    ) -> std::result::Result<Vec<Entity>, D::Error> {
+        self.entity_manager
+            .write()
+            .unwrap()
+            .load(registry, deserializer)
+    }
+
+    /// Spawns all Entities of `scene`, as described by `EntityManager::spawn_scene`.
+    #[cfg(feature = "scene")]
+    pub fn spawn_scene(&mut self, scene: &Scene, registry: &TypeRegistry) -> Vec<Entity> {
+        self.entity_manager
+            .write()
+            .unwrap()
+            .spawn_scene(scene, registry)
+    }
+
+    /// Spawns all Entities of `scene`, as described by `EntityManager::try_spawn_scene`.
+    #[cfg(feature = "scene")]
+    pub fn try_spawn_scene(
+        &mut self,
+        scene: &Scene,
+        registry: &TypeRegistry,
+    ) -> Result<Vec<Entity>> {
+        self.entity_manager
+            .write()
+            .unwrap()
+            .try_spawn_scene(scene, registry)
     }
 
     /// Resets all data held by the Engine, including registered SystemGroups, the EntityManager,
-    /// etc.
+    /// Resources, etc.
     pub fn reset(&mut self) {
         *self = Engine::new();
     }
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::component::ComponentColumn;
+use crate::entity::{Entity, Query, QueryData};
+
+/// An `Archetype` stores every Entity that has exactly the same set of `Component` types. Each
+/// `Component` type is kept in its own contiguous column, and an Entity's `Component`s all live at
+/// the same row across columns.
+///
+/// Columns are individually locked, so that `Component`s of different types may be read from and
+/// written to concurrently while the owning `EntityManager` is only borrowed immutably.
+pub(crate) struct Archetype {
+    component_ids: Vec<u64>,
+    entities: Vec<Entity>,
+    columns: HashMap<u64, RwLock<Box<dyn ComponentColumn>>>,
+}
+
+impl Archetype {
+    /// Creates a new `Archetype` from a set of empty columns.
+    pub fn new(columns: Vec<Box<dyn ComponentColumn>>) -> Self {
+        let mut component_ids: Vec<u64> =
+            columns.iter().map(|column| column.component_id()).collect();
+        component_ids.sort_unstable();
+        Archetype {
+            component_ids,
+            entities: Vec::new(),
+            columns: columns
+                .into_iter()
+                .map(|column| (column.component_id(), RwLock::new(column)))
+                .collect(),
+        }
+    }
+
+    /// The (sorted) ids of all `Component` types stored in this `Archetype`.
+    pub fn get_component_ids(&self) -> &[u64] {
+        &self.component_ids
+    }
+
+    pub fn get_entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub fn contains_component(&self, component_id: &u64) -> bool {
+        self.component_ids.binary_search(component_id).is_ok()
+    }
+
+    pub fn matches<D: QueryData>(&self, query: &Query<D>) -> bool {
+        query.matches(&self.component_ids)
+    }
+
+    pub fn get_column(&self, component_id: &u64) -> Option<&RwLock<Box<dyn ComponentColumn>>> {
+        self.columns.get(component_id)
+    }
+
+    pub fn get_column_mut(&mut self, component_id: &u64) -> Option<&mut Box<dyn ComponentColumn>> {
+        self.columns
+            .get_mut(component_id)
+            .map(|column| column.get_mut().unwrap())
+    }
+
+    pub fn get_columns_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (&u64, &mut Box<dyn ComponentColumn>)> {
+        self.columns
+            .iter_mut()
+            .map(|(component_id, column)| (component_id, column.get_mut().unwrap()))
+    }
+
+    /// Creates a set of empty columns matching the ones stored in this `Archetype`.
+    pub fn new_empty_columns(&self) -> Vec<Box<dyn ComponentColumn>> {
+        self.columns
+            .values()
+            .map(|column| column.read().unwrap().new_empty())
+            .collect()
+    }
+
+    /// Adds an Entity to the end of this `Archetype`, returning its row. The caller is responsible
+    /// for pushing its `Component`s onto every column.
+    pub fn push_entity(&mut self, entity: Entity) -> usize {
+        self.entities.push(entity);
+        self.entities.len() - 1
+    }
+
+    /// Removes the Entity at `row`, replacing it with the last Entity. Returns the Entity that was
+    /// moved into `row`, if any. The caller is responsible for removing the corresponding
+    /// `Component`s from every column.
+    pub fn swap_remove_entity(&mut self, row: usize) -> Option<Entity> {
+        self.entities.swap_remove(row);
+        self.entities.get(row).copied()
+    }
+}
@@ -0,0 +1,145 @@
+use crate::component::{Component, ComponentBundle};
+use crate::entity::{Entity, EntityManager};
+use crate::Result;
+
+type RemoveComponentFn = fn(&mut EntityManager, &Entity) -> Result<()>;
+
+/// A single deferred structural change, recorded by `Commands`.
+pub(crate) enum Command {
+    Create(Entity, ComponentBundle),
+    Delete(Entity),
+    AddComponents(Entity, ComponentBundle),
+    RemoveComponent(Entity, RemoveComponentFn),
+    SetParent(Entity, Entity),
+    RemoveParent(Entity),
+    DeleteRecursive(Entity),
+}
+
+impl Command {
+    /// Applies this command. Commands targeting an Entity that has since been deleted are ignored.
+    pub fn apply(self, entity_manager: &mut EntityManager) -> Result<()> {
+        match self {
+            Command::Create(entity_id, components)
+            | Command::AddComponents(entity_id, components) => {
+                if !entity_manager.is_alive(&entity_id) {
+                    return Ok(());
+                }
+                entity_manager.try_add_components_to_entity(&entity_id, components)
+            }
+            Command::Delete(entity_id) => {
+                if !entity_manager.is_alive(&entity_id) {
+                    return Ok(());
+                }
+                entity_manager.try_delete_entity(&entity_id)
+            }
+            Command::RemoveComponent(entity_id, remove_component) => {
+                if !entity_manager.is_alive(&entity_id) {
+                    return Ok(());
+                }
+                remove_component(entity_manager, &entity_id)
+            }
+            Command::SetParent(child, parent) => {
+                if !entity_manager.is_alive(&child) || !entity_manager.is_alive(&parent) {
+                    return Ok(());
+                }
+                entity_manager.try_set_parent(&child, &parent)
+            }
+            Command::RemoveParent(entity_id) => {
+                if !entity_manager.is_alive(&entity_id) {
+                    return Ok(());
+                }
+                entity_manager.try_remove_parent(&entity_id)
+            }
+            Command::DeleteRecursive(entity_id) => {
+                if !entity_manager.is_alive(&entity_id) {
+                    return Ok(());
+                }
+                entity_manager.try_delete_entity_recursive(&entity_id)
+            }
+        }
+    }
+}
+
+/// Records structural changes (creating / deleting Entities, adding / removing `Component`s) to be
+/// applied later, at a well-defined sync point. Obtained via `EntityManager::commands`, which only
+/// requires shared access to the `EntityManager`, so changes can be recorded from within a `System`
+/// holding `entity_manager.read()`, or even while iterating over a `Query`.
+///
+/// Recorded commands are submitted to the `EntityManager` when the `Commands` is dropped, and
+/// applied in order by `EntityManager::flush_commands`. The `Engine` does so after every executed
+/// `SystemGroup`; `FlushCommandsSystem` may be registered to do so between `System`s of a group.
+///
+/// Note: commands targeting an Entity that was deleted in the meantime are ignored.
+pub struct Commands<'a> {
+    entity_manager: &'a EntityManager,
+    commands: Vec<Command>,
+}
+
+impl<'a> Commands<'a> {
+    pub(crate) fn new(entity_manager: &'a EntityManager) -> Self {
+        Commands {
+            entity_manager,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Records the creation of a new Entity with the provided `Component`s. The returned `Entity`
+    /// handle may be used in further commands right away, but only refers to a live Entity once
+    /// the commands have been applied.
+    ///
+    /// Will panic when applied if registering multiple `Component`s of the same type is attempted.
+    pub fn create_entity(&mut self, components: ComponentBundle) -> Entity {
+        let entity_id = self.entity_manager.reserve_entity();
+        self.commands.push(Command::Create(entity_id, components));
+        entity_id
+    }
+
+    /// Records the deletion of an Entity.
+    pub fn delete_entity(&mut self, entity_id: &Entity) {
+        self.commands.push(Command::Delete(*entity_id));
+    }
+
+    /// Records adding the provided `Component`s to an Entity.
+    ///
+    /// Will panic when applied if the Entity already has a `Component` of one of the provided
+    /// types, unless applied via `EntityManager::try_flush_commands`.
+    pub fn add_components_to_entity(&mut self, entity_id: &Entity, components: ComponentBundle) {
+        self.commands
+            .push(Command::AddComponents(*entity_id, components));
+    }
+
+    /// Records removing the `Component` of type `T` from an Entity.
+    pub fn remove_component_from_entity<T: 'static + Component>(&mut self, entity_id: &Entity) {
+        self.commands.push(Command::RemoveComponent(
+            *entity_id,
+            EntityManager::try_remove_component_from_entity::<T>,
+        ));
+    }
+
+    /// Records making `parent` the parent of `child`.
+    ///
+    /// Will panic when applied if `parent` is `child` or one of its descendants, unless applied via
+    /// `EntityManager::try_flush_commands`.
+    pub fn set_parent(&mut self, child: &Entity, parent: &Entity) {
+        self.commands.push(Command::SetParent(*child, *parent));
+    }
+
+    /// Records detaching an Entity from its parent.
+    pub fn remove_parent(&mut self, child: &Entity) {
+        self.commands.push(Command::RemoveParent(*child));
+    }
+
+    /// Records the deletion of an Entity alongside all of its descendants.
+    pub fn delete_entity_recursive(&mut self, entity_id: &Entity) {
+        self.commands.push(Command::DeleteRecursive(*entity_id));
+    }
+}
+
+impl Drop for Commands<'_> {
+    fn drop(&mut self) {
+        if !self.commands.is_empty() {
+            self.entity_manager
+                .submit_commands(std::mem::take(&mut self.commands));
+        }
+    }
+}
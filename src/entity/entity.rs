@@ -0,0 +1,59 @@
+use std::fmt;
+
+/// A handle to an Entity registered in an `EntityManager`.
+///
+/// An `Entity` is made up of an `index`, which may be recycled once the Entity it refers to is
+/// deleted, and a `generation`, which is bumped every time that happens. This means a handle to a
+/// deleted Entity will never alias a newer Entity that happens to reuse the same index; the
+/// `EntityManager` simply rejects it as stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    /// A handle that never refers to a live Entity, e.g. to stand in for a stale handle.
+    pub const DANGLING: Entity = Entity {
+        index: u32::MAX,
+        generation: u32::MAX,
+    };
+
+    pub(crate) fn new(index: u32, generation: u32) -> Self {
+        Entity { index, generation }
+    }
+
+    /// The slot this Entity occupies. Not unique over time, see `generation`.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The number of times the slot at `index` has been recycled.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+/// Serialized as a single `u64`. While a snapshot is being loaded (see `EntityManager::load`),
+/// deserialized handles are mapped to the Entities created for them.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Entity {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64((self.generation as u64) << 32 | self.index as u64)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Entity {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = <u64 as serde::Deserialize>::deserialize(deserializer)?;
+        let entity_id = Entity::new(bits as u32, (bits >> 32) as u32);
+        Ok(crate::snapshot::EntityMap::map(entity_id))
+    }
+}
@@ -0,0 +1,129 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use crate::entity::Entity;
+
+struct EntitySlot {
+    generation: u32,
+    alive: bool,
+}
+
+/// Hands out `Entity` handles, recycling the indices of deleted Entities.
+///
+/// Handles may also be reserved through a shared reference, e.g. while recording `Commands`.
+/// Reserved handles claim freed indices first, via an atomic cursor into `free_indices`, and only
+/// fall back to fresh indices once the free list is exhausted. They become live once
+/// `flush_reserved` is called.
+#[derive(Default)]
+pub(crate) struct EntityAllocator {
+    slots: Vec<EntitySlot>,
+    free_indices: Vec<u32>,
+    /// The number of `free_indices` not yet reserved. Entries at and past the cursor are
+    /// reserved; a negative cursor counts the fresh indices reserved beyond the free list.
+    free_cursor: AtomicI64,
+}
+
+impl EntityAllocator {
+    pub fn new() -> Self {
+        EntityAllocator {
+            slots: Vec::new(),
+            free_indices: Vec::new(),
+            free_cursor: AtomicI64::new(0),
+        }
+    }
+
+    /// Returns a new, live `Entity`. Previously freed indices are reused before new ones are
+    /// created.
+    ///
+    /// Note: all reserved `Entity`s must be flushed beforehand, so that their indices aren't
+    /// handed out twice.
+    pub fn allocate(&mut self) -> Entity {
+        debug_assert!(!self.has_reserved());
+        let entity = match self.free_indices.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.alive = true;
+                Entity::new(index, slot.generation)
+            }
+            None => {
+                let index = self.slots.len() as u32;
+                self.slots.push(EntitySlot {
+                    generation: 0,
+                    alive: true,
+                });
+                Entity::new(index, 0)
+            }
+        };
+        self.reset_free_cursor();
+        entity
+    }
+
+    /// Returns a new `Entity` that will become live on the next call to `flush_reserved`. Like
+    /// `allocate`, previously freed indices are reused before new ones are created.
+    pub fn reserve(&self) -> Entity {
+        let cursor = self.free_cursor.fetch_sub(1, Ordering::Relaxed);
+        if cursor > 0 {
+            let index = self.free_indices[cursor as usize - 1];
+            Entity::new(index, self.slots[index as usize].generation)
+        } else {
+            Entity::new(self.slots.len() as u32 + (-cursor) as u32, 0)
+        }
+    }
+
+    /// Makes all reserved `Entity`s live, returning them in order of reservation.
+    pub fn flush_reserved(&mut self) -> Vec<Entity> {
+        let cursor = *self.free_cursor.get_mut();
+        let reused_start = cursor.max(0) as usize;
+        let mut reserved: Vec<Entity> = self
+            .free_indices
+            .drain(reused_start..)
+            .rev()
+            .map(|index| {
+                let slot = &mut self.slots[index as usize];
+                slot.alive = true;
+                Entity::new(index, slot.generation)
+            })
+            .collect();
+        let start = self.slots.len() as u32;
+        (start..start + (-cursor).max(0) as u32).for_each(|index| {
+            self.slots.push(EntitySlot {
+                generation: 0,
+                alive: true,
+            });
+            reserved.push(Entity::new(index, 0));
+        });
+        self.reset_free_cursor();
+        reserved
+    }
+
+    /// Frees the provided `Entity`, invalidating all outstanding handles to it. Returns `false` if
+    /// the `Entity` was already stale.
+    ///
+    /// Note: all reserved `Entity`s must be flushed beforehand, so that the freed index isn't
+    /// mistaken for a reserved one.
+    pub fn deallocate(&mut self, entity: &Entity) -> bool {
+        debug_assert!(!self.has_reserved());
+        if !self.is_alive(entity) {
+            return false;
+        }
+        let slot = &mut self.slots[entity.index() as usize];
+        slot.alive = false;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free_indices.push(entity.index());
+        self.reset_free_cursor();
+        true
+    }
+
+    pub fn is_alive(&self, entity: &Entity) -> bool {
+        self.slots
+            .get(entity.index() as usize)
+            .is_some_and(|slot| slot.alive && slot.generation == entity.generation())
+    }
+
+    fn has_reserved(&mut self) -> bool {
+        *self.free_cursor.get_mut() != self.free_indices.len() as i64
+    }
+
+    fn reset_free_cursor(&mut self) {
+        *self.free_cursor.get_mut() = self.free_indices.len() as i64;
+    }
+}
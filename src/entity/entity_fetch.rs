@@ -0,0 +1,136 @@
+use crate::component::{Component, ComponentAccess, ComponentMut, ComponentRef};
+use crate::entity::{add_access_once, ArchetypeLocks};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Describes which `Component`s `EntityManager::get_many` fetches for a single Entity.
+///
+/// Implemented for:
+/// *   `&T`: a `ComponentRef` to a required `Component`.
+/// *   `&mut T`: a `ComponentMut` to a required `Component`.
+/// *   `Option<&T>` / `Option<&mut T>`: either of the above if the Entity has the `Component`,
+///     without requiring it.
+/// *   Tuples of up to 8 of the above.
+pub trait EntityFetch: sealed::Sealed {
+    /// The value returned for the Entity.
+    type Item<'w>;
+
+    /// Records the `Component`s accessed. Will panic if the same `Component` type is accessed more
+    /// than once, as that would deadlock.
+    #[doc(hidden)]
+    fn add_access(access: &mut ComponentAccess);
+
+    /// Records the `Component`s the Entity must have to be fetched.
+    #[doc(hidden)]
+    fn add_required_components(component_ids: &mut Vec<u64>);
+
+    #[doc(hidden)]
+    fn fetch<'w>(locks: &mut ArchetypeLocks<'w>, row: usize) -> Self::Item<'w>;
+}
+
+impl<T: 'static + Component> sealed::Sealed for &T {}
+
+impl<T: 'static + Component> EntityFetch for &T {
+    type Item<'w> = ComponentRef<'w, T>;
+
+    fn add_access(access: &mut ComponentAccess) {
+        add_access_once(access, T::property_id(), T::property_string());
+        access.add_read::<T>();
+    }
+
+    fn add_required_components(component_ids: &mut Vec<u64>) {
+        component_ids.push(T::property_id());
+    }
+
+    fn fetch<'w>(locks: &mut ArchetypeLocks<'w>, row: usize) -> Self::Item<'w> {
+        <Option<&T>>::fetch(locks, row).unwrap()
+    }
+}
+
+impl<T: 'static + Component> sealed::Sealed for &mut T {}
+
+impl<T: 'static + Component> EntityFetch for &mut T {
+    type Item<'w> = ComponentMut<'w, T>;
+
+    fn add_access(access: &mut ComponentAccess) {
+        add_access_once(access, T::property_id(), T::property_string());
+        access.add_write::<T>();
+    }
+
+    fn add_required_components(component_ids: &mut Vec<u64>) {
+        component_ids.push(T::property_id());
+    }
+
+    fn fetch<'w>(locks: &mut ArchetypeLocks<'w>, row: usize) -> Self::Item<'w> {
+        <Option<&mut T>>::fetch(locks, row).unwrap()
+    }
+}
+
+impl<T: 'static + Component> sealed::Sealed for Option<&T> {}
+
+impl<T: 'static + Component> EntityFetch for Option<&T> {
+    type Item<'w> = Option<ComponentRef<'w, T>>;
+
+    fn add_access(access: &mut ComponentAccess) {
+        <&T>::add_access(access);
+    }
+
+    fn add_required_components(_component_ids: &mut Vec<u64>) {}
+
+    fn fetch<'w>(locks: &mut ArchetypeLocks<'w>, row: usize) -> Self::Item<'w> {
+        locks
+            .take_read(&T::property_id())
+            .map(|guard| ComponentRef::new(guard.0, row))
+    }
+}
+
+impl<T: 'static + Component> sealed::Sealed for Option<&mut T> {}
+
+impl<T: 'static + Component> EntityFetch for Option<&mut T> {
+    type Item<'w> = Option<ComponentMut<'w, T>>;
+
+    fn add_access(access: &mut ComponentAccess) {
+        <&mut T>::add_access(access);
+    }
+
+    fn add_required_components(_component_ids: &mut Vec<u64>) {}
+
+    fn fetch<'w>(locks: &mut ArchetypeLocks<'w>, row: usize) -> Self::Item<'w> {
+        locks
+            .take_write(&T::property_id())
+            .map(|guard| ComponentMut::new(guard.0, row, guard.1))
+    }
+}
+
+macro_rules! impl_entity_fetch_for_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: EntityFetch),+> sealed::Sealed for ($($name,)+) {}
+
+        impl<$($name: EntityFetch),+> EntityFetch for ($($name,)+) {
+            type Item<'w> = ($($name::Item<'w>,)+);
+
+            fn add_access(access: &mut ComponentAccess) {
+                $($name::add_access(access);)+
+            }
+
+            fn add_required_components(component_ids: &mut Vec<u64>) {
+                $($name::add_required_components(component_ids);)+
+            }
+
+            fn fetch<'w>(locks: &mut ArchetypeLocks<'w>, row: usize) -> Self::Item<'w> {
+                ($($name::fetch(locks, row),)+)
+            }
+        }
+    };
+}
+
+impl_entity_fetch_for_tuple!(A);
+impl_entity_fetch_for_tuple!(A, B);
+impl_entity_fetch_for_tuple!(A, B, C);
+impl_entity_fetch_for_tuple!(A, B, C, D);
+impl_entity_fetch_for_tuple!(A, B, C, D, E);
+impl_entity_fetch_for_tuple!(A, B, C, D, E, F);
+impl_entity_fetch_for_tuple!(A, B, C, D, E, F, G);
+impl_entity_fetch_for_tuple!(A, B, C, D, E, F, G, H);
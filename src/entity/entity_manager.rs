@@ -1,188 +1,695 @@
 use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
-use std::sync::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 
-use crate::component::{Component, ComponentBundle, ComponentManager};
-use crate::entity::{Query, QueryCache, QueryResult};
+use crate::component::{
+    Component, ComponentAccess, ComponentBundle, ComponentColumn, ComponentMut, ComponentRef,
+    ComponentTicks,
+};
+use crate::entity::{
+    Archetype, ArchetypeLocks, Command, Commands, Entity, EntityAllocator, EntityFetch, FilterIter,
+    Query, QueryBorrow, QueryCache, QueryCacheStats, QueryData, QueryResult,
+};
+use crate::event::{Event, Events};
+use crate::resource::{Resource, ResourceMap};
+use crate::system::SystemContext;
+use crate::{Error, Result};
+
+/// Where an Entity's `Component`s are stored: a row in one of the `EntityManager`'s archetypes.
+#[derive(Debug, Default, Clone, Copy)]
+struct EntityLocation {
+    archetype_id: usize,
+    row: usize,
+}
 
 /// Drives all actions on Entities (as collections of `Component`s).
+///
+/// Entities are grouped into archetypes by their exact set of `Component` types. Within an
+/// archetype, each `Component` type is stored contiguously, so iterating over many Entities with
+/// the same shape touches tightly packed memory rather than chasing a pointer per `Component`.
+/// Adding or removing `Component`s moves an Entity from one archetype to another.
+///
+/// Every `Component` records the change tick at which it was added and last mutably accessed,
+/// which is what `Query::added` / `Query::changed` filter on. The change tick is advanced each time
+/// a `System` executes. Removed `Component`s and deleted Entities are logged, and are reported by
+/// `get_removed_components` / `get_deleted_entities`.
 #[derive(Default)]
 pub struct EntityManager {
-    next_entity_id: u32,
-    entity_id_to_component_ids: HashMap<u32, HashSet<u64>>,
-    component_id_to_component_managers: HashMap<u64, Box<ComponentManager<dyn Component>>>,
+    entity_allocator: EntityAllocator,
+    entity_locations: Vec<EntityLocation>,
+    archetypes: Vec<Archetype>,
+    component_ids_to_archetype_id: HashMap<Vec<u64>, usize>,
     query_cache: RwLock<QueryCache>,
+    command_queue: Mutex<Vec<Command>>,
+    resources: ResourceMap,
+    change_tick: AtomicU64,
+    removed_components: Vec<(u64, Entity, u64)>,
+    deleted_entities: Vec<(u64, Entity)>,
 }
 
 impl EntityManager {
     pub fn new() -> Self {
         EntityManager {
-            next_entity_id: 0,
-            entity_id_to_component_ids: HashMap::new(),
-            component_id_to_component_managers: HashMap::new(),
+            entity_allocator: EntityAllocator::new(),
+            entity_locations: Vec::new(),
+            archetypes: Vec::new(),
+            component_ids_to_archetype_id: HashMap::new(),
             query_cache: RwLock::new(QueryCache::new()),
+            command_queue: Mutex::new(Vec::new()),
+            resources: ResourceMap::new(),
+            change_tick: AtomicU64::new(0),
+            removed_components: Vec::new(),
+            deleted_entities: Vec::new(),
         }
     }
 
     /// Creates a new Entity, and registers all of the provided `Component`s under that Entity.
-    /// Returns a `u32` representing that Entity's id.
+    /// Returns an `Entity` handle identifying the new Entity. Indices of deleted Entities are
+    /// recycled, but the returned handle is never equal to one that was handed out before.
     ///
     /// Will panic if registering multiple `Component`s of the same type is attempted.
-    pub fn create_entity(&mut self, components: ComponentBundle) -> u32 {
-        let entity_id = self.next_entity_id;
-        self.next_entity_id += 1;
-        self.entity_id_to_component_ids
-            .insert(entity_id, HashSet::new());
+    pub fn create_entity(&mut self, components: ComponentBundle) -> Entity {
+        self.flush_reserved_entities();
+        let entity_id = self.entity_allocator.allocate();
+        self.insert_empty_entity(&entity_id);
         self.add_components_to_entity(&entity_id, components);
         entity_id
     }
 
+    /// Returns `true` if the provided `Entity` refers to a live Entity, `false` if it was never
+    /// created or has since been deleted.
+    pub fn is_alive(&self, entity_id: &Entity) -> bool {
+        self.entity_allocator.is_alive(entity_id)
+    }
+
     /// Deletes an Entity given its `entity_id`. Removes / deregisters all `Component`s associated
-    /// with that Entity.
-    pub fn delete_entity(&mut self, entity_id: &u32) {
-        self.entity_id_to_component_ids.remove(entity_id);
-        self.component_id_to_component_managers
-            .values_mut()
-            .for_each(|component_manager| {
-                component_manager.deregister_entity(entity_id);
-            });
+    /// with that Entity. The Entity is detached from its parent, and its children are orphaned; see
+    /// `delete_entity_recursive` to delete them as well.
+    ///
+    /// Will panic if the provided `entity_id` is stale.
+    pub fn delete_entity(&mut self, entity_id: &Entity) {
+        self.try_delete_entity(entity_id)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Deletes an Entity given its `entity_id`. Fails with `Error::EntityNotFound` if the provided
+    /// `entity_id` is stale.
+    pub fn try_delete_entity(&mut self, entity_id: &Entity) -> Result<()> {
+        if !self.is_alive(entity_id) {
+            return Err(Error::EntityNotFound(*entity_id));
+        }
+        self.flush_reserved_entities();
+        self.detach_from_hierarchy(entity_id);
+        self.entity_allocator.deallocate(entity_id);
+
+        let location = self.get_location(entity_id);
+        let change_tick = self.get_system_context().this_run;
+        let archetype = &mut self.archetypes[location.archetype_id];
+        self.removed_components.extend(
+            archetype
+                .get_component_ids()
+                .iter()
+                .map(|component_id| (change_tick, *entity_id, *component_id)),
+        );
+        self.deleted_entities.push((change_tick, *entity_id));
+        archetype
+            .get_columns_mut()
+            .for_each(|(_, column)| column.swap_remove(location.row));
+        if let Some(moved_entity_id) = archetype.swap_remove_entity(location.row) {
+            self.set_location(&moved_entity_id, location);
+        }
         self.query_cache
             .write()
             .unwrap()
             .remove_entity_from_cache(entity_id);
+        Ok(())
     }
 
     /// Adds all specified `Component`s to the Entity with the associated `entity_id`.
     ///
-    /// Will panic if registering multiple `Component`s of the same type is attempted.
-    pub fn add_components_to_entity(&mut self, entity_id: &u32, components: ComponentBundle) {
-        components
-            .get_components()
+    /// Will panic if the provided `entity_id` is stale, or if registering multiple `Component`s of
+    /// the same type is attempted.
+    pub fn add_components_to_entity(&mut self, entity_id: &Entity, components: ComponentBundle) {
+        self.try_add_components_to_entity(entity_id, components)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Adds all specified `Component`s to the Entity with the associated `entity_id`. Fails with
+    /// `Error::EntityNotFound` if the provided `entity_id` is stale, or with
+    /// `Error::ComponentAlreadyRegistered` if the Entity already has a `Component` of one of the
+    /// provided types, in which case none of the `Component`s are added.
+    pub fn try_add_components_to_entity(
+        &mut self,
+        entity_id: &Entity,
+        components: ComponentBundle,
+    ) -> Result<()> {
+        if !self.entity_allocator.is_alive(entity_id) {
+            return Err(Error::EntityNotFound(*entity_id));
+        }
+
+        let location = self.get_location(entity_id);
+        let source = &self.archetypes[location.archetype_id];
+        let components = components.into_components();
+        if let Some(component) = components
             .iter()
-            .for_each(|(component_id, component)| {
-                self.entity_id_to_component_ids
-                    .get_mut(entity_id)
-                    .map(|component_ids| {
-                        if component_ids.contains(component_id) {
-                            panic!(
-                                "Component: {} already registered for Entity: {}!",
-                                component.read().unwrap().self_property_string(),
-                                entity_id
-                            );
-                        }
-                        component_ids.insert(*component_id)
-                    });
-                let component_manager = self
-                    .component_id_to_component_managers
-                    .entry(*component_id)
-                    .or_insert(Box::new(ComponentManager::new()));
-                component_manager.register_entity(entity_id, component.clone());
+            .find(|(component_id, _)| source.contains_component(component_id))
+            .map(|(_, component)| component)
+        {
+            return Err(Error::ComponentAlreadyRegistered {
+                entity_id: *entity_id,
+                component: component.component_name(),
             });
-        self.query_cache
-            .write()
-            .unwrap()
-            .update_cache(entity_id, &self.get_all_components_for_entity(entity_id));
+        }
+
+        if !components.is_empty() {
+            let mut component_ids = source.get_component_ids().to_vec();
+            component_ids.extend(components.keys());
+            let destination_id = self.get_or_create_archetype(component_ids, |entity_manager| {
+                let mut columns =
+                    entity_manager.archetypes[location.archetype_id].new_empty_columns();
+                columns.extend(components.values().map(|column| column.new_empty()));
+                columns
+            });
+            let change_tick = self.get_system_context().this_run;
+            self.move_entity(entity_id, location, destination_id, components, change_tick);
+        }
+
+        let archetype_id = self.get_location(entity_id).archetype_id;
+        self.query_cache.write().unwrap().update_cache(
+            entity_id,
+            self.archetypes[location.archetype_id].get_component_ids(),
+            self.archetypes[archetype_id].get_component_ids(),
+        );
+        Ok(())
     }
 
     /// Removes the specified `Component` from the Entity with the associated `entity_id`.
     ///
-    /// Will panic if the provided `entity_id` is not registered.
-    pub fn remove_component_from_entity<T: 'static + Component>(&mut self, entity_id: &u32) {
-        if !self.entity_id_to_component_ids.contains_key(entity_id) {
-            panic!("Entity: {} does not exist!", *entity_id);
+    /// Will panic if the provided `entity_id` is stale.
+    pub fn remove_component_from_entity<T: 'static + Component>(&mut self, entity_id: &Entity) {
+        self.try_remove_component_from_entity::<T>(entity_id)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Removes the specified `Component` from the Entity with the associated `entity_id`. Fails
+    /// with `Error::EntityNotFound` if the provided `entity_id` is stale.
+    pub fn try_remove_component_from_entity<T: 'static + Component>(
+        &mut self,
+        entity_id: &Entity,
+    ) -> Result<()> {
+        if !self.entity_allocator.is_alive(entity_id) {
+            return Err(Error::EntityNotFound(*entity_id));
         }
 
-        self.entity_id_to_component_ids
-            .get_mut(entity_id)
-            .map(|component_ids| component_ids.remove(&T::property_id()));
-        if let Some(component_manager) = self
-            .component_id_to_component_managers
-            .get_mut(&T::property_id())
-        {
-            component_manager.deregister_entity(entity_id);
+        let location = self.get_location(entity_id);
+        let source = &self.archetypes[location.archetype_id];
+        if !source.contains_component(&T::property_id()) {
+            return Ok(());
         }
-        self.query_cache
-            .write()
-            .unwrap()
-            .update_cache(entity_id, &self.get_all_components_for_entity(entity_id));
+
+        let component_ids: Vec<u64> = source
+            .get_component_ids()
+            .iter()
+            .filter(|component_id| **component_id != T::property_id())
+            .copied()
+            .collect();
+        let destination_id = self.get_or_create_archetype(component_ids, |entity_manager| {
+            entity_manager.archetypes[location.archetype_id]
+                .new_empty_columns()
+                .into_iter()
+                .filter(|column| column.component_id() != T::property_id())
+                .collect()
+        });
+        let change_tick = self.get_system_context().this_run;
+        self.move_entity(
+            entity_id,
+            location,
+            destination_id,
+            HashMap::new(),
+            change_tick,
+        );
+        self.removed_components
+            .push((change_tick, *entity_id, T::property_id()));
+
+        self.query_cache.write().unwrap().update_cache(
+            entity_id,
+            self.archetypes[location.archetype_id].get_component_ids(),
+            self.archetypes[destination_id].get_component_ids(),
+        );
+        Ok(())
     }
 
-    /// Retrieves all of the `Component`s registered for a given `entity_id`.
-    pub fn get_all_components_for_entity(&self, entity_id: &u32) -> ComponentBundle {
-        let mut component_bundle = ComponentBundle::new();
-        self.entity_id_to_component_ids
-            .get(entity_id)
+    /// Retrieves access to all of the `Component`s registered for a given `entity_id`. Returns
+    /// `None` if the `entity_id` is stale.
+    pub fn get_all_components_for_entity(&self, entity_id: &Entity) -> Option<QueryResult<'_>> {
+        if !self.entity_allocator.is_alive(entity_id) {
+            return None;
+        }
+        Some(self.get_query_result(entity_id, self.get_system_context().this_run))
+    }
+
+    /// Returns `true` if the provided `Entity` refers to a live Entity. Equivalent to `is_alive`.
+    pub fn contains(&self, entity_id: &Entity) -> bool {
+        self.is_alive(entity_id)
+    }
+
+    /// Returns `true` if the provided `Entity` is alive and has a `Component` of type `T`.
+    pub fn has<T: 'static + Component>(&self, entity_id: &Entity) -> bool {
+        self.get_archetype(entity_id)
+            .is_some_and(|(archetype, _)| archetype.contains_component(&T::property_id()))
+    }
+
+    /// Retrieves shared access to the `Component` of type `T` of an Entity. Returns `None` if the
+    /// `entity_id` is stale, or the Entity has no such `Component`.
+    ///
+    /// Note: blocks while any `ComponentMut` of the same type is held for an Entity with the same
+    /// set of `Component`s.
+    pub fn get<T: 'static + Component>(&self, entity_id: &Entity) -> Option<ComponentRef<'_, T>> {
+        let (archetype, row) = self.get_archetype(entity_id)?;
+        archetype
+            .get_column(&T::property_id())
+            .map(|column| ComponentRef::new(column.read().unwrap(), row))
+    }
+
+    /// Retrieves exclusive access to the `Component` of type `T` of an Entity, marking it as
+    /// changed. Returns `None` if the `entity_id` is stale, or the Entity has no such `Component`.
+    ///
+    /// Note: blocks while any `ComponentRef` or `ComponentMut` of the same type is held for an
+    /// Entity with the same set of `Component`s.
+    pub fn get_mut<T: 'static + Component>(
+        &self,
+        entity_id: &Entity,
+    ) -> Option<ComponentMut<'_, T>> {
+        let (archetype, row) = self.get_archetype(entity_id)?;
+        let change_tick = self.get_system_context().this_run;
+        archetype
+            .get_column(&T::property_id())
+            .map(|column| ComponentMut::new(column.write().unwrap(), row, change_tick))
+    }
+
+    /// Retrieves access to several `Component`s of an Entity at once, as described by an
+    /// `EntityFetch` such as `(&A, &mut B, Option<&C>)`. For example:
+    ///
+    /// ```ignore
+    /// if let Some((target, mut velocity)) =
+    ///     entity_manager.get_many::<(&TargetComponent, &mut VelocityComponent)>(&entity_id)
+    /// {
+    ///     velocity.x_vel = target.x - velocity.x_vel;
+    /// }
+    /// ```
+    ///
+    /// Returns `None` if the `entity_id` is stale, or the Entity lacks a required `Component`, in
+    /// which case no `Component` is marked as changed. Locks are acquired in the same order as for
+    /// queries, so concurrent calls cannot deadlock against each other.
+    ///
+    /// Will panic if `F` accesses the same `Component` type more than once.
+    pub fn get_many<F: EntityFetch>(&self, entity_id: &Entity) -> Option<F::Item<'_>> {
+        let mut access = ComponentAccess::new();
+        F::add_access(&mut access);
+        let (archetype, row) = self.get_archetype(entity_id)?;
+        let mut required_components = Vec::new();
+        F::add_required_components(&mut required_components);
+        if !required_components
             .iter()
-            .for_each(|component_ids| {
-                component_ids.iter().for_each(|component_id| {
-                    component_bundle.add_component_arc(
-                        self.component_id_to_component_managers
-                            .get(component_id)
-                            .unwrap()
-                            .get_component_for_entity(entity_id)
-                            .unwrap()
-                            .clone(),
-                    );
-                });
-            });
-        component_bundle
+            .all(|component_id| archetype.contains_component(component_id))
+        {
+            return None;
+        }
+
+        let change_tick = self.get_system_context().this_run;
+        let mut locks = ArchetypeLocks::acquire(archetype, &access, change_tick);
+        Some(F::fetch(&mut locks, row))
     }
 
     /// Executes a `Query` over all Entities. Returns a vector of `QueryResult`s.
     /// This is generally how one finds all Entities with a specific `Component` type, for example.
     /// Queries execution is parallelized, and results are automatically cached to improve future
-    /// queries of the same type.
-    pub fn filter(&self, query: Query) -> Vec<QueryResult> {
-        if let Some(query_results) = self.query_cache.read().unwrap().check_cache(&query) {
-            return query_results;
+    /// queries of the same type. The cache holds a bounded number of queries, evicting the least
+    /// recently used one when full; see `pin_query`, `clear_query_cache` and
+    /// `get_query_cache_stats` to manage it.
+    ///
+    /// Queries filtering on change detection (`Query::added` / `Query::changed`) are never cached.
+    /// See `filter_iter` to iterate over results without allocating.
+    pub fn filter(&self, query: Query) -> Vec<QueryResult<'_>> {
+        let context = self.get_system_context();
+        if query.has_change_filters() {
+            return self.filter_iter(&query).collect();
         }
 
-        let mut entities: HashSet<u32> = self.entity_id_to_component_ids.keys().copied().collect();
-        entities = entities
-            .par_iter()
-            .filter(|entity_id| {
-                query.get_with_components().iter().all(|component_id| {
-                    self.entity_id_to_component_ids
-                        .get(entity_id)
-                        .map(|entity_component_ids| entity_component_ids.contains(component_id))
-                        .unwrap()
-                })
-            })
-            .copied()
-            .collect();
-        entities = entities
-            .par_iter()
-            .filter(|entity_id| {
-                query.get_without_components().iter().all(|component_id| {
-                    !self
-                        .entity_id_to_component_ids
-                        .get(entity_id)
-                        .map(|entity_component_ids| entity_component_ids.contains(component_id))
-                        .unwrap()
-                })
-            })
-            .copied()
+        if let Some(entities) = self.query_cache.read().unwrap().check_cache(&query) {
+            return entities
+                .iter()
+                .map(|entity_id| self.get_query_result(entity_id, context.this_run))
+                .collect();
+        }
+
+        let entities = self.get_matching_entities(&query);
+        let results = entities
+            .iter()
+            .map(|entity_id| self.get_query_result(entity_id, context.this_run))
             .collect();
 
-        let mut results: Vec<QueryResult> = Vec::new();
-        entities.iter().for_each(|entity_id| {
-            let mut result = QueryResult::new(*entity_id);
-            query.get_with_components().iter().for_each(|component_id| {
-                result.add_component(
-                    self.component_id_to_component_managers
-                        .get(component_id)
-                        .unwrap()
-                        .get_component_for_entity(entity_id)
-                        .unwrap(),
-                );
-            });
-            results.push(result);
-        });
+        // Another thread may have cached the same `Query` in the meantime, with the same result.
+        self.query_cache
+            .write()
+            .unwrap()
+            .add_to_cache((query, entities));
+        results
+    }
 
+    /// Pins a `Query` in the cache, so that it is never evicted nor removed by `clear_query_cache`.
+    /// Useful for queries executed every frame via `filter`.
+    ///
+    /// Queries filtering on change detection are never cached, and are ignored.
+    pub fn pin_query(&self, query: Query) {
+        if query.has_change_filters() {
+            return;
+        }
         self.query_cache
             .write()
             .unwrap()
-            .add_to_cache((query, results.to_vec()));
-        results.to_vec()
+            .pin_query(query.clone(), || self.get_matching_entities(&query));
+    }
+
+    /// Unpins a `Query` previously pinned via `pin_query`, after which it is cached like any other
+    /// `Query`. Returns `false` if the `Query` was not pinned.
+    pub fn unpin_query(&self, query: &Query) -> bool {
+        self.query_cache.write().unwrap().unpin_query(query)
+    }
+
+    /// Removes all queries from the cache, except for pinned ones. Each cached `Query` adds a small
+    /// cost to creating and deleting Entities and to adding and removing `Component`s, so this is
+    /// useful after running many one-off queries.
+    pub fn clear_query_cache(&self) {
+        self.query_cache.write().unwrap().clear();
+    }
+
+    /// Sets the number of queries (besides pinned ones) `filter` keeps cached, evicting the least
+    /// recently used ones when exceeded. Defaults to `DEFAULT_QUERY_CACHE_CAPACITY`. A capacity of
+    /// 0 disables caching of unpinned queries.
+    pub fn set_query_cache_capacity(&mut self, capacity: usize) {
+        self.query_cache.write().unwrap().set_capacity(capacity);
+    }
+
+    /// Returns the query cache's hit, miss, eviction and maintenance counters.
+    pub fn get_query_cache_stats(&self) -> QueryCacheStats {
+        self.query_cache.read().unwrap().get_stats()
+    }
+
+    /// Executes a `Query` over all Entities, returning an iterator over a `QueryResult` per matched
+    /// Entity. Borrows directly from storage, so unlike `filter`, no results are allocated, copied,
+    /// or cached. Preferable for queries executed every frame over many Entities.
+    pub fn filter_iter<'q>(&self, query: &'q Query) -> FilterIter<'_, 'q> {
+        let context = self.get_system_context();
+        FilterIter::new(&self.archetypes, query, context.last_run, context.this_run)
+    }
+
+    /// Executes a typed `Query` over all Entities, returning a `QueryBorrow` that iterates directly
+    /// over the fetched `Component`s of every matched Entity. For example:
+    ///
+    /// ```ignore
+    /// entity_manager
+    ///     .query(&Query::<(&mut PositionComponent, &VelocityComponent)>::default())
+    ///     .for_each(|(position, velocity)| position.x += velocity.x_vel);
+    /// ```
+    ///
+    /// Unlike `filter`, results are not cached; matching is done per archetype rather than per
+    /// Entity.
+    pub fn query<D: QueryData>(&self, query: &Query<D>) -> QueryBorrow<'_, D> {
+        let context = self.get_system_context();
+        QueryBorrow::new(
+            self.archetypes.iter().filter(|archetype| {
+                !archetype.get_entities().is_empty() && archetype.matches(query)
+            }),
+            query,
+            context.last_run,
+            context.this_run,
+        )
+    }
+
+    /// Returns the Entities that had their `Component` of type `T` removed, or were deleted while
+    /// having one, since the currently executing `System` last ran. Outside of a `System`, returns
+    /// every such Entity that is still logged.
+    ///
+    /// Note: the `Engine` forgets removals at the end of the frame after the one they happened in
+    /// (see `Engine::finish_frame`).
+    pub fn get_removed_components<T: 'static + Component>(&self) -> Vec<Entity> {
+        let last_run = self.get_system_context().last_run;
+        self.removed_components
+            .iter()
+            .filter(|(tick, _, component_id)| *tick > last_run && *component_id == T::property_id())
+            .map(|(_, entity_id, _)| *entity_id)
+            .collect()
+    }
+
+    /// Returns the Entities deleted since the currently executing `System` last ran. Outside of a
+    /// `System`, returns every deleted Entity that is still logged.
+    pub fn get_deleted_entities(&self) -> Vec<Entity> {
+        let last_run = self.get_system_context().last_run;
+        self.deleted_entities
+            .iter()
+            .filter(|(tick, _)| *tick > last_run)
+            .map(|(_, entity_id)| *entity_id)
+            .collect()
+    }
+
+    /// Returns a new `Commands` buffer, used to record structural changes that are applied later
+    /// via `flush_commands`. See `Commands` for details.
+    pub fn commands(&self) -> Commands<'_> {
+        Commands::new(self)
+    }
+
+    /// Applies all `Commands` submitted so far, in the order they were submitted.
+    ///
+    /// Will panic if a command fails, e.g. because it adds a `Component` the Entity already has.
+    pub fn flush_commands(&mut self) {
+        self.try_flush_commands()
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Applies all `Commands` submitted so far, in the order they were submitted. Commands that
+    /// fail are skipped, and the first such failure is returned once all others were applied.
+    pub fn try_flush_commands(&mut self) -> Result<()> {
+        self.flush_reserved_entities();
+        let commands = std::mem::take(self.command_queue.get_mut().unwrap());
+        let mut result = Ok(());
+        commands.into_iter().for_each(|command| {
+            let command_result = command.apply(self);
+            if result.is_ok() {
+                result = command_result;
+            }
+        });
+        result
+    }
+
+    /// Inserts a `Resource`, replacing any existing `Resource` of the same type.
+    pub fn insert_resource<T: Resource>(&mut self, resource: T) {
+        self.resources.insert(resource);
+    }
+
+    /// Removes the `Resource` of type `T`, returning it if it existed.
+    pub fn remove_resource<T: Resource>(&mut self) -> Option<Arc<RwLock<T>>> {
+        self.resources.remove::<T>()
+    }
+
+    /// Retrieves access to the `Resource` of type `T`. Returns `None` if it does not exist.
+    pub fn get_resource<T: Resource>(&self) -> Option<Arc<RwLock<T>>> {
+        self.resources.get::<T>()
+    }
+
+    pub fn has_resource<T: Resource>(&self) -> bool {
+        self.resources.contains::<T>()
+    }
+
+    /// Sends an `Event` of type `T`. Shorthand for writing to the `Events<T>` `Resource`.
+    ///
+    /// Will panic if `T` was not registered via `Engine::add_event`.
+    pub fn send_event<T: Event>(&self, event: T) {
+        self.try_send_event(event)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Sends an `Event` of type `T`. Fails with `Error::EventNotRegistered` if `T` was not
+    /// registered via `Engine::add_event`.
+    pub fn try_send_event<T: Event>(&self, event: T) -> Result<()> {
+        match self.get_resource::<Events<T>>() {
+            Some(events) => {
+                events.write().unwrap().send(event);
+                Ok(())
+            }
+            None => Err(Error::EventNotRegistered(std::any::type_name::<T>())),
+        }
+    }
+
+    pub(crate) fn reserve_entity(&self) -> Entity {
+        self.entity_allocator.reserve()
+    }
+
+    pub(crate) fn submit_commands(&self, commands: Vec<Command>) {
+        self.command_queue.lock().unwrap().extend(commands);
+    }
+
+    /// Returns all archetypes, e.g. to serialize every Entity's `Component`s.
+    #[cfg(feature = "serde")]
+    pub(crate) fn get_archetypes(&self) -> &[Archetype] {
+        &self.archetypes
+    }
+
+    /// Returns the current change tick.
+    pub(crate) fn get_change_tick(&self) -> u64 {
+        self.change_tick.load(Ordering::Acquire)
+    }
+
+    /// Advances the change tick, returning the new value.
+    pub(crate) fn increment_change_tick(&self) -> u64 {
+        self.change_tick.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    /// Forgets all removals and deletions logged at or before `tick`.
+    pub(crate) fn clear_removal_log(&mut self, tick: u64) {
+        self.removed_components
+            .retain(|(removed_tick, _, _)| *removed_tick > tick);
+        self.deleted_entities
+            .retain(|(deleted_tick, _)| *deleted_tick > tick);
+    }
+
+    /// The context of the currently executing `System`. Outside of a `System`, changes are
+    /// recorded after every `System` executed so far, and all changes are reported.
+    fn get_system_context(&self) -> SystemContext {
+        SystemContext::current().unwrap_or_else(|| SystemContext {
+            system_id: 0,
+            last_run: 0,
+            this_run: self.change_tick.load(Ordering::Acquire) + 1,
+        })
+    }
+
+    /// Makes all Entities reserved by `Commands` live, without any `Component`s.
+    fn flush_reserved_entities(&mut self) {
+        self.entity_allocator
+            .flush_reserved()
+            .iter()
+            .for_each(|entity_id| self.insert_empty_entity(entity_id));
+    }
+
+    fn insert_empty_entity(&mut self, entity_id: &Entity) {
+        let archetype_id = self.get_or_create_archetype(Vec::new(), |_| Vec::new());
+        let row = self.archetypes[archetype_id].push_entity(*entity_id);
+        self.set_location(entity_id, EntityLocation { archetype_id, row });
+        self.query_cache.write().unwrap().insert_entity(entity_id);
+    }
+
+    fn get_matching_entities(&self, query: &Query) -> Vec<Entity> {
+        self.archetypes
+            .par_iter()
+            .filter(|archetype| archetype.matches(query))
+            .flat_map_iter(|archetype| archetype.get_entities().iter().copied())
+            .collect()
+    }
+
+    fn get_query_result(&self, entity_id: &Entity, change_tick: u64) -> QueryResult<'_> {
+        let location = self.get_location(entity_id);
+        QueryResult::new(
+            *entity_id,
+            &self.archetypes[location.archetype_id],
+            location.row,
+            change_tick,
+        )
+    }
+
+    /// Returns the archetype and row storing the `Component`s of a live Entity.
+    fn get_archetype(&self, entity_id: &Entity) -> Option<(&Archetype, usize)> {
+        if !self.entity_allocator.is_alive(entity_id) {
+            return None;
+        }
+        let location = self.get_location(entity_id);
+        Some((&self.archetypes[location.archetype_id], location.row))
+    }
+
+    fn get_location(&self, entity_id: &Entity) -> EntityLocation {
+        self.entity_locations[entity_id.index() as usize]
+    }
+
+    fn set_location(&mut self, entity_id: &Entity, location: EntityLocation) {
+        let index = entity_id.index() as usize;
+        if index >= self.entity_locations.len() {
+            self.entity_locations
+                .resize(index + 1, EntityLocation::default());
+        }
+        self.entity_locations[index] = location;
+    }
+
+    /// Returns the id of the archetype storing exactly the provided `Component` ids, creating it
+    /// from the columns returned by `new_columns` if it does not exist yet.
+    fn get_or_create_archetype<F>(&mut self, mut component_ids: Vec<u64>, new_columns: F) -> usize
+    where
+        F: FnOnce(&EntityManager) -> Vec<Box<dyn ComponentColumn>>,
+    {
+        component_ids.sort_unstable();
+        if let Some(archetype_id) = self.component_ids_to_archetype_id.get(&component_ids) {
+            return *archetype_id;
+        }
+
+        let archetype_id = self.archetypes.len();
+        let archetype = Archetype::new(new_columns(self));
+        self.archetypes.push(archetype);
+        self.component_ids_to_archetype_id
+            .insert(component_ids, archetype_id);
+        archetype_id
+    }
+
+    /// Moves an Entity from its current archetype to the archetype `destination_id`. Columns
+    /// missing from the destination are dropped, and `additional_components` (single-row columns)
+    /// are moved in alongside the existing ones, marked as added at `change_tick`.
+    fn move_entity(
+        &mut self,
+        entity_id: &Entity,
+        location: EntityLocation,
+        destination_id: usize,
+        additional_components: HashMap<u64, Box<dyn ComponentColumn>>,
+        change_tick: u64,
+    ) {
+        let (source, destination) =
+            self.get_archetype_pair_mut(location.archetype_id, destination_id);
+        let destination_row = destination.get_entities().len();
+        source.get_columns_mut().for_each(|(component_id, column)| {
+            match destination.get_column_mut(component_id) {
+                Some(destination_column) => {
+                    column.move_row(location.row, destination_column.as_mut())
+                }
+                None => column.swap_remove(location.row),
+            }
+        });
+        additional_components
+            .into_iter()
+            .for_each(|(component_id, mut column)| {
+                let destination_column = destination.get_column_mut(&component_id).unwrap();
+                column.move_row(0, destination_column.as_mut());
+                destination_column.set_ticks(destination_row, ComponentTicks::new(change_tick));
+            });
+
+        let moved_entity_id = source.swap_remove_entity(location.row);
+        let row = destination.push_entity(*entity_id);
+        if let Some(moved_entity_id) = moved_entity_id {
+            self.set_location(&moved_entity_id, location);
+        }
+        self.set_location(
+            entity_id,
+            EntityLocation {
+                archetype_id: destination_id,
+                row,
+            },
+        );
+    }
+
+    fn get_archetype_pair_mut(&mut self, a: usize, b: usize) -> (&mut Archetype, &mut Archetype) {
+        assert_ne!(a, b);
+        if a < b {
+            let (left, right) = self.archetypes.split_at_mut(b);
+            (&mut left[a], &mut right[0])
+        } else {
+            let (left, right) = self.archetypes.split_at_mut(a);
+            (&mut right[0], &mut left[b])
+        }
     }
 }
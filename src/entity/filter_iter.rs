@@ -0,0 +1,64 @@
+use std::iter::Enumerate;
+use std::slice::Iter;
+
+use crate::entity::{Archetype, Entity, Query, QueryResult};
+
+/// Iterates over a `QueryResult` per Entity matched by a `Query`, borrowing directly from archetype
+/// storage. Returned by `EntityManager::filter_iter`.
+///
+/// Unlike `EntityManager::filter`, nothing is allocated or cached: matching is done per archetype,
+/// and only rows of matching archetypes are visited.
+pub struct FilterIter<'w, 'q> {
+    archetypes: Iter<'w, Archetype>,
+    query: &'q Query,
+    last_run: u64,
+    this_run: u64,
+    current: Option<(&'w Archetype, Enumerate<Iter<'w, Entity>>)>,
+}
+
+impl<'w, 'q> FilterIter<'w, 'q> {
+    pub(crate) fn new(
+        archetypes: &'w [Archetype],
+        query: &'q Query,
+        last_run: u64,
+        this_run: u64,
+    ) -> Self {
+        FilterIter {
+            archetypes: archetypes.iter(),
+            query,
+            last_run,
+            this_run,
+            current: None,
+        }
+    }
+
+    /// Returns `true` if the Entity in `row` passes the `Query`'s change detection filters.
+    fn matches_ticks(&self, archetype: &Archetype, row: usize) -> bool {
+        !self.query.has_change_filters()
+            || self.query.matches_ticks(self.last_run, |component_id| {
+                let column = archetype.get_column(component_id).unwrap();
+                column.read().unwrap().get_ticks(row)
+            })
+    }
+}
+
+impl<'w> Iterator for FilterIter<'w, '_> {
+    type Item = QueryResult<'w>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((archetype, rows)) = self.current.as_mut() {
+                let archetype = *archetype;
+                if let Some((row, entity_id)) = rows.next() {
+                    if self.matches_ticks(archetype, row) {
+                        return Some(QueryResult::new(*entity_id, archetype, row, self.this_run));
+                    }
+                    continue;
+                }
+            }
+            let query = self.query;
+            let archetype = self.archetypes.find(|archetype| archetype.matches(query))?;
+            self.current = Some((archetype, archetype.get_entities().iter().enumerate()));
+        }
+    }
+}
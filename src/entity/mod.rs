@@ -1,8 +1,31 @@
+mod archetype;
+mod commands;
+#[allow(clippy::module_inception)]
+mod entity;
+mod entity_allocator;
+mod entity_fetch;
 mod entity_manager;
+mod filter_iter;
 mod query;
+mod query_borrow;
 mod query_cache;
+mod query_data;
+mod query_filter;
+mod signature;
 mod test;
 
+pub(crate) use self::archetype::*;
+pub(crate) use self::commands::Command;
+pub use self::commands::Commands;
+pub use self::entity::*;
+pub(crate) use self::entity_allocator::*;
+pub use self::entity_fetch::*;
 pub use self::entity_manager::*;
+pub use self::filter_iter::*;
 pub use self::query::*;
-pub(crate) use self::query_cache::*;
+pub use self::query_borrow::*;
+pub(crate) use self::query_cache::QueryCache;
+pub use self::query_cache::{QueryCacheStats, DEFAULT_QUERY_CACHE_CAPACITY};
+pub use self::query_data::*;
+pub use self::query_filter::*;
+pub(crate) use self::signature::*;
@@ -1,75 +1,294 @@
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 
-use crate::component::Component;
+use crate::component::{
+    Component, ComponentAccess, ComponentMut, ComponentRef, ComponentSet, ComponentTicks,
+};
+use crate::entity::{Archetype, Entity, QueryData};
 
-#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
-pub struct Query {
+/// Describes a subset of Entities, by the `Component`s they must have (`with`), must not have
+/// (`without`), and of which they must have at least one (`any_of`). `Component`s that are used
+/// when present, without filtering on them, may be declared via `optional`.
+///
+/// An untyped `Query`, created via `Query::new()`, is executed with `EntityManager::filter`, which
+/// returns a `QueryResult` per matched Entity.
+///
+/// A typed `Query<D>` additionally describes what to fetch for each matched Entity, where `D` is
+/// a `QueryData` such as `(&A, &mut B, Option<&C>)`. `D` implicitly adds its required `Component`s
+/// to `with`. Typed queries are created via e.g. `Query::<(&A, &mut B)>::default()` and executed
+/// with `EntityManager::query`, which iterates directly over references to the `Component`s.
+///
+/// Either kind of `Query` may additionally filter on change detection: `added` matches Entities
+/// whose `Component` was added, and `changed` Entities whose `Component` was added or mutably
+/// accessed, since the currently executing `System` last ran. Outside of a `System`, every
+/// `Component` counts as added and changed.
+///
+/// Queries are kept in a canonical form, so the order in which filters are added, and adding the
+/// same filter twice, make no difference: `with::<A>().with::<B>()` equals
+/// `with::<B>().with::<A>()`.
+///
+/// Note: constructing a typed `Query` that accesses the same `Component` type more than once will
+/// panic, as it would hand out aliasing references.
+pub struct Query<D: QueryData = ()> {
     with_components: Vec<u64>,
     without_components: Vec<u64>,
+    any_of_components: Vec<Vec<u64>>,
+    optional_components: Vec<u64>,
+    added_components: Vec<u64>,
+    changed_components: Vec<u64>,
+    access: ComponentAccess,
+    marker: PhantomData<fn() -> D>,
 }
 
 impl Query {
     pub fn new() -> Self {
-        Query {
-            with_components: Vec::new(),
-            without_components: Vec::new(),
-        }
+        Query::default()
+    }
+}
+
+impl<D: QueryData> Query<D> {
+    pub fn with<T: 'static + Component>(mut self) -> Query<D> {
+        insert_sorted(&mut self.with_components, T::property_id());
+        self
     }
 
-    pub fn with<T: 'static + Component>(mut self) -> Query {
-        self.with_components.push(T::property_id());
+    pub fn without<T: 'static + Component>(mut self) -> Query<D> {
+        insert_sorted(&mut self.without_components, T::property_id());
         self
     }
 
-    pub fn without<T: 'static + Component>(mut self) -> Query {
-        self.without_components.push(T::property_id());
+    /// Only matches Entities that have at least one of the `Component`s in `S`, e.g.
+    /// `any_of::<(PlayerComponent, NpcComponent)>()`. Multiple OR groups must all be satisfied.
+    pub fn any_of<S: ComponentSet>(mut self) -> Query<D> {
+        let mut component_ids = Vec::new();
+        S::add_component_ids(&mut component_ids);
+        component_ids.sort_unstable();
+        component_ids.dedup();
+        insert_sorted(&mut self.any_of_components, component_ids);
         self
     }
 
-    pub(crate) fn get_with_components(&self) -> &Vec<u64> {
+    /// Declares that the `Component` of type `T` is read when an Entity has it, without filtering
+    /// on it. It is then available via `QueryResult::get_component`, and recorded in
+    /// `get_access`. Typed queries fetch such `Component`s via `Option<&T>` instead.
+    pub fn optional<T: 'static + Component>(mut self) -> Query<D> {
+        insert_sorted(&mut self.optional_components, T::property_id());
+        self.add_filter_access::<T>();
+        self
+    }
+
+    /// Only matches Entities whose `Component` of type `T` was added since the currently executing
+    /// `System` last ran. Implies `with::<T>()`.
+    pub fn added<T: 'static + Component>(mut self) -> Query<D> {
+        insert_sorted(&mut self.added_components, T::property_id());
+        self.add_filter_access::<T>();
+        self.with::<T>()
+    }
+
+    /// Only matches Entities whose `Component` of type `T` was added or mutably accessed since the
+    /// currently executing `System` last ran. Implies `with::<T>()`.
+    pub fn changed<T: 'static + Component>(mut self) -> Query<D> {
+        insert_sorted(&mut self.changed_components, T::property_id());
+        self.add_filter_access::<T>();
+        self.with::<T>()
+    }
+
+    /// The `Component`s read and written when executing this `Query`.
+    pub fn get_access(&self) -> &ComponentAccess {
+        &self.access
+    }
+
+    pub(crate) fn get_with_components(&self) -> &[u64] {
         &self.with_components
     }
 
-    pub(crate) fn get_without_components(&self) -> &Vec<u64> {
+    pub(crate) fn get_without_components(&self) -> &[u64] {
         &self.without_components
     }
+
+    pub(crate) fn get_any_of_components(&self) -> &[Vec<u64>] {
+        &self.any_of_components
+    }
+
+    /// Returns `true` if this `Query` filters on change detection, in which case matching depends
+    /// on more than the set of `Component`s of an Entity.
+    pub(crate) fn has_change_filters(&self) -> bool {
+        !self.added_components.is_empty() || !self.changed_components.is_empty()
+    }
+
+    /// Returns `true` if an Entity, whose `Component` ticks are provided by `get_ticks`, satisfies
+    /// the change detection filters of this `Query`.
+    pub(crate) fn matches_ticks<F>(&self, last_run: u64, get_ticks: F) -> bool
+    where
+        F: Fn(&u64) -> ComponentTicks,
+    {
+        self.added_components
+            .iter()
+            .all(|component_id| get_ticks(component_id).is_added(last_run))
+            && self
+                .changed_components
+                .iter()
+                .all(|component_id| get_ticks(component_id).is_changed(last_run))
+    }
+
+    /// Records shared access to `T`, unless it is already accessed (e.g. by `D`).
+    fn add_filter_access<T: 'static + Component>(&mut self) {
+        if !self.access.reads(&T::property_id()) && !self.access.writes(&T::property_id()) {
+            self.access.add_read::<T>();
+        }
+    }
+
+    /// Returns `true` if an Entity with the provided (sorted) set of `Component` ids satisfies this
+    /// `Query`.
+    pub(crate) fn matches(&self, component_ids: &[u64]) -> bool {
+        self.with_components
+            .iter()
+            .all(|component_id| component_ids.binary_search(component_id).is_ok())
+            && !self
+                .without_components
+                .iter()
+                .any(|component_id| component_ids.binary_search(component_id).is_ok())
+            && self.any_of_components.iter().all(|group| {
+                group
+                    .iter()
+                    .any(|component_id| component_ids.binary_search(component_id).is_ok())
+            })
+    }
 }
 
+impl<D: QueryData> Default for Query<D> {
+    fn default() -> Self {
+        let mut access = ComponentAccess::new();
+        D::add_access(&mut access);
+        let mut with_components = Vec::new();
+        D::add_required_components(&mut with_components);
+        with_components.sort_unstable();
+        with_components.dedup();
+        Query {
+            with_components,
+            without_components: Vec::new(),
+            any_of_components: Vec::new(),
+            optional_components: Vec::new(),
+            added_components: Vec::new(),
+            changed_components: Vec::new(),
+            access,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<D: QueryData> Clone for Query<D> {
+    fn clone(&self) -> Self {
+        Query {
+            with_components: self.with_components.clone(),
+            without_components: self.without_components.clone(),
+            any_of_components: self.any_of_components.clone(),
+            optional_components: self.optional_components.clone(),
+            added_components: self.added_components.clone(),
+            changed_components: self.changed_components.clone(),
+            access: self.access.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<D: QueryData> fmt::Debug for Query<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Query")
+            .field("with_components", &self.with_components)
+            .field("without_components", &self.without_components)
+            .field("any_of_components", &self.any_of_components)
+            .field("optional_components", &self.optional_components)
+            .field("added_components", &self.added_components)
+            .field("changed_components", &self.changed_components)
+            .field("access", &self.access)
+            .finish()
+    }
+}
+
+impl<D: QueryData> PartialEq for Query<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.with_components == other.with_components
+            && self.without_components == other.without_components
+            && self.any_of_components == other.any_of_components
+            && self.added_components == other.added_components
+            && self.changed_components == other.changed_components
+    }
+}
+
+impl<D: QueryData> Eq for Query<D> {}
+
+impl<D: QueryData> Hash for Query<D> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.with_components.hash(state);
+        self.without_components.hash(state);
+        self.any_of_components.hash(state);
+        self.added_components.hash(state);
+        self.changed_components.hash(state);
+    }
+}
+
+/// A single Entity matched by a `Query`, providing access to its `Component`s.
 #[derive(Clone)]
-pub struct QueryResult {
-    entity_id: u32,
-    component_id_to_component: HashMap<u64, Arc<RwLock<dyn Component>>>,
+pub struct QueryResult<'a> {
+    entity_id: Entity,
+    archetype: &'a Archetype,
+    row: usize,
+    change_tick: u64,
 }
 
-impl QueryResult {
-    pub fn new(entity_id: u32) -> Self {
+impl<'a> QueryResult<'a> {
+    pub(crate) fn new(
+        entity_id: Entity,
+        archetype: &'a Archetype,
+        row: usize,
+        change_tick: u64,
+    ) -> Self {
         QueryResult {
             entity_id,
-            component_id_to_component: HashMap::new(),
+            archetype,
+            row,
+            change_tick,
         }
     }
 
-    pub fn entity_id(&self) -> u32 {
+    pub fn entity_id(&self) -> Entity {
         self.entity_id
     }
 
-    pub(crate) fn add_component(
-        &mut self,
-        component: Arc<RwLock<dyn Component>>,
-    ) -> &mut QueryResult {
-        self.component_id_to_component.insert(
-            component.read().unwrap().self_property_id(),
-            component.clone(),
-        );
-        self
+    /// Returns `true` if the Entity has a `Component` of the provided type.
+    pub fn has_component<T: 'static + Component>(&self) -> bool {
+        self.archetype.contains_component(&T::property_id())
     }
 
-    pub fn get_component<T: 'static + Component>(&self) -> Option<Arc<RwLock<T>>> {
-        self.component_id_to_component
-            .get(&T::property_id())
-            .map(|component| unsafe {
-                Arc::from_raw(Arc::into_raw(component.clone()) as *const RwLock<T>)
-            })
+    /// Retrieves shared access to a `Component` of the provided type. Returns `Some(component)` if
+    /// the Entity has one, `None` otherwise.
+    ///
+    /// Note: blocks while any `ComponentMut` of the same type is held for an Entity with the same
+    /// set of `Component`s.
+    pub fn get_component<T: 'static + Component>(&self) -> Option<ComponentRef<'a, T>> {
+        self.archetype
+            .get_column(&T::property_id())
+            .map(|column| ComponentRef::new(column.read().unwrap(), self.row))
+    }
+
+    /// Retrieves exclusive access to a `Component` of the provided type. Returns
+    /// `Some(component)` if the Entity has one, `None` otherwise. The `Component` is marked as
+    /// changed.
+    ///
+    /// Note: blocks while any `ComponentRef` or `ComponentMut` of the same type is held for an
+    /// Entity with the same set of `Component`s.
+    pub fn get_component_mut<T: 'static + Component>(&self) -> Option<ComponentMut<'a, T>> {
+        self.archetype
+            .get_column(&T::property_id())
+            .map(|column| ComponentMut::new(column.write().unwrap(), self.row, self.change_tick))
+    }
+}
+
+/// Inserts `value` into the sorted `values`, unless already present.
+fn insert_sorted<T: Ord>(values: &mut Vec<T>, value: T) {
+    if let Err(index) = values.binary_search(&value) {
+        values.insert(index, value);
     }
 }
@@ -0,0 +1,141 @@
+use std::slice::{Iter, IterMut};
+
+use crate::entity::{Archetype, ArchetypeLocks, Query, QueryData};
+
+/// The result of executing a typed `Query` via `EntityManager::query`.
+///
+/// A `QueryBorrow` locks the relevant `Component` columns of every matched archetype for as long as
+/// it is alive: shared for `&T`, exclusive for `&mut T`. Locks are always acquired in the same
+/// order, so concurrently executed queries cannot deadlock against each other, but other access
+/// to exclusively borrowed `Component` types (e.g. via `QueryResult::get_component`) will block
+/// until this is dropped.
+///
+/// Change detection filters (`Query::added` / `Query::changed`) are evaluated once, up front.
+pub struct QueryBorrow<'w, D: QueryData> {
+    archetypes: Vec<ArchetypeBorrow<'w, D>>,
+}
+
+/// The locked state of a single matched archetype, and the rows that pass the `Query`'s change
+/// detection filters (`None` if it has none).
+struct ArchetypeBorrow<'w, D: QueryData> {
+    archetype: &'w Archetype,
+    state: D::State<'w>,
+    rows: Option<Vec<usize>>,
+}
+
+impl<'w, D: QueryData> QueryBorrow<'w, D> {
+    pub(crate) fn new<I>(archetypes: I, query: &Query<D>, last_run: u64, this_run: u64) -> Self
+    where
+        I: Iterator<Item = &'w Archetype>,
+    {
+        QueryBorrow {
+            archetypes: archetypes
+                .map(|archetype| {
+                    let mut locks =
+                        ArchetypeLocks::acquire(archetype, query.get_access(), this_run);
+                    let rows = query.has_change_filters().then(|| {
+                        (0..archetype.get_entities().len())
+                            .filter(|row| {
+                                query.matches_ticks(last_run, |component_id| {
+                                    locks.get_ticks(component_id, *row).unwrap()
+                                })
+                            })
+                            .collect()
+                    });
+                    ArchetypeBorrow {
+                        archetype,
+                        state: D::take_state(&mut locks),
+                        rows,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Iterates over the fetched data of every matched Entity.
+    pub fn iter(&mut self) -> QueryIter<'_, 'w, D> {
+        QueryIter {
+            archetypes: self.archetypes.iter_mut(),
+            current: None,
+        }
+    }
+
+    /// Invokes `f` with the fetched data of every matched Entity.
+    pub fn for_each<F>(mut self, f: F)
+    where
+        F: FnMut(D::Item<'_>),
+    {
+        self.iter().for_each(f);
+    }
+
+    /// The number of matched Entities.
+    pub fn len(&self) -> usize {
+        self.archetypes
+            .iter()
+            .map(|borrow| match &borrow.rows {
+                Some(rows) => rows.len(),
+                None => borrow.archetype.get_entities().len(),
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a, 'w, D: QueryData> IntoIterator for &'a mut QueryBorrow<'w, D> {
+    type Item = D::Item<'a>;
+    type IntoIter = QueryIter<'a, 'w, D>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterates over the data fetched by a `QueryBorrow`, one archetype at a time.
+pub struct QueryIter<'a, 'w, D: QueryData> {
+    archetypes: IterMut<'a, ArchetypeBorrow<'w, D>>,
+    current: Option<ArchetypeIter<'a, D>>,
+}
+
+/// Iterates over a single archetype, skipping rows that don't pass the change detection filters.
+struct ArchetypeIter<'a, D: QueryData> {
+    iter: D::Iter<'a>,
+    rows: Option<Iter<'a, usize>>,
+    next_row: usize,
+}
+
+impl<'a, D: QueryData> Iterator for ArchetypeIter<'a, D> {
+    type Item = D::Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.rows.as_mut() {
+            None => self.iter.next(),
+            Some(rows) => {
+                let row = *rows.next()?;
+                let item = self.iter.nth(row - self.next_row);
+                self.next_row = row + 1;
+                item
+            }
+        }
+    }
+}
+
+impl<'a, D: QueryData> Iterator for QueryIter<'a, '_, D> {
+    type Item = D::Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.current.as_mut().and_then(|current| current.next()) {
+                return Some(item);
+            }
+            let borrow = self.archetypes.next()?;
+            self.current = Some(ArchetypeIter {
+                iter: D::iter(&mut borrow.state, borrow.archetype.get_entities()),
+                rows: borrow.rows.as_ref().map(|rows| rows.iter()),
+                next_row: 0,
+            });
+        }
+    }
+}
@@ -1,81 +1,426 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use crate::component::ComponentBundle;
-use crate::entity::{Query, QueryResult};
+use crate::entity::{Entity, Query, Signature};
+
+/// The number of unpinned queries the `QueryCache` holds by default, before evicting the least
+/// recently used one.
+pub const DEFAULT_QUERY_CACHE_CAPACITY: usize = 64;
+
+/// A snapshot of the `EntityManager`'s query cache counters, as returned by
+/// `EntityManager::get_query_cache_stats`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueryCacheStats {
+    /// Untyped queries answered from the cache.
+    pub hits: u64,
+    /// Untyped queries that had to be executed over all archetypes, and were then cached.
+    pub misses: u64,
+    /// Queries evicted to keep the cache within its capacity.
+    pub evictions: u64,
+    /// Cached queries re-evaluated or updated due to Entities being created, deleted, or having
+    /// `Component`s added or removed. This is the cost the cache adds to every such mutation.
+    pub maintenance: u64,
+    /// Queries currently cached, including pinned ones.
+    pub cached_queries: usize,
+    /// Queries currently pinned, which are never evicted.
+    pub pinned_queries: usize,
+}
+
+/// The `Component` requirements of a cached `Query`, as `Signature`s.
+struct QuerySignature {
+    with: Signature,
+    without: Signature,
+    any_of: Vec<Signature>,
+}
+
+impl QuerySignature {
+    fn matches(&self, signature: &Signature) -> bool {
+        signature.contains_all(&self.with)
+            && !signature.intersects(&self.without)
+            && self.any_of.iter().all(|group| signature.intersects(group))
+    }
+}
+
+/// A cached `Query`, alongside the Entities matching it and their position in `entities`.
+struct CachedQuery {
+    query: Query,
+    component_ids: Vec<u64>,
+    signature: QuerySignature,
+    entities: Vec<Entity>,
+    entity_rows: HashMap<Entity, usize>,
+    pinned: bool,
+    last_used: AtomicU64,
+}
+
+impl CachedQuery {
+    fn insert(&mut self, entity_id: &Entity) {
+        self.entity_rows.insert(*entity_id, self.entities.len());
+        self.entities.push(*entity_id);
+    }
+
+    fn remove(&mut self, entity_id: &Entity) {
+        if let Some(row) = self.entity_rows.remove(entity_id) {
+            self.entities.swap_remove(row);
+            if let Some(moved_entity_id) = self.entities.get(row) {
+                self.entity_rows.insert(*moved_entity_id, row);
+            }
+        }
+    }
+}
 
 /// Caches Entity queries to improve lookup speed.
-#[derive(Default)]
+///
+/// Every `Component` referenced by a cached `Query` is assigned a bit, so that matching an Entity
+/// against a `Query` is a handful of bitset operations. Cached queries are indexed by the
+/// `Component`s they reference, and Entities by the cached queries they match, so keeping the
+/// cache up to date only touches the queries whose result may actually change.
+///
+/// At most `capacity` unpinned queries are cached; beyond that, the least recently used one is
+/// evicted. Pinned queries do not count towards the capacity.
 pub(crate) struct QueryCache {
-    query_cache: HashMap<Query, Vec<QueryResult>>,
+    queries: Vec<Option<CachedQuery>>,
+    free_query_ids: Vec<usize>,
+    query_ids: HashMap<Query, usize>,
+    component_bits: HashMap<u64, usize>,
+    component_queries: HashMap<u64, Vec<usize>>,
+    unconditional_queries: Vec<usize>,
+    entity_queries: HashMap<Entity, Vec<usize>>,
+    capacity: usize,
+    pinned_count: usize,
+    clock: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: u64,
+    maintenance: u64,
+}
+
+impl Default for QueryCache {
+    fn default() -> Self {
+        QueryCache::new()
+    }
 }
 
 impl QueryCache {
     pub fn new() -> Self {
         QueryCache {
-            query_cache: HashMap::new(),
+            queries: Vec::new(),
+            free_query_ids: Vec::new(),
+            query_ids: HashMap::new(),
+            component_bits: HashMap::new(),
+            component_queries: HashMap::new(),
+            unconditional_queries: Vec::new(),
+            entity_queries: HashMap::new(),
+            capacity: DEFAULT_QUERY_CACHE_CAPACITY,
+            pinned_count: 0,
+            clock: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: 0,
+            maintenance: 0,
         }
     }
 
-    /// Checks the cache if a given `Query` is represented. Returns `Some(results)` if yes, `None`
-    /// if not.
-    pub fn check_cache(&self, query: &Query) -> Option<Vec<QueryResult>> {
-        return self.query_cache.get(query).map(|results| results.to_vec());
+    /// Checks the cache if a given `Query` is represented. Returns `Some(entities)` if yes, `None`
+    /// if not. Counts as a hit or a miss respectively.
+    pub fn check_cache(&self, query: &Query) -> Option<&[Entity]> {
+        match self.query_ids.get(query) {
+            Some(query_id) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                let cached_query = self.get_query(*query_id);
+                cached_query.last_used.store(self.tick(), Ordering::Relaxed);
+                Some(cached_query.entities.as_slice())
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
     }
 
-    /// Adds a particular `Query` and `Results` to the cache.
-    ///
-    /// Note: will panic if the `Query` is already represented.
-    pub fn add_to_cache(&mut self, entry: (Query, Vec<QueryResult>)) {
-        if self.query_cache.contains_key(&entry.0) {
-            panic!(
-                "Query cache already contains the provided Query: {:?}!",
-                entry.0
-            );
+    /// Adds a particular `Query` and the Entities matching it to the cache, evicting the least
+    /// recently used unpinned `Query` if the cache is full. Does nothing if the `Query` is already
+    /// represented, as its cached Entities are kept up to date.
+    pub fn add_to_cache(&mut self, entry: (Query, Vec<Entity>)) {
+        let (query, entities) = entry;
+        if self.query_ids.contains_key(&query) || self.capacity == 0 {
+            return;
         }
-        self.query_cache.insert(entry.0, entry.1);
+
+        self.insert_query(query, entities, false);
+        self.evict_to_capacity();
     }
 
-    pub fn remove_entity_from_cache(&mut self, entity_id: &u32) {
-        self.query_cache.iter_mut().for_each(|(_query, results)| {
-            for i in 0..results.len() {
-                if results[i].entity_id() == *entity_id {
-                    results.swap_remove(i);
-                    break;
+    /// Pins a `Query`, so that it is never evicted. If the `Query` is not yet cached, it is added
+    /// with the Entities returned by `get_entities`.
+    pub fn pin_query<F>(&mut self, query: Query, get_entities: F)
+    where
+        F: FnOnce() -> Vec<Entity>,
+    {
+        match self.query_ids.get(&query) {
+            Some(query_id) => {
+                let cached_query = self.queries[*query_id].as_mut().unwrap();
+                if !cached_query.pinned {
+                    cached_query.pinned = true;
+                    self.pinned_count += 1;
                 }
             }
-        });
+            None => self.insert_query(query, get_entities(), true),
+        }
     }
 
-    /// Updates the cache given an Entity and *all* of its `Component`s.
-    /// Note that this process grows in cost based on the number of queries in the cache.
-    pub fn update_cache(&mut self, entity_id: &u32, all_components: &ComponentBundle) {
-        self.remove_entity_from_cache(entity_id);
-        if all_components.get_components().is_empty() {
-            return;
+    /// Unpins a `Query`, making it subject to eviction again. Returns `false` if the `Query` was
+    /// not pinned.
+    pub fn unpin_query(&mut self, query: &Query) -> bool {
+        let Some(query_id) = self.query_ids.get(query) else {
+            return false;
+        };
+        let cached_query = self.queries[*query_id].as_mut().unwrap();
+        if !cached_query.pinned {
+            return false;
+        }
+        cached_query.pinned = false;
+        self.pinned_count -= 1;
+        self.evict_to_capacity();
+        true
+    }
+
+    /// Removes all unpinned queries from the cache.
+    pub fn clear(&mut self) {
+        let query_ids: Vec<usize> = self
+            .query_ids
+            .values()
+            .copied()
+            .filter(|query_id| !self.get_query(*query_id).pinned)
+            .collect();
+        query_ids
+            .into_iter()
+            .for_each(|query_id| self.remove_query(query_id));
+    }
+
+    /// Sets the number of unpinned queries held, evicting the least recently used ones if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.evict_to_capacity();
+    }
+
+    pub fn get_stats(&self) -> QueryCacheStats {
+        QueryCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions,
+            maintenance: self.maintenance,
+            cached_queries: self.query_ids.len(),
+            pinned_queries: self.pinned_count,
         }
+    }
 
-        self.query_cache.iter_mut().for_each(|(query, results)| {
-            let contains_all_with = query
-                .get_with_components()
+    /// Adds a new Entity, without any `Component`s, to all cached queries matching it.
+    pub fn insert_entity(&mut self, entity_id: &Entity) {
+        let signature = Signature::new();
+        let query_ids = self.unconditional_queries.clone();
+        self.update_entity_queries(entity_id, &signature, query_ids);
+    }
+
+    pub fn remove_entity_from_cache(&mut self, entity_id: &Entity) {
+        if let Some(query_ids) = self.entity_queries.remove(entity_id) {
+            self.maintenance += query_ids.len() as u64;
+            query_ids
                 .iter()
-                .all(|component_id| all_components.get_components().contains_key(component_id));
-            let contains_any_without = query
-                .get_without_components()
+                .for_each(|query_id| self.queries[*query_id].as_mut().unwrap().remove(entity_id));
+        }
+    }
+
+    /// Updates the cache given an Entity whose (sorted) `Component` ids changed from
+    /// `old_component_ids` to `component_ids`. Only cached queries referencing one of the added or
+    /// removed `Component`s are re-evaluated.
+    pub fn update_cache(
+        &mut self,
+        entity_id: &Entity,
+        old_component_ids: &[u64],
+        component_ids: &[u64],
+    ) {
+        let mut query_ids: Vec<usize> = symmetric_difference(old_component_ids, component_ids)
+            .filter_map(|component_id| self.component_queries.get(component_id))
+            .flatten()
+            .copied()
+            .collect();
+        if query_ids.is_empty() {
+            return;
+        }
+        query_ids.sort_unstable();
+        query_ids.dedup();
+
+        let signature = self.get_entity_signature(component_ids);
+        self.update_entity_queries(entity_id, &signature, query_ids);
+    }
+
+    /// Re-evaluates whether the Entity with the provided `Signature` matches each of `query_ids`.
+    fn update_entity_queries(
+        &mut self,
+        entity_id: &Entity,
+        signature: &Signature,
+        query_ids: Vec<usize>,
+    ) {
+        self.maintenance += query_ids.len() as u64;
+        let memberships = self.entity_queries.entry(*entity_id).or_default();
+        query_ids.into_iter().for_each(|query_id| {
+            let query = self.queries[query_id].as_mut().unwrap();
+            let is_member = query.entity_rows.contains_key(entity_id);
+            match (query.signature.matches(signature), is_member) {
+                (true, false) => {
+                    query.insert(entity_id);
+                    memberships.push(query_id);
+                }
+                (false, true) => {
+                    query.remove(entity_id);
+                    memberships.retain(|member_query_id| *member_query_id != query_id);
+                }
+                _ => {}
+            }
+        });
+        if memberships.is_empty() {
+            self.entity_queries.remove(entity_id);
+        }
+    }
+
+    fn insert_query(&mut self, query: Query, entities: Vec<Entity>, pinned: bool) {
+        let query_id = self.free_query_ids.pop().unwrap_or(self.queries.len());
+        let signature = QuerySignature {
+            with: self.get_signature(query.get_with_components()),
+            without: self.get_signature(query.get_without_components()),
+            any_of: query
+                .get_any_of_components()
                 .iter()
-                .any(|component_id| all_components.get_components().contains_key(component_id));
-            if contains_all_with && !contains_any_without {
-                let mut result = QueryResult::new(*entity_id);
-                query.get_with_components().iter().for_each(|component_id| {
-                    result.add_component(
-                        all_components
-                            .get_components()
-                            .get(component_id)
-                            .unwrap()
-                            .clone(),
-                    );
-                });
-                results.push(result)
+                .map(|group| self.get_signature(group))
+                .collect(),
+        };
+        let mut component_ids: Vec<u64> = query
+            .get_with_components()
+            .iter()
+            .chain(query.get_without_components())
+            .chain(query.get_any_of_components().iter().flatten())
+            .copied()
+            .collect();
+        component_ids.sort_unstable();
+        component_ids.dedup();
+        component_ids.iter().for_each(|component_id| {
+            self.component_queries
+                .entry(*component_id)
+                .or_default()
+                .push(query_id);
+        });
+        if signature.matches(&Signature::new()) {
+            self.unconditional_queries.push(query_id);
+        }
+
+        let mut cached_query = CachedQuery {
+            query: query.clone(),
+            component_ids,
+            signature,
+            entities: Vec::with_capacity(entities.len()),
+            entity_rows: HashMap::with_capacity(entities.len()),
+            pinned,
+            last_used: AtomicU64::new(self.tick()),
+        };
+        entities.iter().for_each(|entity_id| {
+            cached_query.insert(entity_id);
+            self.entity_queries
+                .entry(*entity_id)
+                .or_default()
+                .push(query_id);
+        });
+        if query_id == self.queries.len() {
+            self.queries.push(Some(cached_query));
+        } else {
+            self.queries[query_id] = Some(cached_query);
+        }
+        self.query_ids.insert(query, query_id);
+        if pinned {
+            self.pinned_count += 1;
+        }
+    }
+
+    /// Removes a cached `Query` and every index entry referring to it.
+    fn remove_query(&mut self, query_id: usize) {
+        let cached_query = self.queries[query_id].take().unwrap();
+        self.query_ids.remove(&cached_query.query);
+        cached_query.component_ids.iter().for_each(|component_id| {
+            if let Some(query_ids) = self.component_queries.get_mut(component_id) {
+                query_ids.retain(|other_query_id| *other_query_id != query_id);
             }
         });
+        self.unconditional_queries
+            .retain(|other_query_id| *other_query_id != query_id);
+        cached_query.entities.iter().for_each(|entity_id| {
+            if let Some(query_ids) = self.entity_queries.get_mut(entity_id) {
+                query_ids.retain(|other_query_id| *other_query_id != query_id);
+                if query_ids.is_empty() {
+                    self.entity_queries.remove(entity_id);
+                }
+            }
+        });
+        if cached_query.pinned {
+            self.pinned_count -= 1;
+        }
+        self.free_query_ids.push(query_id);
+    }
+
+    /// Evicts least recently used unpinned queries until at most `capacity` of them remain.
+    fn evict_to_capacity(&mut self) {
+        while self.query_ids.len() - self.pinned_count > self.capacity {
+            let least_recently_used = self
+                .query_ids
+                .values()
+                .copied()
+                .filter(|query_id| !self.get_query(*query_id).pinned)
+                .min_by_key(|query_id| self.get_query(*query_id).last_used.load(Ordering::Relaxed));
+            match least_recently_used {
+                Some(query_id) => {
+                    self.remove_query(query_id);
+                    self.evictions += 1;
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn get_query(&self, query_id: usize) -> &CachedQuery {
+        self.queries[query_id].as_ref().unwrap()
+    }
+
+    /// Advances the clock used to determine the least recently used `Query`.
+    fn tick(&self) -> u64 {
+        self.clock.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Returns the `Signature` of the provided `Component` ids, assigning bits as needed.
+    fn get_signature(&mut self, component_ids: &[u64]) -> Signature {
+        let mut signature = Signature::new();
+        component_ids.iter().for_each(|component_id| {
+            let next_bit = self.component_bits.len();
+            signature.insert(*self.component_bits.entry(*component_id).or_insert(next_bit));
+        });
+        signature
+    }
+
+    /// Returns the `Signature` of an Entity with the provided `Component` ids. `Component`s not
+    /// referenced by any cached `Query` are irrelevant, and left out.
+    fn get_entity_signature(&self, component_ids: &[u64]) -> Signature {
+        let mut signature = Signature::new();
+        component_ids
+            .iter()
+            .filter_map(|component_id| self.component_bits.get(component_id))
+            .for_each(|bit| signature.insert(*bit));
+        signature
     }
 }
+
+/// Returns the ids present in exactly one of the two sorted slices.
+fn symmetric_difference<'a>(a: &'a [u64], b: &'a [u64]) -> impl Iterator<Item = &'a u64> {
+    a.iter()
+        .filter(|component_id| b.binary_search(component_id).is_err())
+        .chain(
+            b.iter()
+                .filter(|component_id| a.binary_search(component_id).is_err()),
+        )
+}
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+use std::iter::{Copied, RepeatN};
+use std::ops::Range;
+use std::slice::IterMut;
+use std::sync::{RwLockReadGuard, RwLockWriteGuard};
+
+use crate::component::{Component, ComponentAccess, ComponentColumn, ComponentTicks};
+use crate::entity::{Archetype, Entity};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Describes what a typed `Query` fetches for every matched Entity, and by extension which
+/// `Component`s an Entity must have to be matched.
+///
+/// Implemented for:
+/// *   `&T`: shared access to a required `Component`.
+/// *   `&mut T`: exclusive access to a required `Component`.
+/// *   `Option<&T>` / `Option<&mut T>`: access to a `Component` if the Entity has it, without
+///     filtering on it.
+/// *   `Entity`: the handle of the matched Entity.
+/// *   `()` and tuples of up to 8 of the above.
+pub trait QueryData: sealed::Sealed {
+    /// The value yielded for each matched Entity.
+    type Item<'a>;
+
+    #[doc(hidden)]
+    type State<'w>;
+
+    #[doc(hidden)]
+    type Iter<'a>: Iterator<Item = Self::Item<'a>>;
+
+    /// Records the `Component`s accessed. Will panic if the same `Component` type is accessed more
+    /// than once, as that would hand out aliasing references.
+    #[doc(hidden)]
+    fn add_access(access: &mut ComponentAccess);
+
+    /// Records the `Component`s an Entity must have to be matched.
+    #[doc(hidden)]
+    fn add_required_components(component_ids: &mut Vec<u64>);
+
+    #[doc(hidden)]
+    fn take_state<'w>(locks: &mut ArchetypeLocks<'w>) -> Self::State<'w>;
+
+    #[doc(hidden)]
+    fn iter<'a>(state: &'a mut Self::State<'_>, entities: &'a [Entity]) -> Self::Iter<'a>;
+}
+
+/// Column locks for a single archetype, acquired up front in ascending `Component` id order so
+/// that concurrent queries cannot deadlock against each other. `Component`s fetched mutably are
+/// marked as changed at `change_tick`.
+#[doc(hidden)]
+pub struct ArchetypeLocks<'w> {
+    reads: HashMap<u64, ColumnReadGuard<'w>>,
+    writes: HashMap<u64, ColumnWriteGuard<'w>>,
+}
+
+impl<'w> ArchetypeLocks<'w> {
+    pub(crate) fn acquire(
+        archetype: &'w Archetype,
+        access: &ComponentAccess,
+        change_tick: u64,
+    ) -> Self {
+        let mut locks = ArchetypeLocks {
+            reads: HashMap::new(),
+            writes: HashMap::new(),
+        };
+        access.iter().for_each(|(component_id, write)| {
+            if let Some(column) = archetype.get_column(&component_id) {
+                if write {
+                    locks.writes.insert(
+                        component_id,
+                        ColumnWriteGuard(column.write().unwrap(), change_tick),
+                    );
+                } else {
+                    locks
+                        .reads
+                        .insert(component_id, ColumnReadGuard(column.read().unwrap()));
+                }
+            }
+        });
+        locks
+    }
+
+    /// Returns the `ComponentTicks` of the `Component` in `row`, if its column is locked.
+    pub(crate) fn get_ticks(&self, component_id: &u64, row: usize) -> Option<ComponentTicks> {
+        match self.reads.get(component_id) {
+            Some(guard) => Some(guard.0.get_ticks(row)),
+            None => self
+                .writes
+                .get(component_id)
+                .map(|guard| guard.0.get_ticks(row)),
+        }
+    }
+
+    pub(crate) fn take_read(&mut self, component_id: &u64) -> Option<ColumnReadGuard<'w>> {
+        self.reads.remove(component_id)
+    }
+
+    pub(crate) fn take_write(&mut self, component_id: &u64) -> Option<ColumnWriteGuard<'w>> {
+        self.writes.remove(component_id)
+    }
+}
+
+/// A shared lock over a single `Component` column.
+#[doc(hidden)]
+pub struct ColumnReadGuard<'w>(pub(crate) RwLockReadGuard<'w, Box<dyn ComponentColumn>>);
+
+/// An exclusive lock over a single `Component` column, alongside the tick to mark fetched
+/// `Component`s as changed at.
+#[doc(hidden)]
+pub struct ColumnWriteGuard<'w>(
+    pub(crate) RwLockWriteGuard<'w, Box<dyn ComponentColumn>>,
+    pub(crate) u64,
+);
+
+/// Yields exclusive references to `Component`s, marking each yielded `Component` as changed.
+/// Skipped `Component`s (see `Iterator::nth`) are left untouched.
+#[doc(hidden)]
+pub struct ChangeTrackingIter<'a, T> {
+    components: IterMut<'a, T>,
+    ticks: IterMut<'a, ComponentTicks>,
+    change_tick: u64,
+}
+
+impl<'a, T> Iterator for ChangeTrackingIter<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let component = self.components.nth(n)?;
+        self.ticks.nth(n).unwrap().set_changed(self.change_tick);
+        Some(component)
+    }
+}
+
+/// Yields `Some` for every item of the wrapped iterator, or `None` a fixed number of times if the
+/// `Component` is absent from the archetype.
+#[doc(hidden)]
+pub enum OptionalIter<I> {
+    Present(I),
+    Absent(Range<usize>),
+}
+
+impl<I: Iterator> Iterator for OptionalIter<I> {
+    type Item = Option<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            OptionalIter::Present(iter) => iter.next().map(Some),
+            OptionalIter::Absent(range) => range.next().map(|_| None),
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        match self {
+            OptionalIter::Present(iter) => iter.nth(n).map(Some),
+            OptionalIter::Absent(range) => range.nth(n).map(|_| None),
+        }
+    }
+}
+
+/// Advances a tuple of iterators in lockstep. Skipping (see `Iterator::nth`) is forwarded to every
+/// iterator, so that no `Component` is marked as changed unless it is yielded.
+#[doc(hidden)]
+pub struct TupleIter<T>(T);
+
+pub(crate) fn add_access_once(access: &mut ComponentAccess, component_id: u64, name: &'static str) {
+    if access.reads(&component_id) || access.writes(&component_id) {
+        panic!("Query accesses Component: {} more than once!", name);
+    }
+}
+
+impl sealed::Sealed for () {}
+
+impl QueryData for () {
+    type Item<'a> = ();
+    type State<'w> = ();
+    type Iter<'a> = RepeatN<()>;
+
+    fn add_access(_access: &mut ComponentAccess) {}
+
+    fn add_required_components(_component_ids: &mut Vec<u64>) {}
+
+    fn take_state<'w>(_locks: &mut ArchetypeLocks<'w>) -> Self::State<'w> {}
+
+    fn iter<'a>(_state: &'a mut Self::State<'_>, entities: &'a [Entity]) -> Self::Iter<'a> {
+        std::iter::repeat_n((), entities.len())
+    }
+}
+
+impl sealed::Sealed for Entity {}
+
+impl QueryData for Entity {
+    type Item<'a> = Entity;
+    type State<'w> = ();
+    type Iter<'a> = Copied<std::slice::Iter<'a, Entity>>;
+
+    fn add_access(_access: &mut ComponentAccess) {}
+
+    fn add_required_components(_component_ids: &mut Vec<u64>) {}
+
+    fn take_state<'w>(_locks: &mut ArchetypeLocks<'w>) -> Self::State<'w> {}
+
+    fn iter<'a>(_state: &'a mut Self::State<'_>, entities: &'a [Entity]) -> Self::Iter<'a> {
+        entities.iter().copied()
+    }
+}
+
+impl<T: 'static + Component> sealed::Sealed for &T {}
+
+impl<T: 'static + Component> QueryData for &T {
+    type Item<'a> = &'a T;
+    type State<'w> = ColumnReadGuard<'w>;
+    type Iter<'a> = std::slice::Iter<'a, T>;
+
+    fn add_access(access: &mut ComponentAccess) {
+        add_access_once(access, T::property_id(), T::property_string());
+        access.add_read::<T>();
+    }
+
+    fn add_required_components(component_ids: &mut Vec<u64>) {
+        component_ids.push(T::property_id());
+    }
+
+    fn take_state<'w>(locks: &mut ArchetypeLocks<'w>) -> Self::State<'w> {
+        locks.take_read(&T::property_id()).unwrap()
+    }
+
+    fn iter<'a>(state: &'a mut Self::State<'_>, _entities: &'a [Entity]) -> Self::Iter<'a> {
+        state.0.typed::<T>().as_slice().iter()
+    }
+}
+
+impl<T: 'static + Component> sealed::Sealed for &mut T {}
+
+impl<T: 'static + Component> QueryData for &mut T {
+    type Item<'a> = &'a mut T;
+    type State<'w> = ColumnWriteGuard<'w>;
+    type Iter<'a> = ChangeTrackingIter<'a, T>;
+
+    fn add_access(access: &mut ComponentAccess) {
+        add_access_once(access, T::property_id(), T::property_string());
+        access.add_write::<T>();
+    }
+
+    fn add_required_components(component_ids: &mut Vec<u64>) {
+        component_ids.push(T::property_id());
+    }
+
+    fn take_state<'w>(locks: &mut ArchetypeLocks<'w>) -> Self::State<'w> {
+        locks.take_write(&T::property_id()).unwrap()
+    }
+
+    fn iter<'a>(state: &'a mut Self::State<'_>, _entities: &'a [Entity]) -> Self::Iter<'a> {
+        let (components, ticks) = state.0.typed_mut::<T>().as_mut_slices();
+        ChangeTrackingIter {
+            components: components.iter_mut(),
+            ticks: ticks.iter_mut(),
+            change_tick: state.1,
+        }
+    }
+}
+
+impl<T: 'static + Component> sealed::Sealed for Option<&T> {}
+
+impl<T: 'static + Component> QueryData for Option<&T> {
+    type Item<'a> = Option<&'a T>;
+    type State<'w> = Option<ColumnReadGuard<'w>>;
+    type Iter<'a> = OptionalIter<std::slice::Iter<'a, T>>;
+
+    fn add_access(access: &mut ComponentAccess) {
+        <&T>::add_access(access);
+    }
+
+    fn add_required_components(_component_ids: &mut Vec<u64>) {}
+
+    fn take_state<'w>(locks: &mut ArchetypeLocks<'w>) -> Self::State<'w> {
+        locks.take_read(&T::property_id())
+    }
+
+    fn iter<'a>(state: &'a mut Self::State<'_>, entities: &'a [Entity]) -> Self::Iter<'a> {
+        match state {
+            Some(state) => OptionalIter::Present(<&T>::iter(state, entities)),
+            None => OptionalIter::Absent(0..entities.len()),
+        }
+    }
+}
+
+impl<T: 'static + Component> sealed::Sealed for Option<&mut T> {}
+
+impl<T: 'static + Component> QueryData for Option<&mut T> {
+    type Item<'a> = Option<&'a mut T>;
+    type State<'w> = Option<ColumnWriteGuard<'w>>;
+    type Iter<'a> = OptionalIter<ChangeTrackingIter<'a, T>>;
+
+    fn add_access(access: &mut ComponentAccess) {
+        <&mut T>::add_access(access);
+    }
+
+    fn add_required_components(_component_ids: &mut Vec<u64>) {}
+
+    fn take_state<'w>(locks: &mut ArchetypeLocks<'w>) -> Self::State<'w> {
+        locks.take_write(&T::property_id())
+    }
+
+    fn iter<'a>(state: &'a mut Self::State<'_>, entities: &'a [Entity]) -> Self::Iter<'a> {
+        match state {
+            Some(state) => OptionalIter::Present(<&mut T>::iter(state, entities)),
+            None => OptionalIter::Absent(0..entities.len()),
+        }
+    }
+}
+
+macro_rules! impl_query_data_for_tuple {
+    ($(($name:ident, $index:tt)),+) => {
+        impl<$($name: QueryData),+> sealed::Sealed for ($($name,)+) {}
+
+        impl<$($name: QueryData),+> QueryData for ($($name,)+) {
+            type Item<'a> = ($($name::Item<'a>,)+);
+            type State<'w> = ($($name::State<'w>,)+);
+            type Iter<'a> = TupleIter<($($name::Iter<'a>,)+)>;
+
+            fn add_access(access: &mut ComponentAccess) {
+                $($name::add_access(access);)+
+            }
+
+            fn add_required_components(component_ids: &mut Vec<u64>) {
+                $($name::add_required_components(component_ids);)+
+            }
+
+            fn take_state<'w>(locks: &mut ArchetypeLocks<'w>) -> Self::State<'w> {
+                ($($name::take_state(locks),)+)
+            }
+
+            fn iter<'a>(state: &'a mut Self::State<'_>, entities: &'a [Entity]) -> Self::Iter<'a> {
+                TupleIter(($($name::iter(&mut state.$index, entities),)+))
+            }
+        }
+
+        impl<$($name: Iterator),+> Iterator for TupleIter<($($name,)+)> {
+            type Item = ($($name::Item,)+);
+
+            fn next(&mut self) -> Option<Self::Item> {
+                Some(($(self.0.$index.next()?,)+))
+            }
+
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                Some(($(self.0.$index.nth(n)?,)+))
+            }
+        }
+    };
+}
+
+impl_query_data_for_tuple!((A, 0));
+impl_query_data_for_tuple!((A, 0), (B, 1));
+impl_query_data_for_tuple!((A, 0), (B, 1), (C, 2));
+impl_query_data_for_tuple!((A, 0), (B, 1), (C, 2), (D, 3));
+impl_query_data_for_tuple!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4));
+impl_query_data_for_tuple!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5));
+impl_query_data_for_tuple!((A, 0), (B, 1), (C, 2), (D, 3), (E, 4), (F, 5), (G, 6));
+impl_query_data_for_tuple!(
+    (A, 0),
+    (B, 1),
+    (C, 2),
+    (D, 3),
+    (E, 4),
+    (F, 5),
+    (G, 6),
+    (H, 7)
+);
@@ -0,0 +1,89 @@
+use std::marker::PhantomData;
+
+use crate::component::{Component, ComponentSet};
+use crate::entity::{Query, QueryData};
+
+/// A filter added to a typed `Query` at compile time, e.g. by the `Filtered` argument of a function
+/// `System`. Mirrors the builder methods of `Query`:
+/// *   `With<T>` / `Without<T>`: see `Query::with` / `Query::without`.
+/// *   `AnyOf<S>`: see `Query::any_of`.
+/// *   `Added<T>` / `Changed<T>`: see `Query::added` / `Query::changed`.
+///
+/// Implemented for `()` and tuples of up to 8 filters, which must all be satisfied.
+pub trait QueryFilter: 'static {
+    /// Adds this filter to `query`.
+    fn apply<D: QueryData>(query: Query<D>) -> Query<D>;
+}
+
+/// Only matches Entities that have a `Component` of type `T`.
+pub struct With<T>(PhantomData<fn() -> T>);
+
+/// Only matches Entities that don't have a `Component` of type `T`.
+pub struct Without<T>(PhantomData<fn() -> T>);
+
+/// Only matches Entities that have at least one of the `Component`s in `S`.
+pub struct AnyOf<S>(PhantomData<fn() -> S>);
+
+/// Only matches Entities whose `Component` of type `T` was added since the currently executing
+/// `System` last ran.
+pub struct Added<T>(PhantomData<fn() -> T>);
+
+/// Only matches Entities whose `Component` of type `T` was added or mutably accessed since the
+/// currently executing `System` last ran.
+pub struct Changed<T>(PhantomData<fn() -> T>);
+
+impl<T: 'static + Component> QueryFilter for With<T> {
+    fn apply<D: QueryData>(query: Query<D>) -> Query<D> {
+        query.with::<T>()
+    }
+}
+
+impl<T: 'static + Component> QueryFilter for Without<T> {
+    fn apply<D: QueryData>(query: Query<D>) -> Query<D> {
+        query.without::<T>()
+    }
+}
+
+impl<S: 'static + ComponentSet> QueryFilter for AnyOf<S> {
+    fn apply<D: QueryData>(query: Query<D>) -> Query<D> {
+        query.any_of::<S>()
+    }
+}
+
+impl<T: 'static + Component> QueryFilter for Added<T> {
+    fn apply<D: QueryData>(query: Query<D>) -> Query<D> {
+        query.added::<T>()
+    }
+}
+
+impl<T: 'static + Component> QueryFilter for Changed<T> {
+    fn apply<D: QueryData>(query: Query<D>) -> Query<D> {
+        query.changed::<T>()
+    }
+}
+
+impl QueryFilter for () {
+    fn apply<D: QueryData>(query: Query<D>) -> Query<D> {
+        query
+    }
+}
+
+macro_rules! impl_query_filter_for_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: QueryFilter),+> QueryFilter for ($($name,)+) {
+            fn apply<Q: QueryData>(query: Query<Q>) -> Query<Q> {
+                $(let query = $name::apply(query);)+
+                query
+            }
+        }
+    };
+}
+
+impl_query_filter_for_tuple!(A);
+impl_query_filter_for_tuple!(A, B);
+impl_query_filter_for_tuple!(A, B, C);
+impl_query_filter_for_tuple!(A, B, C, D);
+impl_query_filter_for_tuple!(A, B, C, D, E);
+impl_query_filter_for_tuple!(A, B, C, D, E, F);
+impl_query_filter_for_tuple!(A, B, C, D, E, F, G);
+impl_query_filter_for_tuple!(A, B, C, D, E, F, G, H);
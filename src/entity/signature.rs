@@ -0,0 +1,36 @@
+/// A set of `Component`s, as a bitset over bit indices assigned by the `QueryCache`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Signature {
+    words: Vec<u64>,
+}
+
+impl Signature {
+    pub fn new() -> Self {
+        Signature { words: Vec::new() }
+    }
+
+    pub fn insert(&mut self, bit: usize) {
+        let word = bit / 64;
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        self.words[word] |= 1 << (bit % 64);
+    }
+
+    /// Returns `true` if every bit set in `other` is also set in `self`.
+    pub fn contains_all(&self, other: &Signature) -> bool {
+        other
+            .words
+            .iter()
+            .enumerate()
+            .all(|(index, word)| self.words.get(index).copied().unwrap_or(0) & word == *word)
+    }
+
+    /// Returns `true` if any bit is set in both `self` and `other`.
+    pub fn intersects(&self, other: &Signature) -> bool {
+        self.words
+            .iter()
+            .zip(other.words.iter())
+            .any(|(word, other_word)| word & other_word != 0)
+    }
+}
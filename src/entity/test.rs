@@ -1,10 +1,10 @@
 #![cfg(test)]
 
-use std::sync::{Arc, RwLock};
-
 use crate::component::{Component, ComponentBundle};
 use crate::entity::*;
+use crate::system::SystemContext;
 use crate::util::PropertyId;
+use crate::Error;
 
 #[derive(Component)]
 struct TestComponent;
@@ -12,36 +12,66 @@ struct TestComponent;
 #[derive(Component)]
 struct OtherTestComponent;
 
+#[derive(Component)]
+struct ValueComponent {
+    value: u32,
+}
+
 #[test]
 fn query_with_success() {
     let query = Query::new().with::<TestComponent>();
-    assert_eq!(query.get_with_components().len(), 1);
-    assert_eq!(
-        query.get_with_components().first().unwrap(),
-        &TestComponent::property_id()
-    );
+    assert!(query.matches(&[TestComponent::property_id()]));
+    assert!(!query.matches(&[]));
 }
 
 #[test]
 fn query_without_success() {
     let query = Query::new().without::<TestComponent>();
-    assert_eq!(query.get_without_components().len(), 1);
-    assert_eq!(
-        query.get_without_components().first().unwrap(),
-        &TestComponent::property_id()
-    );
+    assert!(!query.matches(&[TestComponent::property_id()]));
+    assert!(query.matches(&[]));
+}
+
+#[test]
+fn query_any_of_success() {
+    let query = Query::new()
+        .any_of::<(TestComponent, OtherTestComponent)>()
+        .without::<ValueComponent>();
+    assert!(query.matches(&[TestComponent::property_id()]));
+    assert!(query.matches(&[OtherTestComponent::property_id()]));
+    assert!(!query.matches(&[]));
+    let mut component_ids = vec![TestComponent::property_id(), ValueComponent::property_id()];
+    component_ids.sort_unstable();
+    assert!(!query.matches(&component_ids));
+    assert_ne!(query, Query::new().without::<ValueComponent>());
+}
+
+#[test]
+fn query_optional_success() {
+    let query = Query::new()
+        .with::<TestComponent>()
+        .optional::<ValueComponent>();
+    assert!(query.matches(&[TestComponent::property_id()]));
+    assert!(query.get_access().reads(&ValueComponent::property_id()));
+    assert_eq!(query, Query::new().with::<TestComponent>());
+
+    let query = Query::<&mut ValueComponent>::default().optional::<ValueComponent>();
+    assert!(query.get_access().writes(&ValueComponent::property_id()));
+    assert!(!query.get_access().reads(&ValueComponent::property_id()));
 }
 
 #[test]
 fn query_result_success() {
-    let entity_id = 1;
-    let entity_component = Arc::new(RwLock::new(TestComponent));
-    let mut result = QueryResult::new(entity_id);
-    result.add_component(entity_component.clone());
-    assert!(Arc::ptr_eq(
-        &result.get_component::<TestComponent>().unwrap(),
-        &entity_component
-    ));
+    let mut manager = EntityManager::new();
+    let entity_id =
+        manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 1 }));
+    let result = manager.get_all_components_for_entity(&entity_id).unwrap();
+    assert_eq!(result.entity_id(), entity_id);
+    assert!(result.has_component::<ValueComponent>());
+    assert!(!result.has_component::<TestComponent>());
+    assert!(result.get_component::<TestComponent>().is_none());
+
+    result.get_component_mut::<ValueComponent>().unwrap().value += 1;
+    assert_eq!(result.get_component::<ValueComponent>().unwrap().value, 2);
 }
 
 #[test]
@@ -69,18 +99,18 @@ fn entity_manager_create_entity_unique_id_success() {
 fn entity_manager_add_components_to_entity_success() {
     let mut manager = EntityManager::new();
     let entity_id = manager.create_entity(ComponentBundle::new());
-    assert!(manager
+    assert!(!manager
         .get_all_components_for_entity(&entity_id)
-        .get_components()
-        .is_empty());
+        .unwrap()
+        .has_component::<TestComponent>());
 
     manager.add_components_to_entity(
         &entity_id,
         ComponentBundle::new().add_component(TestComponent),
     );
-    let component_bundle = manager.get_all_components_for_entity(&entity_id);
-    assert_eq!(component_bundle.get_components().len(), 1);
-    assert!(component_bundle.get_component::<TestComponent>().is_some());
+    let result = manager.get_all_components_for_entity(&entity_id).unwrap();
+    assert!(result.has_component::<TestComponent>());
+    assert!(result.get_component::<TestComponent>().is_some());
 }
 
 #[test]
@@ -98,14 +128,13 @@ fn entity_manager_add_components_to_entity_already_registered_panic() {
 fn entity_manager_remove_component_from_entity_success() {
     let mut manager = EntityManager::new();
     let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
-    let component_bundle = manager.get_all_components_for_entity(&entity_id);
-    assert_eq!(component_bundle.get_components().len(), 1);
-    assert!(component_bundle.get_component::<TestComponent>().is_some());
+    let result = manager.get_all_components_for_entity(&entity_id).unwrap();
+    assert!(result.has_component::<TestComponent>());
 
     manager.remove_component_from_entity::<TestComponent>(&entity_id);
-    let component_bundle = manager.get_all_components_for_entity(&entity_id);
-    assert!(component_bundle.get_components().is_empty());
-    assert!(component_bundle.get_component::<TestComponent>().is_none());
+    let result = manager.get_all_components_for_entity(&entity_id).unwrap();
+    assert!(!result.has_component::<TestComponent>());
+    assert!(result.get_component::<TestComponent>().is_none());
 }
 
 #[test]
@@ -139,41 +168,157 @@ fn entity_manager_filter_without_success() {
 #[test]
 fn query_cache_success() {
     let mut cache = QueryCache::new();
-    let entity_id = 1;
-    let entity_component = Arc::new(RwLock::new(TestComponent));
+    let entity_id = Entity::new(1, 0);
 
     let query = Query::new().with::<TestComponent>();
-    let mut query_result = QueryResult::new(entity_id);
-    query_result.add_component(entity_component.clone());
-
-    cache.add_to_cache((query.clone(), vec![query_result.clone()]));
-    let query_result = cache.check_cache(&query).unwrap();
-    assert!(Arc::ptr_eq(
-        &query_result
-            .first()
-            .unwrap()
-            .get_component::<TestComponent>()
-            .unwrap(),
-        &entity_component
-    ));
+    cache.add_to_cache((query.clone(), Vec::new()));
+    assert!(cache.check_cache(&query).unwrap().is_empty());
+
+    cache.update_cache(&entity_id, &[], &[TestComponent::property_id()]);
+    assert_eq!(cache.check_cache(&query).unwrap(), vec![entity_id]);
+
+    cache.update_cache(&entity_id, &[TestComponent::property_id()], &[]);
+    assert!(cache.check_cache(&query).unwrap().is_empty());
 
+    cache.update_cache(&entity_id, &[], &[TestComponent::property_id()]);
     cache.remove_entity_from_cache(&entity_id);
     assert!(cache.check_cache(&query).unwrap().is_empty());
 }
 
 #[test]
-#[should_panic]
-fn query_cache_add_already_existing_query_panics() {
+fn query_cache_incremental_update_success() {
     let mut cache = QueryCache::new();
-    let entity_id = 1;
-    let entity_component = Arc::new(RwLock::new(TestComponent));
+    let entity_ids = [Entity::new(1, 0), Entity::new(2, 0), Entity::new(3, 0)];
 
-    let query = Query::new().with::<TestComponent>();
-    let mut query_result = QueryResult::new(entity_id);
-    query_result.add_component(entity_component.clone());
+    let with_query = Query::new().with::<TestComponent>();
+    let without_query = Query::new().without::<TestComponent>();
+    cache.add_to_cache((with_query.clone(), Vec::new()));
+    cache.add_to_cache((without_query.clone(), Vec::new()));
+
+    entity_ids
+        .iter()
+        .for_each(|entity_id| cache.insert_entity(entity_id));
+    assert!(cache.check_cache(&with_query).unwrap().is_empty());
+    assert_eq!(cache.check_cache(&without_query).unwrap(), entity_ids);
+
+    entity_ids.iter().for_each(|entity_id| {
+        cache.update_cache(entity_id, &[], &[TestComponent::property_id()]);
+    });
+    assert_eq!(cache.check_cache(&with_query).unwrap(), entity_ids);
+    assert!(cache.check_cache(&without_query).unwrap().is_empty());
+
+    // Components not referenced by any cached query leave the results untouched.
+    cache.update_cache(
+        &entity_ids[0],
+        &[TestComponent::property_id()],
+        &[
+            TestComponent::property_id(),
+            OtherTestComponent::property_id(),
+        ],
+    );
+    assert_eq!(cache.check_cache(&with_query).unwrap(), entity_ids);
+
+    cache.remove_entity_from_cache(&entity_ids[0]);
+    let mut remaining = cache.check_cache(&with_query).unwrap().to_vec();
+    remaining.sort();
+    assert_eq!(remaining, entity_ids[1..]);
+
+    cache.update_cache(&entity_ids[2], &[TestComponent::property_id()], &[]);
+    assert_eq!(cache.check_cache(&with_query).unwrap(), [entity_ids[1]]);
+    assert_eq!(cache.check_cache(&without_query).unwrap(), [entity_ids[2]]);
+}
+
+#[test]
+fn query_canonical_form_success() {
+    assert_eq!(
+        Query::new()
+            .with::<TestComponent>()
+            .with::<ValueComponent>(),
+        Query::new()
+            .with::<ValueComponent>()
+            .with::<TestComponent>()
+            .with::<ValueComponent>()
+    );
+    assert_eq!(
+        Query::new().any_of::<(TestComponent, OtherTestComponent)>(),
+        Query::new().any_of::<(OtherTestComponent, TestComponent, OtherTestComponent)>()
+    );
+    assert_ne!(
+        Query::new().with::<TestComponent>(),
+        Query::new().without::<TestComponent>()
+    );
+}
+
+#[test]
+fn query_cache_evicts_least_recently_used_success() {
+    let mut cache = QueryCache::new();
+    cache.set_capacity(2);
+    let first = Query::new().with::<TestComponent>();
+    let second = Query::new().with::<OtherTestComponent>();
+    let third = Query::new().with::<ValueComponent>();
+
+    cache.add_to_cache((first.clone(), Vec::new()));
+    cache.add_to_cache((second.clone(), Vec::new()));
+    assert!(cache.check_cache(&first).is_some());
+    cache.add_to_cache((third.clone(), Vec::new()));
+
+    assert!(cache.check_cache(&first).is_some());
+    assert!(cache.check_cache(&second).is_none());
+    assert!(cache.check_cache(&third).is_some());
+    let stats = cache.get_stats();
+    assert_eq!(stats.evictions, 1);
+    assert_eq!(stats.cached_queries, 2);
+    assert_eq!((stats.hits, stats.misses), (3, 1));
+
+    // Evicted queries no longer need maintaining.
+    let entity_id = Entity::new(1, 0);
+    cache.update_cache(&entity_id, &[], &[OtherTestComponent::property_id()]);
+    assert_eq!(cache.get_stats().maintenance, 0);
+}
+
+#[test]
+fn entity_manager_query_cache_pin_and_clear_success() {
+    let mut manager = EntityManager::new();
+    manager.set_query_cache_capacity(1);
+    let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+
+    let pinned = Query::new().with::<TestComponent>();
+    manager.pin_query(pinned.clone());
+    manager.filter(Query::new().without::<TestComponent>());
+    manager.filter(Query::new().with::<OtherTestComponent>());
+    let stats = manager.get_query_cache_stats();
+    assert_eq!(stats.cached_queries, 2);
+    assert_eq!(stats.pinned_queries, 1);
+    assert_eq!(stats.evictions, 1);
+
+    manager.clear_query_cache();
+    assert_eq!(manager.get_query_cache_stats().cached_queries, 1);
+    assert_eq!(manager.filter(pinned.clone())[0].entity_id(), entity_id);
+    assert_eq!(manager.get_query_cache_stats().hits, 1);
 
-    cache.add_to_cache((query.clone(), vec![query_result.clone()]));
-    cache.add_to_cache((query.clone(), vec![query_result.clone()]));
+    manager.add_components_to_entity(
+        &entity_id,
+        ComponentBundle::new().add_component(OtherTestComponent),
+    );
+    assert_eq!(manager.get_query_cache_stats().maintenance, 0);
+    manager.remove_component_from_entity::<TestComponent>(&entity_id);
+    assert!(manager.filter(pinned.clone()).is_empty());
+    assert_eq!(manager.get_query_cache_stats().maintenance, 1);
+
+    assert!(manager.unpin_query(&pinned));
+    assert!(!manager.unpin_query(&pinned));
+    manager.clear_query_cache();
+    assert_eq!(manager.get_query_cache_stats().cached_queries, 0);
+}
+
+#[test]
+fn query_cache_add_already_existing_query_keeps_entry_success() {
+    let mut cache = QueryCache::new();
+    let query = Query::new().with::<TestComponent>();
+    cache.add_to_cache((query.clone(), vec![Entity::new(1, 0)]));
+    cache.add_to_cache((query.clone(), Vec::new()));
+    assert_eq!(cache.check_cache(&query).unwrap(), vec![Entity::new(1, 0)]);
+    assert_eq!(cache.get_stats().cached_queries, 1);
 }
 
 #[test]
@@ -190,3 +335,797 @@ fn entity_manager_filter_cached_success() {
         .iter()
         .any(|result| result.entity_id() == entity_id));
 }
+
+#[test]
+fn entity_allocator_recycles_index_success() {
+    let mut allocator = EntityAllocator::new();
+    let entity_id = allocator.allocate();
+    assert!(allocator.is_alive(&entity_id));
+
+    assert!(allocator.deallocate(&entity_id));
+    assert!(!allocator.is_alive(&entity_id));
+    assert!(!allocator.deallocate(&entity_id));
+
+    let other_entity_id = allocator.allocate();
+    assert_eq!(entity_id.index(), other_entity_id.index());
+    assert_ne!(entity_id.generation(), other_entity_id.generation());
+    assert!(!allocator.is_alive(&entity_id));
+    assert!(allocator.is_alive(&other_entity_id));
+}
+
+#[test]
+fn entity_allocator_reserve_recycles_index_success() {
+    let mut allocator = EntityAllocator::new();
+    let entity_ids = [allocator.allocate(), allocator.allocate()];
+    entity_ids.iter().for_each(|entity_id| {
+        allocator.deallocate(entity_id);
+    });
+
+    // Freed indices are reserved before fresh ones, with bumped generations.
+    let reserved_ids = [
+        allocator.reserve(),
+        allocator.reserve(),
+        allocator.reserve(),
+    ];
+    assert!(reserved_ids
+        .iter()
+        .all(|reserved_id| !allocator.is_alive(reserved_id)));
+    assert_eq!(reserved_ids[0].index(), entity_ids[1].index());
+    assert_eq!(reserved_ids[1].index(), entity_ids[0].index());
+    assert_eq!(reserved_ids[2].index(), 2);
+    assert_ne!(reserved_ids[0].generation(), entity_ids[1].generation());
+    assert_ne!(reserved_ids[1].generation(), entity_ids[0].generation());
+
+    assert_eq!(allocator.flush_reserved(), reserved_ids.to_vec());
+    assert!(reserved_ids
+        .iter()
+        .all(|reserved_id| allocator.is_alive(reserved_id)));
+    assert!(!allocator.is_alive(&entity_ids[0]));
+    assert_eq!(allocator.allocate().index(), 3);
+}
+
+#[test]
+fn commands_create_entity_recycles_id_success() {
+    let mut manager = EntityManager::new();
+    for _ in 0..100 {
+        let entity_ids: Vec<Entity> = {
+            let mut commands = manager.commands();
+            (0..10)
+                .map(|_| commands.create_entity(ComponentBundle::new()))
+                .collect()
+        };
+        manager.flush_commands();
+        {
+            let mut commands = manager.commands();
+            entity_ids
+                .iter()
+                .for_each(|entity_id| commands.delete_entity(entity_id));
+        }
+        manager.flush_commands();
+    }
+
+    // Despawned indices are reused, so the slot table doesn't grow.
+    let entity_id = manager.commands().create_entity(ComponentBundle::new());
+    assert!(entity_id.index() < 10);
+    assert_ne!(entity_id.generation(), 0);
+    manager.flush_commands();
+    assert!(manager.is_alive(&entity_id));
+}
+
+#[test]
+fn entity_manager_delete_entity_recycles_id_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    manager.delete_entity(&entity_id);
+    assert!(!manager.is_alive(&entity_id));
+
+    let other_entity_id = manager.create_entity(ComponentBundle::new());
+    assert_eq!(entity_id.index(), other_entity_id.index());
+    assert_ne!(entity_id, other_entity_id);
+    assert!(manager.is_alive(&other_entity_id));
+    assert!(manager
+        .filter(Query::new().with::<TestComponent>())
+        .is_empty());
+}
+
+#[test]
+#[should_panic]
+fn entity_manager_delete_stale_entity_panics() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new());
+    manager.delete_entity(&entity_id);
+    manager.delete_entity(&entity_id);
+}
+
+#[test]
+#[should_panic]
+fn entity_manager_add_components_to_stale_entity_panics() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new());
+    manager.delete_entity(&entity_id);
+    manager.create_entity(ComponentBundle::new());
+    manager.add_components_to_entity(
+        &entity_id,
+        ComponentBundle::new().add_component(TestComponent),
+    );
+}
+
+#[test]
+#[should_panic]
+fn entity_manager_remove_component_from_stale_entity_panics() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    manager.delete_entity(&entity_id);
+    manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    manager.remove_component_from_entity::<TestComponent>(&entity_id);
+}
+
+#[test]
+fn entity_manager_archetype_move_preserves_components_success() {
+    let mut manager = EntityManager::new();
+    let entity_ids: Vec<Entity> = (0..3)
+        .map(|value| {
+            manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value }))
+        })
+        .collect();
+
+    // Moving the first Entity to a new archetype swaps the last Entity into its old row.
+    manager.add_components_to_entity(
+        &entity_ids[0],
+        ComponentBundle::new().add_component(TestComponent),
+    );
+    manager.delete_entity(&entity_ids[1]);
+    manager.remove_component_from_entity::<TestComponent>(&entity_ids[0]);
+
+    for (entity_id, value) in [(entity_ids[0], 0), (entity_ids[2], 2)] {
+        let result = manager.get_all_components_for_entity(&entity_id).unwrap();
+        assert_eq!(result.entity_id(), entity_id);
+        assert_eq!(
+            result.get_component::<ValueComponent>().unwrap().value,
+            value
+        );
+        assert!(!result.has_component::<TestComponent>());
+    }
+    assert!(manager
+        .get_all_components_for_entity(&entity_ids[1])
+        .is_none());
+}
+
+#[test]
+fn entity_manager_filter_updates_cached_results_success() {
+    let mut manager = EntityManager::new();
+    let entity_id =
+        manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 1 }));
+    let query = Query::new()
+        .with::<ValueComponent>()
+        .without::<TestComponent>();
+    assert_eq!(manager.filter(query.clone()).len(), 1);
+
+    manager.add_components_to_entity(
+        &entity_id,
+        ComponentBundle::new().add_component(TestComponent),
+    );
+    assert!(manager.filter(query.clone()).is_empty());
+
+    manager.remove_component_from_entity::<TestComponent>(&entity_id);
+    let results = manager.filter(query);
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].get_component::<ValueComponent>().unwrap().value,
+        1
+    );
+}
+
+#[test]
+fn typed_query_iter_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(
+        ComponentBundle::new()
+            .add_component(ValueComponent { value: 1 })
+            .add_component(TestComponent),
+    );
+    let other_entity_id =
+        manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 2 }));
+    manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+
+    let query = Query::<(Entity, &mut ValueComponent)>::default();
+    let mut borrow = manager.query(&query);
+    assert_eq!(borrow.len(), 2);
+    for (_, value) in &mut borrow {
+        value.value *= 10;
+    }
+    drop(borrow);
+
+    let mut values: Vec<(Entity, u32)> = Vec::new();
+    manager
+        .query(&Query::<(Entity, &ValueComponent)>::default())
+        .for_each(|(entity_id, value)| values.push((entity_id, value.value)));
+    values.sort();
+    assert_eq!(values, vec![(entity_id, 10), (other_entity_id, 20)]);
+}
+
+#[test]
+fn typed_query_optional_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(
+        ComponentBundle::new()
+            .add_component(ValueComponent { value: 1 })
+            .add_component(TestComponent),
+    );
+    let other_entity_id =
+        manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 2 }));
+
+    let mut results: Vec<(Entity, bool)> = manager
+        .query(&Query::<(Entity, &ValueComponent, Option<&TestComponent>)>::default())
+        .iter()
+        .map(|(entity_id, _, test)| (entity_id, test.is_some()))
+        .collect();
+    results.sort();
+    assert_eq!(results, vec![(entity_id, true), (other_entity_id, false)]);
+}
+
+#[test]
+fn typed_query_with_without_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(
+        ComponentBundle::new()
+            .add_component(ValueComponent { value: 1 })
+            .add_component(TestComponent),
+    );
+    manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 2 }));
+
+    let query = Query::<Entity>::default().with::<TestComponent>();
+    assert_eq!(
+        manager.query(&query).iter().collect::<Vec<_>>(),
+        vec![entity_id]
+    );
+
+    let query = Query::<&ValueComponent>::default().without::<TestComponent>();
+    let values: Vec<u32> = manager
+        .query(&query)
+        .iter()
+        .map(|value| value.value)
+        .collect();
+    assert_eq!(values, vec![2]);
+}
+
+#[test]
+fn typed_query_access_success() {
+    let query = Query::<(&mut ValueComponent, Option<&TestComponent>)>::default();
+    assert!(query.get_access().writes(&ValueComponent::property_id()));
+    assert!(query.get_access().reads(&TestComponent::property_id()));
+    assert!(query.matches(&[ValueComponent::property_id()]));
+}
+
+#[test]
+#[should_panic]
+fn typed_query_conflicting_access_panics() {
+    Query::<(&ValueComponent, &mut ValueComponent)>::default();
+}
+
+#[test]
+fn commands_create_entity_success() {
+    let mut manager = EntityManager::new();
+    let existing_entity_id = manager.create_entity(ComponentBundle::new());
+
+    let mut commands = manager.commands();
+    let entity_id = commands.create_entity(ComponentBundle::new().add_component(TestComponent));
+    let other_entity_id = commands.create_entity(ComponentBundle::new());
+    drop(commands);
+
+    assert_ne!(entity_id, existing_entity_id);
+    assert_ne!(entity_id, other_entity_id);
+    assert!(!manager.is_alive(&entity_id));
+    assert!(manager
+        .filter(Query::new().with::<TestComponent>())
+        .is_empty());
+
+    manager.flush_commands();
+    assert!(manager.is_alive(&entity_id));
+    assert!(manager.is_alive(&other_entity_id));
+    let results = manager.filter(Query::new().with::<TestComponent>());
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].entity_id(), entity_id);
+}
+
+#[test]
+fn commands_create_entity_then_create_directly_success() {
+    let mut manager = EntityManager::new();
+    let reserved_entity_id = manager.commands().create_entity(ComponentBundle::new());
+    let entity_id = manager.create_entity(ComponentBundle::new());
+    assert_ne!(reserved_entity_id, entity_id);
+
+    manager.flush_commands();
+    assert!(manager.is_alive(&reserved_entity_id));
+    assert!(manager.is_alive(&entity_id));
+    assert_eq!(manager.filter(Query::new()).len(), 2);
+}
+
+#[test]
+fn commands_modify_entity_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    let deleted_entity_id = manager.create_entity(ComponentBundle::new());
+
+    {
+        let mut commands = manager.commands();
+        commands.remove_component_from_entity::<TestComponent>(&entity_id);
+        commands.add_components_to_entity(
+            &entity_id,
+            ComponentBundle::new().add_component(OtherTestComponent),
+        );
+        commands.delete_entity(&deleted_entity_id);
+    }
+    assert!(manager
+        .get_all_components_for_entity(&entity_id)
+        .unwrap()
+        .has_component::<TestComponent>());
+
+    manager.flush_commands();
+    let result = manager.get_all_components_for_entity(&entity_id).unwrap();
+    assert!(!result.has_component::<TestComponent>());
+    assert!(result.has_component::<OtherTestComponent>());
+    assert!(!manager.is_alive(&deleted_entity_id));
+}
+
+#[test]
+fn commands_stale_entity_ignored_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new());
+    {
+        let mut commands = manager.commands();
+        commands.delete_entity(&entity_id);
+        commands.delete_entity(&entity_id);
+        commands.add_components_to_entity(
+            &entity_id,
+            ComponentBundle::new().add_component(TestComponent),
+        );
+    }
+    manager.flush_commands();
+    assert!(!manager.is_alive(&entity_id));
+}
+
+#[test]
+fn commands_spawn_during_query_success() {
+    let mut manager = EntityManager::new();
+    manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 1 }));
+    manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 2 }));
+
+    let mut commands = manager.commands();
+    manager
+        .query(&Query::<&ValueComponent>::default())
+        .for_each(|component| {
+            commands.create_entity(ComponentBundle::new().add_component(ValueComponent {
+                value: component.value * 10,
+            }));
+        });
+    drop(commands);
+    manager.flush_commands();
+
+    let mut values: Vec<u32> = manager
+        .query(&Query::<&ValueComponent>::default())
+        .iter()
+        .map(|component| component.value)
+        .collect();
+    values.sort_unstable();
+    assert_eq!(values, vec![1, 2, 10, 20]);
+}
+
+#[test]
+fn typed_query_changed_filter_success() {
+    let mut manager = EntityManager::new();
+    let entity_id =
+        manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 1 }));
+    manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 2 }));
+    let changed_query = Query::<Entity>::default().changed::<ValueComponent>();
+    assert_eq!(manager.query(&changed_query).len(), 2);
+
+    {
+        let _context = SystemContext {
+            system_id: 0,
+            last_run: 1,
+            this_run: 2,
+        }
+        .enter();
+        assert!(manager.query(&changed_query).is_empty());
+        manager
+            .get_all_components_for_entity(&entity_id)
+            .unwrap()
+            .get_component_mut::<ValueComponent>()
+            .unwrap()
+            .value += 1;
+    }
+
+    let _context = SystemContext {
+        system_id: 0,
+        last_run: 1,
+        this_run: 3,
+    }
+    .enter();
+    let changed: Vec<Entity> = manager.query(&changed_query).iter().collect();
+    assert_eq!(changed, vec![entity_id]);
+    let filtered = manager.filter(Query::new().changed::<ValueComponent>());
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].entity_id(), entity_id);
+}
+
+#[test]
+fn typed_query_changed_filter_skips_unmatched_success() {
+    let mut manager = EntityManager::new();
+    (0..3).for_each(|value| {
+        manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value }));
+    });
+    manager.create_entity(
+        ComponentBundle::new()
+            .add_component(ValueComponent { value: 3 })
+            .add_component(TestComponent),
+    );
+
+    let _context = SystemContext {
+        system_id: 0,
+        last_run: 1,
+        this_run: 2,
+    }
+    .enter();
+    manager
+        .filter(Query::new().with::<ValueComponent>())
+        .iter()
+        .filter(|result| result.get_component::<ValueComponent>().unwrap().value % 2 == 1)
+        .for_each(|result| {
+            result.get_component_mut::<ValueComponent>();
+        });
+
+    // Only the rows passing the filter are fetched, and therefore marked as changed.
+    let _context = SystemContext {
+        system_id: 0,
+        last_run: 1,
+        this_run: 3,
+    }
+    .enter();
+    let mut values: Vec<u32> = manager
+        .query(&Query::<&mut ValueComponent>::default().changed::<ValueComponent>())
+        .iter()
+        .map(|component| component.value)
+        .collect();
+    values.sort_unstable();
+    assert_eq!(values, vec![1, 3]);
+
+    let _context = SystemContext {
+        system_id: 0,
+        last_run: 2,
+        this_run: 4,
+    }
+    .enter();
+    assert_eq!(
+        manager
+            .query(&Query::<&ValueComponent>::default().changed::<ValueComponent>())
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn typed_query_added_filter_success() {
+    let mut manager = EntityManager::new();
+    manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    let other_entity_id =
+        manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+
+    let _context = SystemContext {
+        system_id: 0,
+        last_run: 1,
+        this_run: 2,
+    }
+    .enter();
+    manager.add_components_to_entity(
+        &other_entity_id,
+        ComponentBundle::new().add_component(ValueComponent { value: 1 }),
+    );
+    manager
+        .query(&Query::<&mut ValueComponent>::default())
+        .for_each(|component| component.value += 1);
+
+    let added_query = Query::<Entity>::default().added::<ValueComponent>();
+    assert_eq!(
+        manager.query(&added_query).iter().collect::<Vec<_>>(),
+        vec![other_entity_id]
+    );
+    assert!(manager
+        .query(&Query::<Entity>::default().added::<TestComponent>())
+        .is_empty());
+    // Moving between archetypes preserves ticks.
+    assert!(manager
+        .filter(Query::new().changed::<TestComponent>())
+        .is_empty());
+}
+
+#[test]
+fn entity_manager_removed_components_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(
+        ComponentBundle::new()
+            .add_component(TestComponent)
+            .add_component(OtherTestComponent),
+    );
+    let other_entity_id =
+        manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+
+    {
+        let _context = SystemContext {
+            system_id: 0,
+            last_run: 0,
+            this_run: 1,
+        }
+        .enter();
+        manager.remove_component_from_entity::<OtherTestComponent>(&entity_id);
+        manager.delete_entity(&other_entity_id);
+    }
+
+    {
+        let _context = SystemContext {
+            system_id: 0,
+            last_run: 0,
+            this_run: 2,
+        }
+        .enter();
+        assert_eq!(
+            manager.get_removed_components::<OtherTestComponent>(),
+            vec![entity_id]
+        );
+        assert_eq!(
+            manager.get_removed_components::<TestComponent>(),
+            vec![other_entity_id]
+        );
+        assert_eq!(manager.get_deleted_entities(), vec![other_entity_id]);
+    }
+
+    {
+        let _context = SystemContext {
+            system_id: 0,
+            last_run: 1,
+            this_run: 3,
+        }
+        .enter();
+        assert!(manager
+            .get_removed_components::<OtherTestComponent>()
+            .is_empty());
+        assert!(manager.get_deleted_entities().is_empty());
+    }
+
+    // Outside of a `System`, everything still logged is reported.
+    assert_eq!(manager.get_deleted_entities(), vec![other_entity_id]);
+    manager.clear_removal_log(1);
+    assert!(manager.get_deleted_entities().is_empty());
+}
+
+#[test]
+fn entity_manager_try_operations_fail_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    assert_eq!(
+        manager.try_add_components_to_entity(
+            &entity_id,
+            ComponentBundle::new()
+                .add_component(OtherTestComponent)
+                .add_component(TestComponent),
+        ),
+        Err(Error::ComponentAlreadyRegistered {
+            entity_id,
+            component: TestComponent::property_string(),
+        })
+    );
+    // Nothing is added if any `Component` is already registered.
+    let result = manager.get_all_components_for_entity(&entity_id).unwrap();
+    assert!(!result.has_component::<OtherTestComponent>());
+
+    manager.try_delete_entity(&entity_id).unwrap();
+    assert_eq!(
+        manager.try_delete_entity(&entity_id),
+        Err(Error::EntityNotFound(entity_id))
+    );
+    assert_eq!(
+        manager.try_add_components_to_entity(&entity_id, ComponentBundle::new()),
+        Err(Error::EntityNotFound(entity_id))
+    );
+    assert_eq!(
+        manager.try_remove_component_from_entity::<TestComponent>(&entity_id),
+        Err(Error::EntityNotFound(entity_id))
+    );
+}
+
+#[test]
+fn commands_try_flush_skips_failed_commands_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    {
+        let mut commands = manager.commands();
+        commands.add_components_to_entity(
+            &entity_id,
+            ComponentBundle::new().add_component(TestComponent),
+        );
+        commands.add_components_to_entity(
+            &entity_id,
+            ComponentBundle::new().add_component(OtherTestComponent),
+        );
+    }
+
+    assert!(matches!(
+        manager.try_flush_commands(),
+        Err(Error::ComponentAlreadyRegistered { .. })
+    ));
+    let result = manager.get_all_components_for_entity(&entity_id).unwrap();
+    assert!(result.has_component::<OtherTestComponent>());
+}
+
+#[test]
+fn entity_manager_filter_any_of_updates_cached_results_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    let other_entity_id =
+        manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 1 }));
+    let query = Query::new()
+        .any_of::<(TestComponent, OtherTestComponent)>()
+        .optional::<ValueComponent>();
+    let get_entities = |manager: &EntityManager| {
+        let mut entities: Vec<Entity> = manager
+            .filter(query.clone())
+            .iter()
+            .map(|result| result.entity_id())
+            .collect();
+        entities.sort_by_key(|entity_id| entity_id.index());
+        entities
+    };
+    assert_eq!(get_entities(&manager), vec![entity_id]);
+
+    manager.add_components_to_entity(
+        &other_entity_id,
+        ComponentBundle::new().add_component(OtherTestComponent),
+    );
+    assert_eq!(get_entities(&manager), vec![entity_id, other_entity_id]);
+    let results = manager.filter(query.clone());
+    assert_eq!(
+        results
+            .iter()
+            .filter_map(|result| result.get_component::<ValueComponent>())
+            .map(|component| component.value)
+            .collect::<Vec<_>>(),
+        vec![1]
+    );
+    drop(results);
+
+    manager.remove_component_from_entity::<TestComponent>(&entity_id);
+    assert_eq!(get_entities(&manager), vec![other_entity_id]);
+}
+
+#[test]
+fn typed_query_any_of_success() {
+    let mut manager = EntityManager::new();
+    manager.create_entity(
+        ComponentBundle::new()
+            .add_component(ValueComponent { value: 1 })
+            .add_component(TestComponent),
+    );
+    manager.create_entity(
+        ComponentBundle::new()
+            .add_component(ValueComponent { value: 2 })
+            .add_component(OtherTestComponent),
+    );
+    manager.create_entity(ComponentBundle::new().add_component(ValueComponent { value: 3 }));
+
+    let mut values: Vec<u32> = manager
+        .query(&Query::<&ValueComponent>::default().any_of::<(TestComponent, OtherTestComponent)>())
+        .iter()
+        .map(|component| component.value)
+        .collect();
+    values.sort_unstable();
+    assert_eq!(values, vec![1, 2]);
+}
+
+#[test]
+fn entity_manager_filter_iter_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    let other_entity_id = manager.create_entity(
+        ComponentBundle::new()
+            .add_component(TestComponent)
+            .add_component(ValueComponent { value: 1 }),
+    );
+    manager.create_entity(ComponentBundle::new().add_component(OtherTestComponent));
+
+    let query = Query::new().with::<TestComponent>();
+    let mut entities: Vec<Entity> = manager
+        .filter_iter(&query)
+        .map(|result| result.entity_id())
+        .collect();
+    entities.sort_by_key(|entity_id| entity_id.index());
+    assert_eq!(entities, vec![entity_id, other_entity_id]);
+
+    // Results borrow from storage, so queries may be nested.
+    let other_query = Query::new().with::<OtherTestComponent>();
+    manager.filter_iter(&query).for_each(|result| {
+        assert!(result.has_component::<TestComponent>());
+        assert_eq!(manager.filter_iter(&other_query).count(), 1);
+    });
+
+    let _context = SystemContext {
+        system_id: 0,
+        last_run: 1,
+        this_run: 2,
+    }
+    .enter();
+    manager
+        .filter_iter(&Query::new().with::<ValueComponent>())
+        .for_each(|result| result.get_component_mut::<ValueComponent>().unwrap().value += 1);
+    let changed: Vec<Entity> = manager
+        .filter_iter(&Query::new().changed::<ValueComponent>())
+        .map(|result| result.entity_id())
+        .collect();
+    assert_eq!(changed, vec![other_entity_id]);
+    assert!(manager
+        .filter_iter(&Query::new().changed::<TestComponent>())
+        .next()
+        .is_none());
+}
+
+#[test]
+fn entity_manager_get_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(
+        ComponentBundle::new()
+            .add_component(TestComponent)
+            .add_component(ValueComponent { value: 1 }),
+    );
+
+    assert!(manager.contains(&entity_id));
+    assert!(manager.has::<TestComponent>(&entity_id));
+    assert!(!manager.has::<OtherTestComponent>(&entity_id));
+    assert_eq!(manager.get::<ValueComponent>(&entity_id).unwrap().value, 1);
+    assert!(manager.get::<OtherTestComponent>(&entity_id).is_none());
+
+    manager.get_mut::<ValueComponent>(&entity_id).unwrap().value = 2;
+    assert_eq!(manager.get::<ValueComponent>(&entity_id).unwrap().value, 2);
+
+    manager.delete_entity(&entity_id);
+    assert!(!manager.contains(&entity_id));
+    assert!(!manager.has::<TestComponent>(&entity_id));
+    assert!(manager.get::<ValueComponent>(&entity_id).is_none());
+    assert!(manager.get_mut::<ValueComponent>(&entity_id).is_none());
+}
+
+#[test]
+fn entity_manager_get_many_success() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(
+        ComponentBundle::new()
+            .add_component(TestComponent)
+            .add_component(ValueComponent { value: 1 }),
+    );
+
+    {
+        let (_, mut value, other) = manager
+            .get_many::<(
+                &TestComponent,
+                &mut ValueComponent,
+                Option<&OtherTestComponent>,
+            )>(&entity_id)
+            .unwrap();
+        assert!(other.is_none());
+        value.value += 1;
+    }
+    assert_eq!(manager.get::<ValueComponent>(&entity_id).unwrap().value, 2);
+
+    assert!(manager
+        .get_many::<(&mut ValueComponent, &OtherTestComponent)>(&entity_id)
+        .is_none());
+    manager.delete_entity(&entity_id);
+    assert!(manager.get_many::<&TestComponent>(&entity_id).is_none());
+}
+
+#[test]
+#[should_panic]
+fn entity_manager_get_many_same_component_twice_panics() {
+    let mut manager = EntityManager::new();
+    let entity_id = manager.create_entity(ComponentBundle::new().add_component(TestComponent));
+    manager.get_many::<(&TestComponent, &mut TestComponent)>(&entity_id);
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+
+use crate::entity::Entity;
+
+/// Describes why an operation failed. Returned by the `try_*` variants of operations that would
+/// otherwise panic, e.g. `EntityManager::try_add_components_to_entity`, so that misuse (such as
+/// malformed level data) can be logged and recovered from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The Entity was never created, or has since been deleted.
+    EntityNotFound(Entity),
+    /// A `ComponentBundle` already contains a `Component` of the named type.
+    DuplicateComponent(&'static str),
+    /// The Entity already has a `Component` of the named type.
+    ComponentAlreadyRegistered {
+        entity_id: Entity,
+        component: &'static str,
+    },
+    /// Making `parent` the parent of `child` would create a cycle, as `parent` is `child` itself
+    /// or one of its descendants.
+    HierarchyCycle { child: Entity, parent: Entity },
+    /// No `Prefab` with the given name is defined by the `Scene`.
+    PrefabNotFound(String),
+    /// The named `Prefab` (directly or indirectly) extends itself.
+    PrefabCycle(String),
+    /// No `Component` with the given name is registered in the `TypeRegistry`.
+    ComponentNotRegistered(String),
+    /// The value given for the named `Component` could not be deserialized.
+    InvalidComponent { component: String, message: String },
+    /// The named `Component` is a hierarchy `Component` (`Parent` or `Children`), which a `Scene`
+    /// can't give, as the Entities it refers to aren't known yet. Nest Entities instead.
+    HierarchyComponentInScene(String),
+    /// The named `System` is already registered to the `SystemGroup`.
+    SystemAlreadyRegistered(&'static str),
+    /// The named `System` is not registered to the `SystemGroup`.
+    SystemNotRegistered(&'static str),
+    /// No `SystemGroup` is registered under the id.
+    SystemGroupNotRegistered(u32),
+    /// The named `Event` type was not registered via `Engine::add_event`.
+    EventNotRegistered(&'static str),
+}
+
+/// Shorthand for a `std::result::Result` failing with an `Error`.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::EntityNotFound(entity_id) => write!(f, "Entity: {} does not exist!", entity_id),
+            Error::DuplicateComponent(component) => write!(
+                f,
+                "Component {} cannot be registered more than once per Entity!",
+                component
+            ),
+            Error::ComponentAlreadyRegistered {
+                entity_id,
+                component,
+            } => write!(
+                f,
+                "Component: {} already registered for Entity: {}!",
+                component, entity_id
+            ),
+            Error::HierarchyCycle { child, parent } => write!(
+                f,
+                "Entity: {} cannot be made the parent of Entity: {}, as that would create a cycle!",
+                parent, child
+            ),
+            Error::PrefabNotFound(prefab) => write!(f, "Prefab: {} not found!", prefab),
+            Error::PrefabCycle(prefab) => write!(f, "Prefab: {} extends itself!", prefab),
+            Error::ComponentNotRegistered(component) => {
+                write!(f, "Component: {} not registered!", component)
+            }
+            Error::InvalidComponent { component, message } => write!(
+                f,
+                "Component: {} could not be deserialized: {}",
+                component, message
+            ),
+            Error::HierarchyComponentInScene(component) => write!(
+                f,
+                "Component: {} cannot be given by a Scene, nest the Entities via children instead!",
+                component
+            ),
+            Error::SystemAlreadyRegistered(system) => {
+                write!(f, "System: {} already registered for SystemGroup!", system)
+            }
+            Error::SystemNotRegistered(system) => {
+                write!(f, "System: {} not registered for SystemGroup!", system)
+            }
+            Error::SystemGroupNotRegistered(group_id) => {
+                write!(f, "SystemGroup with id: {} not registered!", group_id)
+            }
+            Error::EventNotRegistered(event) => write!(f, "Event: {} not registered!", event),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
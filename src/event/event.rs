@@ -0,0 +1,6 @@
+/// An `Event` is a message sent from one `System` to any number of others, e.g. a collision
+/// detected by physics, to be turned into damage and a sound effect. `Event`s are stored in
+/// `Events`, registered via `Engine::add_event`.
+///
+/// Note: All user-defined `Event`s must derive this trait via `#[derive(Event)]`.
+pub trait Event: 'static + Send + Sync {}
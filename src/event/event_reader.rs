@@ -0,0 +1,48 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+
+use crate::entity::EntityManager;
+use crate::event::{get_events, Event, Events};
+use crate::system::{SystemAccess, SystemArg, SystemParamAccessor};
+
+/// Reads `Event`s of type `T`, as an argument of a function `System` (see
+/// `SystemGroup::add_system`). Executing the `System` will panic if `T` was not registered via
+/// `Engine::add_event`.
+pub struct EventReader<'w, T: Event> {
+    events: RwLockReadGuard<'w, Events<T>>,
+}
+
+impl<T: Event> EventReader<'_, T> {
+    /// Returns all `Event`s the `System` has not read yet. See `Events::read`.
+    pub fn read(&self) -> impl Iterator<Item = &T> {
+        self.events.read()
+    }
+}
+
+impl<T: Event> SystemArg for EventReader<'_, T> {
+    type SystemState = ();
+    type State = Arc<RwLock<Events<T>>>;
+    type Item<'w> = EventReader<'w, T>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.add_resource_read::<Events<T>>()
+    }
+
+    fn prepare(
+        entity_manager: &EntityManager,
+        _params: &SystemParamAccessor,
+    ) -> Arc<RwLock<Events<T>>> {
+        get_events::<T>(entity_manager)
+    }
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        state: &'w mut Arc<RwLock<Events<T>>>,
+        _entity_manager: &'w EntityManager,
+    ) -> EventReader<'w, T> {
+        EventReader {
+            events: state.read().unwrap(),
+        }
+    }
+}
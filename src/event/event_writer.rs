@@ -0,0 +1,51 @@
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
+
+use crate::entity::EntityManager;
+use crate::event::{get_events, Event, Events};
+use crate::system::{SystemAccess, SystemArg, SystemParamAccessor};
+
+/// Sends `Event`s of type `T`, as an argument of a function `System` (see
+/// `SystemGroup::add_system`). Executing the `System` will panic if `T` was not registered via
+/// `Engine::add_event`.
+pub struct EventWriter<'w, T: Event> {
+    events: RwLockWriteGuard<'w, Events<T>>,
+}
+
+impl<T: Event> EventWriter<'_, T> {
+    pub fn send(&mut self, event: T) {
+        self.events.send(event);
+    }
+
+    pub fn send_batch<I: IntoIterator<Item = T>>(&mut self, events: I) {
+        self.events.send_batch(events);
+    }
+}
+
+impl<T: Event> SystemArg for EventWriter<'_, T> {
+    type SystemState = ();
+    type State = Arc<RwLock<Events<T>>>;
+    type Item<'w> = EventWriter<'w, T>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.add_resource_write::<Events<T>>()
+    }
+
+    fn prepare(
+        entity_manager: &EntityManager,
+        _params: &SystemParamAccessor,
+    ) -> Arc<RwLock<Events<T>>> {
+        get_events::<T>(entity_manager)
+    }
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        state: &'w mut Arc<RwLock<Events<T>>>,
+        _entity_manager: &'w EntityManager,
+    ) -> EventWriter<'w, T> {
+        EventWriter {
+            events: state.write().unwrap(),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::entity::EntityManager;
+use crate::event::Event;
+use crate::resource::Resource;
+use crate::system::SystemContext;
+use crate::Error;
+
+/// Stores all `Event`s of type `T` sent during the current and the previous update. A `Resource`,
+/// added via `Engine::add_event`, which updates it once per frame (see `Engine::finish_frame`).
+/// Sent `Event`s are therefore dropped at the end of the frame after the one they were sent in.
+///
+/// Each `System` reading `Events` has its own cursor, so `read` yields every `Event` to every
+/// `System` exactly once, as long as the `System` executes before the `Event` is dropped.
+///
+/// From within a `System`:
+///
+/// ```ignore
+/// let events = entity_manager.get_resource::<Events<CollisionEvent>>().unwrap();
+/// events.write().unwrap().send(CollisionEvent { .. });
+/// events.read().unwrap().read().for_each(|collision| { .. });
+/// ```
+#[derive(Resource)]
+pub struct Events<T: Event> {
+    previous: Vec<T>,
+    current: Vec<T>,
+    event_count: usize,
+    cursors: Mutex<HashMap<u64, usize>>,
+}
+
+impl<T: Event> Default for Events<T> {
+    fn default() -> Self {
+        Events::new()
+    }
+}
+
+impl<T: Event> Events<T> {
+    pub fn new() -> Self {
+        Events {
+            previous: Vec::new(),
+            current: Vec::new(),
+            event_count: 0,
+            cursors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+        self.event_count += 1;
+    }
+
+    pub fn send_batch<I: IntoIterator<Item = T>>(&mut self, events: I) {
+        events.into_iter().for_each(|event| self.send(event));
+    }
+
+    /// Returns all `Event`s the currently executing `System` has not read yet, and marks them as
+    /// read for that `System`. Outside of a `System`, returns all stored `Event`s.
+    pub fn read(&self) -> impl Iterator<Item = &T> {
+        let oldest = self.event_count - self.current.len() - self.previous.len();
+        let start = match SystemContext::current() {
+            Some(context) => {
+                let mut cursors = self.cursors.lock().unwrap();
+                let cursor = cursors.entry(context.system_id).or_insert(0);
+                let start = (*cursor).max(oldest);
+                *cursor = self.event_count;
+                start
+            }
+            None => oldest,
+        };
+        self.previous
+            .iter()
+            .chain(self.current.iter())
+            .skip(start - oldest)
+    }
+
+    /// The number of stored `Event`s.
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops the `Event`s sent before the previous update. Called by the `Engine` once per frame.
+    pub fn update(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    /// Drops all stored `Event`s.
+    pub fn clear(&mut self) {
+        self.previous.clear();
+        self.current.clear();
+    }
+}
+
+/// Returns the `Events<T>` `Resource`. Will panic if `T` was not registered.
+pub(crate) fn get_events<T: Event>(entity_manager: &EntityManager) -> Arc<RwLock<Events<T>>> {
+    entity_manager
+        .get_resource::<Events<T>>()
+        .unwrap_or_else(|| panic!("{}", Error::EventNotRegistered(std::any::type_name::<T>())))
+}
@@ -0,0 +1,12 @@
+#[allow(clippy::module_inception)]
+mod event;
+mod event_reader;
+mod event_writer;
+mod events;
+mod test;
+
+pub use self::event::*;
+pub use self::event_reader::*;
+pub use self::event_writer::*;
+pub use self::events::*;
+pub use moecs_macros::Event;
@@ -0,0 +1,116 @@
+#![cfg(test)]
+
+use crate::event::*;
+use crate::system::SystemContext;
+
+#[derive(Event, Debug, PartialEq)]
+struct CollisionEvent {
+    damage: u32,
+}
+
+fn read_as(events: &Events<CollisionEvent>, system_id: u64) -> Vec<&CollisionEvent> {
+    let _context = SystemContext {
+        system_id,
+        last_run: 0,
+        this_run: 1,
+    }
+    .enter();
+    events.read().collect()
+}
+
+#[test]
+fn events_double_buffer_success() {
+    let mut events = Events::new();
+    events.send(CollisionEvent { damage: 1 });
+    assert_eq!(events.len(), 1);
+
+    events.update();
+    events.send(CollisionEvent { damage: 2 });
+    assert_eq!(
+        events.read().collect::<Vec<_>>(),
+        vec![&CollisionEvent { damage: 1 }, &CollisionEvent { damage: 2 }]
+    );
+
+    events.update();
+    assert_eq!(
+        events.read().collect::<Vec<_>>(),
+        vec![&CollisionEvent { damage: 2 }]
+    );
+
+    events.update();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn events_read_per_system_cursor_success() {
+    let mut events = Events::new();
+    events.send_batch([CollisionEvent { damage: 1 }, CollisionEvent { damage: 2 }]);
+    assert_eq!(read_as(&events, 1).len(), 2);
+    assert!(read_as(&events, 1).is_empty());
+
+    events.update();
+    events.send(CollisionEvent { damage: 3 });
+    assert_eq!(read_as(&events, 1), vec![&CollisionEvent { damage: 3 }]);
+    assert_eq!(read_as(&events, 2).len(), 3);
+
+    // Events dropped before a `System` read them are skipped.
+    events.update();
+    events.update();
+    events.send(CollisionEvent { damage: 4 });
+    assert_eq!(read_as(&events, 1), vec![&CollisionEvent { damage: 4 }]);
+    assert_eq!(read_as(&events, 3), vec![&CollisionEvent { damage: 4 }]);
+}
+
+#[test]
+fn events_expire_after_two_updates_success() {
+    let mut events = Events::new();
+    events.send(CollisionEvent { damage: 1 });
+
+    events.update();
+    assert_eq!(events.len(), 1);
+    assert_eq!(read_as(&events, 1), vec![&CollisionEvent { damage: 1 }]);
+
+    events.update();
+    assert!(events.is_empty());
+    assert!(read_as(&events, 2).is_empty());
+    assert!(events.read().next().is_none());
+}
+
+#[test]
+fn events_read_separate_cursors_success() {
+    let mut events = Events::new();
+    events.send(CollisionEvent { damage: 1 });
+    assert_eq!(read_as(&events, 1), vec![&CollisionEvent { damage: 1 }]);
+
+    events.send(CollisionEvent { damage: 2 });
+    assert_eq!(read_as(&events, 1), vec![&CollisionEvent { damage: 2 }]);
+    assert_eq!(
+        read_as(&events, 2),
+        vec![&CollisionEvent { damage: 1 }, &CollisionEvent { damage: 2 }]
+    );
+
+    // Reading as one `System` doesn't advance the cursor of another.
+    events.send(CollisionEvent { damage: 3 });
+    assert_eq!(read_as(&events, 2), vec![&CollisionEvent { damage: 3 }]);
+    assert_eq!(read_as(&events, 1), vec![&CollisionEvent { damage: 3 }]);
+    assert!(read_as(&events, 1).is_empty());
+    assert!(read_as(&events, 2).is_empty());
+}
+
+#[test]
+fn events_reader_added_after_send_success() {
+    let mut events = Events::new();
+    events.send(CollisionEvent { damage: 1 });
+    events.update();
+    events.send(CollisionEvent { damage: 2 });
+
+    // A `System` reading for the first time sees every stored `Event`.
+    assert_eq!(
+        read_as(&events, 1),
+        vec![&CollisionEvent { damage: 1 }, &CollisionEvent { damage: 2 }]
+    );
+
+    events.update();
+    assert_eq!(read_as(&events, 2), vec![&CollisionEvent { damage: 2 }]);
+    assert!(read_as(&events, 1).is_empty());
+}
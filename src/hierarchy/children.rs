@@ -0,0 +1,53 @@
+use std::slice::Iter;
+
+use crate::component::Component;
+use crate::entity::Entity;
+
+/// The children of an Entity, in the order they were attached. Maintained by the `EntityManager`
+/// alongside each child's `Parent`, and removed once the last child is detached.
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Children {
+    children: Vec<Entity>,
+}
+
+impl Children {
+    pub(crate) fn new(child: Entity) -> Self {
+        Children {
+            children: vec![child],
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, Entity> {
+        self.children.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn contains(&self, entity_id: &Entity) -> bool {
+        self.children.contains(entity_id)
+    }
+
+    pub(crate) fn push(&mut self, child: Entity) {
+        self.children.push(child);
+    }
+
+    pub(crate) fn remove(&mut self, child: &Entity) {
+        self.children.retain(|entity_id| entity_id != child);
+    }
+}
+
+impl<'a> IntoIterator for &'a Children {
+    type Item = &'a Entity;
+    type IntoIter = Iter<'a, Entity>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,160 @@
+use crate::component::ComponentBundle;
+use crate::entity::{Entity, EntityManager};
+use crate::hierarchy::{Children, Parent};
+use crate::{Error, Result};
+
+/// Parent / child relationships between Entities, stored as `Parent` and `Children` `Component`s
+/// that are kept in sync with each other.
+impl EntityManager {
+    /// Makes `parent` the parent of `child`, detaching `child` from its previous parent, if any.
+    ///
+    /// Will panic if either Entity is stale, or if `parent` is `child` or one of its descendants.
+    pub fn set_parent(&mut self, child: &Entity, parent: &Entity) {
+        self.try_set_parent(child, parent)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Makes `parent` the parent of `child`, detaching `child` from its previous parent, if any.
+    /// Fails with `Error::EntityNotFound` if either Entity is stale, or `Error::HierarchyCycle` if
+    /// `parent` is `child` or one of its descendants.
+    pub fn try_set_parent(&mut self, child: &Entity, parent: &Entity) -> Result<()> {
+        [child, parent]
+            .into_iter()
+            .try_for_each(|entity_id| match self.is_alive(entity_id) {
+                true => Ok(()),
+                false => Err(Error::EntityNotFound(*entity_id)),
+            })?;
+        if child == parent || self.get_ancestors(parent).contains(child) {
+            return Err(Error::HierarchyCycle {
+                child: *child,
+                parent: *parent,
+            });
+        }
+        if self.get_parent(child) == Some(*parent) {
+            return Ok(());
+        }
+
+        self.detach_from_parent(child);
+        if self.has::<Parent>(child) {
+            self.get_mut::<Parent>(child).unwrap().set(*parent);
+        } else {
+            self.try_add_components_to_entity(
+                child,
+                ComponentBundle::new().add_component(Parent::new(*parent)),
+            )?;
+        }
+        if self.has::<Children>(parent) {
+            self.get_mut::<Children>(parent).unwrap().push(*child);
+        } else {
+            self.try_add_components_to_entity(
+                parent,
+                ComponentBundle::new().add_component(Children::new(*child)),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Detaches `child` from its parent, if any.
+    ///
+    /// Will panic if `child` is stale.
+    pub fn remove_parent(&mut self, child: &Entity) {
+        self.try_remove_parent(child)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Detaches `child` from its parent, if any. Fails with `Error::EntityNotFound` if `child` is
+    /// stale.
+    pub fn try_remove_parent(&mut self, child: &Entity) -> Result<()> {
+        if !self.is_alive(child) {
+            return Err(Error::EntityNotFound(*child));
+        }
+        if self.detach_from_parent(child) {
+            self.try_remove_component_from_entity::<Parent>(child)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the parent of an Entity, or `None` if it has none or is stale.
+    pub fn get_parent(&self, entity_id: &Entity) -> Option<Entity> {
+        self.get::<Parent>(entity_id).map(|parent| parent.get())
+    }
+
+    /// Returns the children of an Entity, in the order they were attached.
+    pub fn get_children(&self, entity_id: &Entity) -> Vec<Entity> {
+        self.get::<Children>(entity_id)
+            .map(|children| children.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the ancestors of an Entity, starting with its parent and ending with the root.
+    pub fn get_ancestors(&self, entity_id: &Entity) -> Vec<Entity> {
+        let mut ancestors = Vec::new();
+        let mut current = *entity_id;
+        while let Some(parent) = self.get_parent(&current) {
+            ancestors.push(parent);
+            current = parent;
+        }
+        ancestors
+    }
+
+    /// Returns the descendants of an Entity in depth-first order, each Entity preceding its
+    /// children.
+    pub fn get_descendants(&self, entity_id: &Entity) -> Vec<Entity> {
+        let mut descendants = Vec::new();
+        let mut stack: Vec<Entity> = self.get_children(entity_id).into_iter().rev().collect();
+        while let Some(current) = stack.pop() {
+            descendants.push(current);
+            stack.extend(self.get_children(&current).into_iter().rev());
+        }
+        descendants
+    }
+
+    /// Deletes an Entity alongside all of its descendants.
+    ///
+    /// Will panic if the provided `entity_id` is stale.
+    pub fn delete_entity_recursive(&mut self, entity_id: &Entity) {
+        self.try_delete_entity_recursive(entity_id)
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Deletes an Entity alongside all of its descendants. Fails with `Error::EntityNotFound` if
+    /// the provided `entity_id` is stale.
+    pub fn try_delete_entity_recursive(&mut self, entity_id: &Entity) -> Result<()> {
+        if !self.is_alive(entity_id) {
+            return Err(Error::EntityNotFound(*entity_id));
+        }
+        // Deleting children before their parents avoids needlessly orphaning them first.
+        self.get_descendants(entity_id)
+            .iter()
+            .rev()
+            .try_for_each(|descendant| self.try_delete_entity(descendant))?;
+        self.try_delete_entity(entity_id)
+    }
+
+    /// Detaches a (live) Entity about to be deleted from its parent, and orphans its children.
+    pub(crate) fn detach_from_hierarchy(&mut self, entity_id: &Entity) {
+        self.detach_from_parent(entity_id);
+        self.get_children(entity_id).iter().for_each(|child| {
+            let _ = self.try_remove_component_from_entity::<Parent>(child);
+        });
+    }
+
+    /// Removes an Entity from its parent's `Children`, removing those once empty. Returns `false`
+    /// if the Entity has no parent. Leaves the Entity's `Parent` as is.
+    fn detach_from_parent(&mut self, child: &Entity) -> bool {
+        let Some(parent) = self.get_parent(child) else {
+            return false;
+        };
+        let is_empty = match self.get_mut::<Children>(&parent) {
+            Some(mut children) => {
+                children.remove(child);
+                children.is_empty()
+            }
+            None => false,
+        };
+        if is_empty {
+            let _ = self.try_remove_component_from_entity::<Children>(&parent);
+        }
+        true
+    }
+}
@@ -0,0 +1,8 @@
+mod children;
+#[allow(clippy::module_inception)]
+mod hierarchy;
+mod parent;
+mod test;
+
+pub use self::children::*;
+pub use self::parent::*;
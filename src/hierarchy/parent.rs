@@ -0,0 +1,28 @@
+use crate::component::Component;
+use crate::entity::Entity;
+
+/// Refers to the parent of an Entity. Maintained by the `EntityManager`: added via
+/// `EntityManager::set_parent`, and removed via `EntityManager::remove_parent`, or when the parent
+/// is deleted.
+///
+/// Note: removing this `Component` via `remove_component_from_entity` leaves the parent's
+/// `Children` out of sync; use `remove_parent` instead.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Parent {
+    parent: Entity,
+}
+
+impl Parent {
+    pub(crate) fn new(parent: Entity) -> Self {
+        Parent { parent }
+    }
+
+    pub fn get(&self) -> Entity {
+        self.parent
+    }
+
+    pub(crate) fn set(&mut self, parent: Entity) {
+        self.parent = parent;
+    }
+}
@@ -0,0 +1,175 @@
+#![cfg(test)]
+
+use crate::component::ComponentBundle;
+use crate::entity::{Entity, EntityManager, Query};
+use crate::hierarchy::*;
+use crate::Error;
+
+fn create_entities(manager: &mut EntityManager, count: usize) -> Vec<Entity> {
+    (0..count)
+        .map(|_| manager.create_entity(ComponentBundle::new()))
+        .collect()
+}
+
+#[test]
+fn hierarchy_set_parent_success() {
+    let mut manager = EntityManager::new();
+    let entities = create_entities(&mut manager, 3);
+
+    manager.set_parent(&entities[1], &entities[0]);
+    manager.set_parent(&entities[2], &entities[0]);
+    assert_eq!(manager.get_parent(&entities[1]), Some(entities[0]));
+    assert_eq!(manager.get_children(&entities[0]), entities[1..]);
+    assert!(manager
+        .get::<Children>(&entities[0])
+        .unwrap()
+        .contains(&entities[2]));
+
+    // Re-parenting detaches from the previous parent.
+    manager.set_parent(&entities[2], &entities[1]);
+    assert_eq!(manager.get_children(&entities[0]), [entities[1]]);
+    assert_eq!(manager.get_children(&entities[1]), [entities[2]]);
+    assert_eq!(
+        manager.get_ancestors(&entities[2]),
+        [entities[1], entities[0]]
+    );
+    assert_eq!(manager.get_descendants(&entities[0]), entities[1..]);
+
+    manager.remove_parent(&entities[2]);
+    assert_eq!(manager.get_parent(&entities[2]), None);
+    assert!(!manager.has::<Children>(&entities[1]));
+    assert_eq!(
+        manager
+            .filter(Query::new().with::<Parent>())
+            .iter()
+            .map(|result| result.entity_id())
+            .collect::<Vec<_>>(),
+        [entities[1]]
+    );
+}
+
+#[test]
+fn hierarchy_set_parent_cycle_fails() {
+    let mut manager = EntityManager::new();
+    let entities = create_entities(&mut manager, 2);
+    manager.set_parent(&entities[1], &entities[0]);
+
+    assert_eq!(
+        manager.try_set_parent(&entities[0], &entities[1]),
+        Err(Error::HierarchyCycle {
+            child: entities[0],
+            parent: entities[1],
+        })
+    );
+    assert!(matches!(
+        manager.try_set_parent(&entities[0], &entities[0]),
+        Err(Error::HierarchyCycle { .. })
+    ));
+    assert_eq!(manager.get_parent(&entities[0]), None);
+}
+
+#[test]
+fn hierarchy_delete_entity_orphans_children_success() {
+    let mut manager = EntityManager::new();
+    let entities = create_entities(&mut manager, 3);
+    manager.set_parent(&entities[1], &entities[0]);
+    manager.set_parent(&entities[2], &entities[1]);
+
+    manager.delete_entity(&entities[1]);
+    assert!(!manager.has::<Children>(&entities[0]));
+    assert!(manager.is_alive(&entities[2]));
+    assert_eq!(manager.get_parent(&entities[2]), None);
+}
+
+#[test]
+fn hierarchy_delete_entity_recursive_success() {
+    let mut manager = EntityManager::new();
+    let entities = create_entities(&mut manager, 5);
+    manager.set_parent(&entities[1], &entities[0]);
+    manager.set_parent(&entities[2], &entities[1]);
+    manager.set_parent(&entities[3], &entities[1]);
+    manager.set_parent(&entities[4], &entities[0]);
+
+    manager.delete_entity_recursive(&entities[1]);
+    entities[1..4]
+        .iter()
+        .for_each(|entity_id| assert!(!manager.is_alive(entity_id)));
+    assert_eq!(manager.get_children(&entities[0]), [entities[4]]);
+
+    manager.commands().delete_entity_recursive(&entities[0]);
+    manager.flush_commands();
+    assert!(!manager.is_alive(&entities[0]));
+    assert!(!manager.is_alive(&entities[4]));
+}
+
+#[test]
+fn hierarchy_commands_set_parent_success() {
+    let mut manager = EntityManager::new();
+    let entities = create_entities(&mut manager, 3);
+    manager.set_parent(&entities[2], &entities[0]);
+
+    let mut commands = manager.commands();
+    let child = commands.create_entity(ComponentBundle::new());
+    commands.set_parent(&child, &entities[0]);
+    // Re-parenting detaches from the previous parent.
+    commands.set_parent(&entities[2], &entities[1]);
+    drop(commands);
+    assert_eq!(manager.get_children(&entities[0]), [entities[2]]);
+
+    manager.flush_commands();
+    assert_eq!(manager.get_parent(&child), Some(entities[0]));
+    assert_eq!(manager.get_children(&entities[0]), [child]);
+    assert_eq!(manager.get_parent(&entities[2]), Some(entities[1]));
+    assert_eq!(manager.get_children(&entities[1]), [entities[2]]);
+
+    let mut commands = manager.commands();
+    commands.remove_parent(&child);
+    commands.remove_parent(&entities[2]);
+    drop(commands);
+    manager.flush_commands();
+    assert_eq!(manager.get_parent(&child), None);
+    assert_eq!(manager.get_parent(&entities[2]), None);
+    assert!(!manager.has::<Children>(&entities[0]));
+    assert!(!manager.has::<Children>(&entities[1]));
+}
+
+#[test]
+fn hierarchy_commands_set_parent_cycle_fails() {
+    let mut manager = EntityManager::new();
+    let entities = create_entities(&mut manager, 3);
+    manager.set_parent(&entities[1], &entities[0]);
+
+    let mut commands = manager.commands();
+    commands.set_parent(&entities[0], &entities[1]);
+    commands.set_parent(&entities[2], &entities[1]);
+    drop(commands);
+    assert_eq!(
+        manager.try_flush_commands(),
+        Err(Error::HierarchyCycle {
+            child: entities[0],
+            parent: entities[1],
+        })
+    );
+    assert_eq!(manager.get_parent(&entities[0]), None);
+    assert_eq!(manager.get_children(&entities[1]), [entities[2]]);
+}
+
+#[test]
+fn hierarchy_commands_delete_parent_success() {
+    let mut manager = EntityManager::new();
+    let entities = create_entities(&mut manager, 4);
+    manager.set_parent(&entities[1], &entities[0]);
+    manager.set_parent(&entities[2], &entities[1]);
+
+    let mut commands = manager.commands();
+    commands.delete_entity(&entities[1]);
+    // Commands targeting the deleted parent are ignored.
+    commands.set_parent(&entities[3], &entities[1]);
+    drop(commands);
+    manager.flush_commands();
+    assert!(!manager.is_alive(&entities[1]));
+    assert!(!manager.has::<Children>(&entities[0]));
+    assert!(manager.is_alive(&entities[2]));
+    assert_eq!(manager.get_parent(&entities[2]), None);
+    assert_eq!(manager.get_parent(&entities[3]), None);
+}
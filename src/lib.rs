@@ -1,11 +1,23 @@
 extern crate self as moecs;
 
 mod engine;
+mod error;
 mod test;
 
-pub mod entity;
 pub mod component;
+pub mod entity;
+pub mod event;
+pub mod hierarchy;
+pub mod resource;
+#[cfg(feature = "scene")]
+pub mod scene;
+#[cfg(feature = "serde")]
+pub mod snapshot;
 pub mod system;
+pub mod time;
+#[cfg(feature = "transform")]
+pub mod transform;
 pub mod util;
 
 pub use self::engine::*;
+pub use self::error::*;
@@ -0,0 +1,10 @@
+mod res;
+#[allow(clippy::module_inception)]
+mod resource;
+mod resource_map;
+mod test;
+
+pub use self::res::*;
+pub use self::resource::*;
+pub(crate) use self::resource_map::*;
+pub use moecs_macros::Resource;
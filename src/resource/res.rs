@@ -0,0 +1,157 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::entity::EntityManager;
+use crate::resource::Resource;
+use crate::system::{SystemAccess, SystemArg, SystemParamAccessor};
+
+/// Shared access to the `Resource` of type `T`, as an argument of a function `System` (see
+/// `SystemGroup::add_system`). Executing the `System` will panic if the `Resource` doesn't exist;
+/// take `Option<Res<T>>` instead if it may not.
+pub struct Res<'w, T: Resource> {
+    guard: RwLockReadGuard<'w, T>,
+}
+
+impl<T: Resource> Deref for Res<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+/// Exclusive access to the `Resource` of type `T`, as an argument of a function `System`. See
+/// `Res`.
+pub struct ResMut<'w, T: Resource> {
+    guard: RwLockWriteGuard<'w, T>,
+}
+
+impl<T: Resource> Deref for ResMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T: Resource> DerefMut for ResMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T: Resource> SystemArg for Res<'_, T> {
+    type SystemState = ();
+    type State = Arc<RwLock<T>>;
+    type Item<'w> = Res<'w, T>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.add_resource_read::<T>()
+    }
+
+    fn prepare(entity_manager: &EntityManager, _params: &SystemParamAccessor) -> Arc<RwLock<T>> {
+        get_resource::<T>(entity_manager)
+    }
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        state: &'w mut Arc<RwLock<T>>,
+        _entity_manager: &'w EntityManager,
+    ) -> Res<'w, T> {
+        Res {
+            guard: state.read().unwrap(),
+        }
+    }
+}
+
+impl<T: Resource> SystemArg for ResMut<'_, T> {
+    type SystemState = ();
+    type State = Arc<RwLock<T>>;
+    type Item<'w> = ResMut<'w, T>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.add_resource_write::<T>()
+    }
+
+    fn prepare(entity_manager: &EntityManager, _params: &SystemParamAccessor) -> Arc<RwLock<T>> {
+        get_resource::<T>(entity_manager)
+    }
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        state: &'w mut Arc<RwLock<T>>,
+        _entity_manager: &'w EntityManager,
+    ) -> ResMut<'w, T> {
+        ResMut {
+            guard: state.write().unwrap(),
+        }
+    }
+}
+
+impl<T: Resource> SystemArg for Option<Res<'_, T>> {
+    type SystemState = ();
+    type State = Option<Arc<RwLock<T>>>;
+    type Item<'w> = Option<Res<'w, T>>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.add_resource_read::<T>()
+    }
+
+    fn prepare(
+        entity_manager: &EntityManager,
+        _params: &SystemParamAccessor,
+    ) -> Option<Arc<RwLock<T>>> {
+        entity_manager.get_resource::<T>()
+    }
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        state: &'w mut Option<Arc<RwLock<T>>>,
+        _entity_manager: &'w EntityManager,
+    ) -> Option<Res<'w, T>> {
+        state.as_ref().map(|resource| Res {
+            guard: resource.read().unwrap(),
+        })
+    }
+}
+
+impl<T: Resource> SystemArg for Option<ResMut<'_, T>> {
+    type SystemState = ();
+    type State = Option<Arc<RwLock<T>>>;
+    type Item<'w> = Option<ResMut<'w, T>>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.add_resource_write::<T>()
+    }
+
+    fn prepare(
+        entity_manager: &EntityManager,
+        _params: &SystemParamAccessor,
+    ) -> Option<Arc<RwLock<T>>> {
+        entity_manager.get_resource::<T>()
+    }
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        state: &'w mut Option<Arc<RwLock<T>>>,
+        _entity_manager: &'w EntityManager,
+    ) -> Option<ResMut<'w, T>> {
+        state.as_ref().map(|resource| ResMut {
+            guard: resource.write().unwrap(),
+        })
+    }
+}
+
+fn get_resource<T: Resource>(entity_manager: &EntityManager) -> Arc<RwLock<T>> {
+    entity_manager
+        .get_resource::<T>()
+        .unwrap_or_else(|| panic!("Resource: {} does not exist!", T::property_string()))
+}
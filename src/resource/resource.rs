@@ -0,0 +1,8 @@
+use crate::util::PropertyId;
+
+/// A `Resource` is a singleton piece of data owned by the `Engine` (e.g. a score, an RNG, or game
+/// settings), as opposed to a `Component`, which is tied to an `Entity`. Unlike `SystemParam`s,
+/// `Resource`s persist between `SystemGroup` executions.
+///
+/// Note: All user-defined `Resource`s must derive this trait via `#[derive(Resource)]`.
+pub trait Resource: 'static + PropertyId + Send + Sync {}
@@ -0,0 +1,52 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::resource::Resource;
+use crate::util::register_property;
+
+/// Stores at most one instance of every `Resource` type, each behind its own lock.
+#[derive(Default)]
+pub(crate) struct ResourceMap {
+    resource_id_to_resource: HashMap<u64, Arc<dyn Any + Send + Sync>>,
+}
+
+impl ResourceMap {
+    pub fn new() -> Self {
+        ResourceMap {
+            resource_id_to_resource: HashMap::new(),
+        }
+    }
+
+    /// Inserts `resource`, replacing any existing `Resource` of the same type.
+    pub fn insert<T: Resource>(&mut self, resource: T) {
+        self.resource_id_to_resource
+            .insert(register_property::<T>(), Arc::new(RwLock::new(resource)));
+    }
+
+    pub fn remove<T: Resource>(&mut self) -> Option<Arc<RwLock<T>>> {
+        self.resource_id_to_resource
+            .remove(&T::property_id())
+            .map(|resource| Self::downcast(resource))
+    }
+
+    pub fn get<T: Resource>(&self) -> Option<Arc<RwLock<T>>> {
+        self.resource_id_to_resource
+            .get(&T::property_id())
+            .map(|resource| Self::downcast(resource.clone()))
+    }
+
+    pub fn contains<T: Resource>(&self) -> bool {
+        self.resource_id_to_resource.contains_key(&T::property_id())
+    }
+
+    fn downcast<T: Resource>(resource: Arc<dyn Any + Send + Sync>) -> Arc<RwLock<T>> {
+        match resource.downcast::<RwLock<T>>() {
+            Ok(resource) => resource,
+            Err(_) => panic!(
+                "Resource: {} is stored under the id of a different type!",
+                T::property_string()
+            ),
+        }
+    }
+}
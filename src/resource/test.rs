@@ -0,0 +1,67 @@
+#![cfg(test)]
+
+use crate::resource::*;
+
+#[derive(Resource)]
+struct ScoreResource {
+    score: u32,
+}
+
+#[derive(Resource)]
+struct OtherResource;
+
+#[test]
+fn resource_map_insert_get_success() {
+    let mut resources = ResourceMap::new();
+    assert!(!resources.contains::<ScoreResource>());
+    assert!(resources.get::<ScoreResource>().is_none());
+
+    resources.insert(ScoreResource { score: 1 });
+    assert!(resources.contains::<ScoreResource>());
+    assert!(!resources.contains::<OtherResource>());
+
+    resources
+        .get::<ScoreResource>()
+        .unwrap()
+        .write()
+        .unwrap()
+        .score += 1;
+    assert_eq!(
+        resources
+            .get::<ScoreResource>()
+            .unwrap()
+            .read()
+            .unwrap()
+            .score,
+        2
+    );
+}
+
+#[test]
+fn resource_map_insert_replaces_success() {
+    let mut resources = ResourceMap::new();
+    resources.insert(ScoreResource { score: 1 });
+    resources.insert(ScoreResource { score: 5 });
+    assert_eq!(
+        resources
+            .get::<ScoreResource>()
+            .unwrap()
+            .read()
+            .unwrap()
+            .score,
+        5
+    );
+}
+
+#[test]
+fn resource_map_remove_success() {
+    let mut resources = ResourceMap::new();
+    resources.insert(ScoreResource { score: 3 });
+    resources.insert(OtherResource);
+
+    let removed = resources.remove::<ScoreResource>().unwrap();
+    assert_eq!(removed.read().unwrap().score, 3);
+    assert!(!resources.contains::<ScoreResource>());
+    assert!(resources.contains::<OtherResource>());
+    assert!(resources.remove::<ScoreResource>().is_none());
+}
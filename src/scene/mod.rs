@@ -0,0 +1,7 @@
+mod prefab;
+#[allow(clippy::module_inception)]
+mod scene;
+mod test;
+
+pub use self::prefab::*;
+pub use self::scene::*;
@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A named Entity template, listing `Component`s (keyed by their `TypeRegistry` name) and their
+/// field values. A Prefab may extend another one, inheriting its `Component`s: fields given
+/// by the extending Prefab override the inherited ones, while all other fields are kept.
+///
+/// ```ignore
+/// "boss": (
+///     extends: Some("enemy"),
+///     components: {
+///         "my_game::HealthComponent": (max: 500),
+///     },
+/// ),
+/// ```
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Prefab {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extends: Option<String>,
+    #[serde(default)]
+    components: BTreeMap<String, Value>,
+}
+
+impl Prefab {
+    pub(crate) fn get_extends(&self) -> Option<&str> {
+        self.extends.as_deref()
+    }
+
+    pub(crate) fn get_components(&self) -> &BTreeMap<String, Value> {
+        &self.components
+    }
+}
+
+/// Applies `overrides` to `components`. Maps (e.g. a `Component`'s fields) are merged
+/// recursively, any other value is replaced.
+pub(crate) fn merge_components(
+    components: &mut BTreeMap<String, Value>,
+    overrides: &BTreeMap<String, Value>,
+) {
+    for (name, value) in overrides {
+        match components.get_mut(name) {
+            Some(component) => merge_value(component, value),
+            None => {
+                components.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn merge_value(value: &mut Value, override_value: &Value) {
+    match (value, override_value) {
+        (Value::Object(fields), Value::Object(override_fields)) => {
+            for (field, override_field) in override_fields {
+                match fields.get_mut(field) {
+                    Some(field) => merge_value(field, override_field),
+                    None => {
+                        fields.insert(field.clone(), override_field.clone());
+                    }
+                }
+            }
+        }
+        (value, override_value) => *value = override_value.clone(),
+    }
+}
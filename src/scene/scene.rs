@@ -0,0 +1,252 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::component::ComponentBundle;
+use crate::entity::{Entity, EntityManager};
+use crate::hierarchy::{Children, Parent};
+use crate::scene::{merge_components, Prefab};
+use crate::snapshot::TypeRegistry;
+use crate::util::PropertyId;
+use crate::{Error, Result};
+
+/// A human-readable description of Entities, meant to be written by hand (e.g. as a level file)
+/// in any self-describing serde format, such as RON or JSON:
+///
+/// ```ignore
+/// (
+///     prefabs: {
+///         "enemy": (
+///             components: {
+///                 "my_game::HealthComponent": (current: 100, max: 100),
+///                 "my_game::PositionComponent": (x: 0, y: 0),
+///             },
+///         ),
+///     },
+///     entities: [
+///         (
+///             prefab: Some("enemy"),
+///             components: {
+///                 "my_game::PositionComponent": (x: 10),
+///             },
+///             children: [
+///                 (components: { "my_game::WeaponComponent": (damage: 5) }),
+///             ],
+///         ),
+///     ],
+/// )
+/// ```
+///
+/// `Component`s are keyed by the name they are registered under in the `TypeRegistry` used to
+/// spawn the Scene (`PropertyId::property_string` by default). Values given by an Entity override
+/// those of its `Prefab`, the same way a `Prefab` overrides the one it extends (see `Prefab`).
+///
+/// Enums are written externally tagged, i.e. `"Idle"` or `{"Patrol": (speed: 2)}`, as RON's own
+/// enum syntax (`Patrol(speed: 2)`) loses the variant name once parsed into a Scene.
+///
+/// Note: The Entity hierarchy is described via `children`. Scenes can't give the `Parent` and
+/// `Children` `Component`s themselves, as the Entities they refer to don't exist yet.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    #[serde(default)]
+    prefabs: BTreeMap<String, Prefab>,
+    #[serde(default)]
+    entities: Vec<SceneEntity>,
+}
+
+/// An Entity described by a `Scene`, optionally based on one of its `Prefab`s.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SceneEntity {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prefab: Option<String>,
+    #[serde(default)]
+    components: BTreeMap<String, Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    children: Vec<SceneEntity>,
+}
+
+/// An Entity whose `Component`s have been deserialized, but that has not been created yet.
+struct PendingEntity {
+    components: ComponentBundle,
+    children: Vec<PendingEntity>,
+}
+
+impl Scene {
+    /// Returns the names of all `Prefab`s defined by the Scene.
+    pub fn get_prefab_names(&self) -> impl Iterator<Item = &str> {
+        self.prefabs.keys().map(|name| name.as_str())
+    }
+
+    /// Returns the `Component`s of the named `Prefab`, including inherited ones.
+    fn resolve_prefab(&self, name: &str) -> Result<BTreeMap<String, Value>> {
+        let mut chain = Vec::new();
+        let mut visited = HashSet::new();
+        let mut next = Some(name);
+        while let Some(name) = next {
+            if !visited.insert(name) {
+                return Err(Error::PrefabCycle(name.to_string()));
+            }
+            let prefab = self
+                .prefabs
+                .get(name)
+                .ok_or_else(|| Error::PrefabNotFound(name.to_string()))?;
+            chain.push(prefab);
+            next = prefab.get_extends();
+        }
+
+        let mut components = BTreeMap::new();
+        chain
+            .iter()
+            .rev()
+            .for_each(|prefab| merge_components(&mut components, prefab.get_components()));
+        Ok(components)
+    }
+
+    fn prepare_entity(
+        &self,
+        entity: &SceneEntity,
+        registry: &TypeRegistry,
+    ) -> Result<PendingEntity> {
+        let mut components = match &entity.prefab {
+            Some(prefab) => self.resolve_prefab(prefab)?,
+            None => BTreeMap::new(),
+        };
+        merge_components(&mut components, &entity.components);
+
+        Ok(PendingEntity {
+            components: build_bundle(&components, registry)?,
+            children: entity
+                .children
+                .iter()
+                .map(|child| self.prepare_entity(child, registry))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+fn build_bundle(
+    components: &BTreeMap<String, Value>,
+    registry: &TypeRegistry,
+) -> Result<ComponentBundle> {
+    components
+        .iter()
+        .try_fold(ComponentBundle::new(), |bundle, (name, value)| {
+            let component_id = registry
+                .get_component_id_by_name(name)
+                .ok_or_else(|| Error::ComponentNotRegistered(name.clone()))?;
+            if component_id == Parent::property_id() || component_id == Children::property_id() {
+                return Err(Error::HierarchyComponentInScene(name.clone()));
+            }
+            let registration = registry.get_component(&component_id).unwrap();
+            let mut deserializer = <dyn erased_serde::Deserializer>::erase(value);
+            (registration.deserialize)(&mut deserializer, bundle).map_err(|error| {
+                Error::InvalidComponent {
+                    component: name.clone(),
+                    message: error.to_string(),
+                }
+            })
+        })
+}
+
+/// Spawning the Entities described by a `Scene`.
+impl EntityManager {
+    /// Spawns all Entities of `scene` (see `try_spawn_scene`).
+    pub fn spawn_scene(&mut self, scene: &Scene, registry: &TypeRegistry) -> Vec<Entity> {
+        self.try_spawn_scene(scene, registry)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Spawns all Entities of `scene`, making child Entities children of their parents (see
+    /// `EntityManager::set_parent`). Returns the created Entities, parents before their children.
+    ///
+    /// Fails if an Entity refers to an unknown `Prefab`, or to a `Component` that isn't
+    /// registered in `registry`, can't be deserialized or is `Parent` / `Children`. No Entities are
+    /// created in that case.
+    pub fn try_spawn_scene(
+        &mut self,
+        scene: &Scene,
+        registry: &TypeRegistry,
+    ) -> Result<Vec<Entity>> {
+        let pending = scene
+            .entities
+            .iter()
+            .map(|entity| scene.prepare_entity(entity, registry))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut entities = Vec::new();
+        for entity in pending {
+            self.create_pending_entity(entity, None, &mut entities)?;
+        }
+        Ok(entities)
+    }
+
+    /// Spawns an instance of the named `Prefab` of `scene` (see `try_spawn_prefab`).
+    pub fn spawn_prefab(&mut self, scene: &Scene, registry: &TypeRegistry, prefab: &str) -> Entity {
+        self.try_spawn_prefab(scene, registry, prefab)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Spawns an instance of the named `Prefab` of `scene`. Fails if the `Prefab` doesn't exist,
+    /// extends itself, or refers to a `Component` that isn't registered in `registry`, can't be
+    /// deserialized or is `Parent` / `Children`.
+    pub fn try_spawn_prefab(
+        &mut self,
+        scene: &Scene,
+        registry: &TypeRegistry,
+        prefab: &str,
+    ) -> Result<Entity> {
+        self.try_spawn_prefab_many(scene, registry, prefab, 1)
+            .map(|entities| entities[0])
+    }
+
+    /// Spawns `count` instances of the named `Prefab` of `scene` (see `try_spawn_prefab_many`).
+    pub fn spawn_prefab_many(
+        &mut self,
+        scene: &Scene,
+        registry: &TypeRegistry,
+        prefab: &str,
+        count: usize,
+    ) -> Vec<Entity> {
+        self.try_spawn_prefab_many(scene, registry, prefab, count)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Spawns `count` instances of the named `Prefab` of `scene`, resolving it only once. Fails
+    /// the same way as `try_spawn_prefab`, in which case no Entities are created.
+    pub fn try_spawn_prefab_many(
+        &mut self,
+        scene: &Scene,
+        registry: &TypeRegistry,
+        prefab: &str,
+        count: usize,
+    ) -> Result<Vec<Entity>> {
+        let components = scene.resolve_prefab(prefab)?;
+        let bundles = (0..count)
+            .map(|_| build_bundle(&components, registry))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(bundles
+            .into_iter()
+            .map(|bundle| self.create_entity(bundle))
+            .collect())
+    }
+
+    fn create_pending_entity(
+        &mut self,
+        pending: PendingEntity,
+        parent: Option<Entity>,
+        entities: &mut Vec<Entity>,
+    ) -> Result<()> {
+        let entity_id = self.create_entity(pending.components);
+        if let Some(parent) = parent {
+            self.try_set_parent(&entity_id, &parent)?;
+        }
+        entities.push(entity_id);
+        for child in pending.children {
+            self.create_pending_entity(child, Some(entity_id), entities)?;
+        }
+        Ok(())
+    }
+}
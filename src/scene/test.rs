@@ -0,0 +1,281 @@
+#![cfg(test)]
+
+use serde::{Deserialize, Serialize};
+
+use crate::component::{Component, ComponentBundle};
+use crate::entity::{EntityManager, Query};
+use crate::scene::*;
+use crate::snapshot::TypeRegistry;
+use crate::util::PropertyId;
+use crate::Error;
+
+#[derive(Component, Serialize, Deserialize, Debug, PartialEq)]
+struct HealthComponent {
+    current: u32,
+    max: u32,
+}
+
+#[derive(Component, Serialize, Deserialize, Debug, PartialEq)]
+struct PositionComponent {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Component, Serialize, Deserialize, Debug, PartialEq)]
+struct BossComponent;
+
+#[derive(Component, Serialize, Deserialize, Debug, PartialEq)]
+enum AiComponent {
+    Idle,
+    Guard(PositionComponent),
+    Patrol { speed: u32 },
+}
+
+#[derive(Component, Serialize, Deserialize, Debug, PartialEq)]
+struct ScoreComponent(u32, i32);
+
+fn registry() -> TypeRegistry {
+    TypeRegistry::new()
+        .register_component::<HealthComponent>()
+        .register_component::<PositionComponent>()
+        .register_component::<BossComponent>()
+        .register_component::<AiComponent>()
+        .register_component::<ScoreComponent>()
+}
+
+fn ron_scene() -> Scene {
+    let source = format!(
+        r#"(
+            prefabs: {{
+                "enemy": (
+                    components: {{
+                        "{health}": (current: 10, max: 10),
+                        "{position}": (x: 0, y: 0),
+                    }},
+                ),
+                "boss": (
+                    extends: Some("enemy"),
+                    components: {{
+                        "{health}": (max: 100),
+                        "{boss}": (),
+                    }},
+                ),
+            }},
+            entities: [
+                (
+                    prefab: Some("boss"),
+                    components: {{
+                        "{position}": (x: 5),
+                    }},
+                    children: [
+                        (prefab: Some("enemy")),
+                    ],
+                ),
+                (
+                    components: {{
+                        "{position}": (x: 1, y: 2),
+                    }},
+                ),
+            ],
+        )"#,
+        health = HealthComponent::property_string(),
+        position = PositionComponent::property_string(),
+        boss = BossComponent::property_string(),
+    );
+    ron::from_str(&source).unwrap()
+}
+
+#[test]
+fn scene_spawn_ron_success() {
+    let mut entity_manager = EntityManager::new();
+    let entities = entity_manager.spawn_scene(&ron_scene(), &registry());
+    assert_eq!(entities.len(), 3);
+    let (boss, minion, marker) = (entities[0], entities[1], entities[2]);
+
+    assert_eq!(
+        *entity_manager.get::<HealthComponent>(&boss).unwrap(),
+        HealthComponent {
+            current: 10,
+            max: 100
+        }
+    );
+    assert_eq!(
+        *entity_manager.get::<PositionComponent>(&boss).unwrap(),
+        PositionComponent { x: 5, y: 0 }
+    );
+    assert!(entity_manager.has::<BossComponent>(&boss));
+
+    assert_eq!(entity_manager.get_parent(&minion), Some(boss));
+    assert!(!entity_manager.has::<BossComponent>(&minion));
+    assert_eq!(
+        *entity_manager.get::<HealthComponent>(&minion).unwrap(),
+        HealthComponent {
+            current: 10,
+            max: 10
+        }
+    );
+
+    assert_eq!(entity_manager.get_parent(&marker), None);
+    assert!(!entity_manager.has::<HealthComponent>(&marker));
+    assert_eq!(
+        *entity_manager.get::<PositionComponent>(&marker).unwrap(),
+        PositionComponent { x: 1, y: 2 }
+    );
+}
+
+#[test]
+fn scene_spawn_prefab_json_success() {
+    let source = serde_json::json!({
+        "prefabs": {
+            "enemy": {
+                "components": {
+                    HealthComponent::property_string(): { "current": 3, "max": 3 },
+                },
+            },
+        },
+    });
+    let scene: Scene = serde_json::from_value(source).unwrap();
+    assert_eq!(scene.get_prefab_names().collect::<Vec<_>>(), vec!["enemy"]);
+
+    let mut entity_manager = EntityManager::new();
+    let entities = entity_manager.spawn_prefab_many(&scene, &registry(), "enemy", 5);
+    assert_eq!(entities.len(), 5);
+    for entity_id in &entities {
+        assert_eq!(
+            *entity_manager.get::<HealthComponent>(entity_id).unwrap(),
+            HealthComponent { current: 3, max: 3 }
+        );
+    }
+
+    let enemy = entity_manager.spawn_prefab(&scene, &registry(), "enemy");
+    assert!(!entities.contains(&enemy));
+    assert!(entity_manager.has::<HealthComponent>(&enemy));
+}
+
+#[test]
+fn scene_spawn_failure() {
+    let scene: Scene = serde_json::from_value(serde_json::json!({
+        "prefabs": {
+            "a": { "extends": "b" },
+            "b": { "extends": "a" },
+            "unregistered": { "components": { "UnknownComponent": {} } },
+            "invalid": {
+                "components": { PositionComponent::property_string(): { "x": "left" } },
+            },
+        },
+        "entities": [
+            { "components": { PositionComponent::property_string(): { "x": 1, "y": 1 } } },
+            { "prefab": "invalid" },
+        ],
+    }))
+    .unwrap();
+
+    let mut entity_manager = EntityManager::new();
+    let registry = registry();
+    assert_eq!(
+        entity_manager.try_spawn_prefab(&scene, &registry, "missing"),
+        Err(Error::PrefabNotFound("missing".to_string()))
+    );
+    assert_eq!(
+        entity_manager.try_spawn_prefab(&scene, &registry, "a"),
+        Err(Error::PrefabCycle("a".to_string()))
+    );
+    assert_eq!(
+        entity_manager.try_spawn_prefab(&scene, &registry, "unregistered"),
+        Err(Error::ComponentNotRegistered(
+            "UnknownComponent".to_string()
+        ))
+    );
+    assert!(matches!(
+        entity_manager.try_spawn_scene(&scene, &registry),
+        Err(Error::InvalidComponent { component, .. })
+            if component == PositionComponent::property_string()
+    ));
+    assert!(entity_manager
+        .filter(Query::new().with::<PositionComponent>())
+        .is_empty());
+}
+
+#[test]
+fn scene_spawn_enum_ron_success() {
+    let source = format!(
+        r#"(
+            prefabs: {{
+                "patrol": (
+                    components: {{
+                        "{ai}": {{"Patrol": (speed: 2)}},
+                        "{score}": (10, -1),
+                    }},
+                ),
+            }},
+            entities: [
+                (components: {{ "{ai}": "Idle" }}),
+                (components: {{ "{ai}": {{"Guard": (x: 1, y: 2)}} }}),
+                (
+                    prefab: Some("patrol"),
+                    components: {{ "{ai}": {{"Patrol": (speed: 3)}} }},
+                ),
+            ],
+        )"#,
+        ai = AiComponent::property_string(),
+        score = ScoreComponent::property_string(),
+    );
+    let scene: Scene = ron::from_str(&source).unwrap();
+
+    let mut entity_manager = EntityManager::new();
+    let entities = entity_manager.spawn_scene(&scene, &registry());
+    assert_eq!(
+        *entity_manager.get::<AiComponent>(&entities[0]).unwrap(),
+        AiComponent::Idle
+    );
+    assert_eq!(
+        *entity_manager.get::<AiComponent>(&entities[1]).unwrap(),
+        AiComponent::Guard(PositionComponent { x: 1, y: 2 })
+    );
+    assert_eq!(
+        *entity_manager.get::<AiComponent>(&entities[2]).unwrap(),
+        AiComponent::Patrol { speed: 3 }
+    );
+    assert_eq!(
+        *entity_manager.get::<ScoreComponent>(&entities[2]).unwrap(),
+        ScoreComponent(10, -1)
+    );
+
+    // RON's own enum syntax loses the variant name, which is reported rather than guessed.
+    let source = format!(
+        r#"(entities: [(components: {{ "{ai}": Patrol(speed: 2) }})])"#,
+        ai = AiComponent::property_string(),
+    );
+    let scene: Scene = ron::from_str(&source).unwrap();
+    assert!(matches!(
+        entity_manager.try_spawn_scene(&scene, &registry()),
+        Err(Error::InvalidComponent { component, .. })
+            if component == AiComponent::property_string()
+    ));
+}
+
+#[test]
+fn scene_spawn_hierarchy_component_failure() {
+    let mut entity_manager = EntityManager::new();
+    let parent = entity_manager.create_entity(ComponentBundle::new());
+    let scene: Scene = serde_json::from_value(serde_json::json!({
+        "prefabs": {
+            "child": { "components": { "Parent": parent } },
+        },
+        "entities": [
+            { "components": { "Children": [parent] } },
+        ],
+    }))
+    .unwrap();
+
+    let registry = registry();
+    assert_eq!(
+        entity_manager.try_spawn_prefab(&scene, &registry, "child"),
+        Err(Error::HierarchyComponentInScene("Parent".to_string()))
+    );
+    assert_eq!(
+        entity_manager.try_spawn_scene(&scene, &registry),
+        Err(Error::HierarchyComponentInScene("Children".to_string()))
+    );
+    assert!(entity_manager.get_children(&parent).is_empty());
+}
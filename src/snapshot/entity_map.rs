@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::entity::Entity;
+
+thread_local! {
+    static ENTITY_MAP: RefCell<Option<HashMap<Entity, Entity>>> = const { RefCell::new(None) };
+}
+
+/// Maps the `Entity` handles stored in a snapshot to the Entities created for them while it is
+/// loaded, so that `Entity`s deserialized on this thread (e.g. a `Parent`, or a target stored in a
+/// `Component`) refer to the loaded Entities.
+pub(crate) struct EntityMap;
+
+impl EntityMap {
+    /// Activates `map` until the returned guard is dropped.
+    pub fn enter(map: HashMap<Entity, Entity>) -> EntityMapGuard {
+        let previous = ENTITY_MAP.with(|current| current.borrow_mut().replace(map));
+        EntityMapGuard { previous }
+    }
+
+    /// Maps a deserialized `Entity`. Handles that aren't part of the snapshot being loaded (i.e.
+    /// that were already stale when it was saved) are mapped to `Entity::DANGLING`. Returns
+    /// `entity_id` as is if no snapshot is being loaded.
+    pub fn map(entity_id: Entity) -> Entity {
+        ENTITY_MAP.with(|current| match current.borrow().as_ref() {
+            Some(map) => map.get(&entity_id).copied().unwrap_or(Entity::DANGLING),
+            None => entity_id,
+        })
+    }
+}
+
+/// Restores the previously active map when dropped.
+pub(crate) struct EntityMapGuard {
+    previous: Option<HashMap<Entity, Entity>>,
+}
+
+impl Drop for EntityMapGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        ENTITY_MAP.with(|current| *current.borrow_mut() = previous);
+    }
+}
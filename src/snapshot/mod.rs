@@ -0,0 +1,8 @@
+mod entity_map;
+#[allow(clippy::module_inception)]
+mod snapshot;
+mod test;
+mod type_registry;
+
+pub(crate) use self::entity_map::*;
+pub use self::type_registry::*;
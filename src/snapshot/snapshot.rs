@@ -0,0 +1,433 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::component::ComponentBundle;
+use crate::entity::{Archetype, Entity, EntityManager};
+use crate::snapshot::{
+    ComponentRegistration, EntityMap, EntityMapGuard, ResourceRegistration, TypeRegistry,
+};
+
+const FIELDS: &[&str] = &["entities", "components", "resources"];
+
+/// Saving and loading the contents of an `EntityManager` via serde.
+impl EntityManager {
+    /// Serializes all Entities, with their `Component`s registered in `registry`, and all
+    /// `Resource`s registered in `registry`. For example:
+    ///
+    /// ```ignore
+    /// let mut bytes = Vec::new();
+    /// entity_manager.save(&registry, &mut serde_json::Serializer::new(&mut bytes))?;
+    /// ```
+    ///
+    /// Note: Entities reserved by `Commands` that have not been applied yet are left out.
+    pub fn save<S: Serializer>(
+        &self,
+        registry: &TypeRegistry,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        SnapshotSerializer {
+            entity_manager: self,
+            registry,
+        }
+        .serialize(serializer)
+    }
+
+    /// Loads a snapshot created via `save`, adding its Entities alongside any existing ones, and
+    /// inserting (replacing) its `Resource`s. Returns the created Entities, in the order they were
+    /// saved in. For example:
+    ///
+    /// ```ignore
+    /// entity_manager.load(&registry, &mut serde_json::Deserializer::from_slice(&bytes))?;
+    /// ```
+    ///
+    /// Loaded Entities are assigned new handles. `Entity`s stored in loaded `Component`s and
+    /// `Resource`s are remapped accordingly; those that were already stale when saved are mapped
+    /// to `Entity::DANGLING`.
+    ///
+    /// Fails if the snapshot is malformed, or contains `Component`s or `Resource`s not registered
+    /// in `registry`. Entities loaded up until then are kept.
+    pub fn load<'de, D: Deserializer<'de>>(
+        &mut self,
+        registry: &TypeRegistry,
+        deserializer: D,
+    ) -> std::result::Result<Vec<Entity>, D::Error> {
+        deserializer.deserialize_struct(
+            "Snapshot",
+            FIELDS,
+            SnapshotVisitor {
+                entity_manager: self,
+                registry,
+            },
+        )
+    }
+
+    fn get_saved_entities(&self) -> impl Iterator<Item = &Entity> {
+        self.get_archetypes()
+            .iter()
+            .flat_map(|archetype| archetype.get_entities())
+    }
+}
+
+struct SnapshotSerializer<'a> {
+    entity_manager: &'a EntityManager,
+    registry: &'a TypeRegistry,
+}
+
+impl Serialize for SnapshotSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut snapshot = serializer.serialize_struct("Snapshot", FIELDS.len())?;
+        snapshot.serialize_field(
+            "entities",
+            &self.entity_manager.get_saved_entities().collect::<Vec<_>>(),
+        )?;
+        snapshot.serialize_field("components", &ComponentsSerializer(self))?;
+        snapshot.serialize_field("resources", &ResourcesSerializer(self))?;
+        snapshot.end()
+    }
+}
+
+/// Serializes a sequence of `(Entity, { Component name: Component })` tuples.
+struct ComponentsSerializer<'a>(&'a SnapshotSerializer<'a>);
+
+impl Serialize for ComponentsSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let archetypes = self.0.entity_manager.get_archetypes();
+        let entity_count = archetypes
+            .iter()
+            .map(|archetype| archetype.get_entities().len())
+            .sum();
+        let mut entities = serializer.serialize_seq(Some(entity_count))?;
+        for archetype in archetypes {
+            for (row, entity_id) in archetype.get_entities().iter().enumerate() {
+                let components = EntityComponentsSerializer {
+                    archetype,
+                    row,
+                    registry: self.0.registry,
+                };
+                entities.serialize_element(&(entity_id, components))?;
+            }
+        }
+        entities.end()
+    }
+}
+
+struct EntityComponentsSerializer<'a> {
+    archetype: &'a Archetype,
+    row: usize,
+    registry: &'a TypeRegistry,
+}
+
+impl Serialize for EntityComponentsSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let registrations: Vec<(&u64, &ComponentRegistration)> = self
+            .archetype
+            .get_component_ids()
+            .iter()
+            .filter_map(|component_id| {
+                self.registry
+                    .get_component(component_id)
+                    .map(|registration| (component_id, registration))
+            })
+            .collect();
+        let mut components = serializer.serialize_map(Some(registrations.len()))?;
+        for (component_id, registration) in registrations {
+            let column = self.archetype.get_column(component_id).unwrap();
+            let column = column.read().unwrap();
+            components.serialize_entry(
+                registration.name,
+                (registration.serialize)(column.as_ref(), self.row),
+            )?;
+        }
+        components.end()
+    }
+}
+
+/// Serializes a `{ Resource name: Resource }` map.
+struct ResourcesSerializer<'a>(&'a SnapshotSerializer<'a>);
+
+impl Serialize for ResourcesSerializer<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let resources: Vec<(&str, Box<dyn erased_serde::Serialize>)> = self
+            .0
+            .registry
+            .get_resources()
+            .filter_map(|registration| {
+                (registration.serialize)(self.0.entity_manager)
+                    .map(|resource| (registration.name, resource))
+            })
+            .collect();
+        let mut map = serializer.serialize_map(Some(resources.len()))?;
+        for (name, resource) in resources {
+            map.serialize_entry(name, resource.as_ref())?;
+        }
+        map.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum Field {
+    Entities,
+    Components,
+    Resources,
+    #[serde(other)]
+    Other,
+}
+
+struct SnapshotVisitor<'a> {
+    entity_manager: &'a mut EntityManager,
+    registry: &'a TypeRegistry,
+}
+
+impl SnapshotVisitor<'_> {
+    /// Creates an Entity for every saved one, and maps the saved handles to them for the rest of
+    /// the load.
+    fn create_entities(&mut self, saved_entities: Vec<Entity>) -> (Vec<Entity>, EntityMapGuard) {
+        let mut map = HashMap::with_capacity(saved_entities.len());
+        let entities = saved_entities
+            .into_iter()
+            .map(|saved_entity_id| {
+                let entity_id = self.entity_manager.create_entity(ComponentBundle::new());
+                map.insert(saved_entity_id, entity_id);
+                entity_id
+            })
+            .collect();
+        (entities, EntityMap::enter(map))
+    }
+}
+
+impl<'de> Visitor<'de> for SnapshotVisitor<'_> {
+    type Value = Vec<Entity>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a moecs snapshot")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<Vec<Entity>, A::Error> {
+        let saved_entities: Vec<Entity> = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let (entities, _guard) = self.create_entities(saved_entities);
+        seq.next_element_seed(ComponentsSeed {
+            entity_manager: self.entity_manager,
+            registry: self.registry,
+        })?
+        .ok_or_else(|| de::Error::invalid_length(1, &"a moecs snapshot"))?;
+        seq.next_element_seed(ResourcesSeed {
+            entity_manager: self.entity_manager,
+            registry: self.registry,
+        })?
+        .ok_or_else(|| de::Error::invalid_length(2, &"a moecs snapshot"))?;
+        Ok(entities)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<Vec<Entity>, A::Error> {
+        let mut loaded = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Entities => {
+                    if loaded.is_some() {
+                        return Err(de::Error::duplicate_field("entities"));
+                    }
+                    let saved_entities: Vec<Entity> = map.next_value()?;
+                    loaded = Some(self.create_entities(saved_entities));
+                }
+                Field::Components => {
+                    if loaded.is_none() {
+                        return Err(de::Error::custom("`entities` must precede `components`"));
+                    }
+                    map.next_value_seed(ComponentsSeed {
+                        entity_manager: self.entity_manager,
+                        registry: self.registry,
+                    })?;
+                }
+                Field::Resources => {
+                    if loaded.is_none() {
+                        return Err(de::Error::custom("`entities` must precede `resources`"));
+                    }
+                    map.next_value_seed(ResourcesSeed {
+                        entity_manager: self.entity_manager,
+                        registry: self.registry,
+                    })?;
+                }
+                Field::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let (entities, _guard) = loaded.ok_or_else(|| de::Error::missing_field("entities"))?;
+        Ok(entities)
+    }
+}
+
+struct ComponentsSeed<'a> {
+    entity_manager: &'a mut EntityManager,
+    registry: &'a TypeRegistry,
+}
+
+impl<'de> DeserializeSeed<'de> for ComponentsSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ComponentsSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of Entities and their Components")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq
+            .next_element_seed(EntityComponentsSeed {
+                entity_manager: &mut *self.entity_manager,
+                registry: self.registry,
+            })?
+            .is_some()
+        {}
+        Ok(())
+    }
+}
+
+/// Deserializes an `(Entity, { Component name: Component })` tuple, adding the `Component`s to the
+/// (already mapped) Entity.
+struct EntityComponentsSeed<'a> {
+    entity_manager: &'a mut EntityManager,
+    registry: &'a TypeRegistry,
+}
+
+impl<'de> DeserializeSeed<'de> for EntityComponentsSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_tuple(2, self)
+    }
+}
+
+impl<'de> Visitor<'de> for EntityComponentsSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an Entity and its Components")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let entity_id: Entity = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let components = seq
+            .next_element_seed(ComponentBundleSeed(self.registry))?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        self.entity_manager
+            .try_add_components_to_entity(&entity_id, components)
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes a `{ Component name: Component }` map into a `ComponentBundle`.
+struct ComponentBundleSeed<'a>(&'a TypeRegistry);
+
+impl<'de> DeserializeSeed<'de> for ComponentBundleSeed<'_> {
+    type Value = ComponentBundle;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ComponentBundleSeed<'_> {
+    type Value = ComponentBundle;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of Component names to Components")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut components = ComponentBundle::new();
+        while let Some(name) = map.next_key::<String>()? {
+            let registration = self
+                .0
+                .get_component_by_name(&name)
+                .ok_or_else(|| de::Error::custom(format!("Component: {} not registered!", name)))?;
+            components = map.next_value_seed(ComponentSeed {
+                registration,
+                components,
+            })?;
+        }
+        Ok(components)
+    }
+}
+
+/// Deserializes a single registered `Component`, adding it to `components`.
+struct ComponentSeed<'a> {
+    registration: &'a ComponentRegistration,
+    components: ComponentBundle,
+}
+
+impl<'de> DeserializeSeed<'de> for ComponentSeed<'_> {
+    type Value = ComponentBundle;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.registration.deserialize)(&mut deserializer, self.components)
+            .map_err(de::Error::custom)
+    }
+}
+
+struct ResourcesSeed<'a> {
+    entity_manager: &'a mut EntityManager,
+    registry: &'a TypeRegistry,
+}
+
+impl<'de> DeserializeSeed<'de> for ResourcesSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ResourcesSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of Resource names to Resources")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(name) = map.next_key::<String>()? {
+            let registration = self
+                .registry
+                .get_resource_by_name(&name)
+                .ok_or_else(|| de::Error::custom(format!("Resource: {} not registered!", name)))?;
+            map.next_value_seed(ResourceSeed {
+                registration,
+                entity_manager: &mut *self.entity_manager,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes a single registered `Resource`, inserting it into the `EntityManager`.
+struct ResourceSeed<'a> {
+    registration: &'a ResourceRegistration,
+    entity_manager: &'a mut EntityManager,
+}
+
+impl<'de> DeserializeSeed<'de> for ResourceSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.registration.deserialize)(&mut deserializer, self.entity_manager)
+            .map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,246 @@
+#![cfg(test)]
+
+use serde::{Deserialize, Serialize};
+
+use crate::component::{Component, ComponentBundle};
+use crate::entity::{Entity, EntityManager, Query};
+use crate::resource::Resource;
+use crate::snapshot::*;
+use crate::util::PropertyId;
+use crate::Engine;
+
+#[derive(Component, Serialize, Deserialize, Debug, PartialEq)]
+struct PositionComponent {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Component, Serialize, Deserialize)]
+struct TargetComponent {
+    target: Entity,
+}
+
+#[derive(Component)]
+struct UnregisteredComponent;
+
+#[derive(Resource, Serialize, Deserialize, Debug, PartialEq)]
+struct ScoreResource {
+    score: u32,
+    best_player: Entity,
+}
+
+fn registry() -> TypeRegistry {
+    TypeRegistry::new()
+        .register_component::<PositionComponent>()
+        .register_component::<TargetComponent>()
+        .register_resource::<ScoreResource>()
+}
+
+fn save(entity_manager: &EntityManager) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    entity_manager
+        .save(&registry(), &mut serde_json::Serializer::new(&mut bytes))
+        .unwrap();
+    bytes
+}
+
+#[test]
+fn snapshot_save_load_success() {
+    let mut entity_manager = EntityManager::new();
+    let stale = entity_manager.create_entity(ComponentBundle::new());
+    entity_manager.delete_entity(&stale);
+    let player = entity_manager.create_entity(
+        ComponentBundle::new()
+            .add_component(PositionComponent { x: 1, y: 2 })
+            .add_component(UnregisteredComponent),
+    );
+    let turret = entity_manager.create_entity(
+        ComponentBundle::new()
+            .add_component(PositionComponent { x: 3, y: 4 })
+            .add_component(TargetComponent { target: player }),
+    );
+    let orphan = entity_manager
+        .create_entity(ComponentBundle::new().add_component(TargetComponent { target: stale }));
+    entity_manager.set_parent(&turret, &player);
+    entity_manager.insert_resource(ScoreResource {
+        score: 10,
+        best_player: player,
+    });
+    let bytes = save(&entity_manager);
+
+    // Pre-existing Entities force loaded ones onto different handles.
+    let mut loaded_manager = EntityManager::new();
+    (0..3).for_each(|_| {
+        loaded_manager.create_entity(ComponentBundle::new());
+    });
+    let loaded = loaded_manager
+        .load(
+            &registry(),
+            &mut serde_json::Deserializer::from_slice(&bytes),
+        )
+        .unwrap();
+    assert_eq!(loaded.len(), 3);
+    let find = |position: PositionComponent| -> Entity {
+        loaded_manager
+            .filter(Query::new().with::<PositionComponent>())
+            .iter()
+            .find(|result| *result.get_component::<PositionComponent>().unwrap() == position)
+            .unwrap()
+            .entity_id()
+    };
+    let loaded_player = find(PositionComponent { x: 1, y: 2 });
+    let loaded_turret = find(PositionComponent { x: 3, y: 4 });
+    assert_ne!(loaded_player, player);
+
+    assert!(!loaded_manager.has::<UnregisteredComponent>(&loaded_player));
+    assert_eq!(
+        loaded_manager
+            .get::<TargetComponent>(&loaded_turret)
+            .unwrap()
+            .target,
+        loaded_player
+    );
+    assert_eq!(
+        loaded_manager.get_parent(&loaded_turret),
+        Some(loaded_player)
+    );
+    assert_eq!(loaded_manager.get_children(&loaded_player), [loaded_turret]);
+    let loaded_orphan = loaded
+        .iter()
+        .find(|entity_id| !loaded_manager.has::<PositionComponent>(entity_id))
+        .unwrap();
+    assert_eq!(
+        loaded_manager
+            .get::<TargetComponent>(loaded_orphan)
+            .unwrap()
+            .target,
+        Entity::DANGLING
+    );
+    assert!(entity_manager.is_alive(&orphan));
+
+    let score = loaded_manager.get_resource::<ScoreResource>().unwrap();
+    assert_eq!(
+        *score.read().unwrap(),
+        ScoreResource {
+            score: 10,
+            best_player: loaded_player,
+        }
+    );
+}
+
+#[test]
+fn snapshot_load_unregistered_component_fails() {
+    let mut entity_manager = EntityManager::new();
+    entity_manager
+        .create_entity(ComponentBundle::new().add_component(PositionComponent { x: 1, y: 2 }));
+    let bytes = save(&entity_manager);
+
+    let result = EntityManager::new().load(
+        &TypeRegistry::new(),
+        &mut serde_json::Deserializer::from_slice(&bytes),
+    );
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains(PositionComponent::property_string()));
+}
+
+#[test]
+fn snapshot_engine_save_load_success() {
+    let mut engine = Engine::new();
+    engine.insert_resource(ScoreResource {
+        score: 5,
+        best_player: Entity::DANGLING,
+    });
+    let mut bytes = Vec::new();
+    engine
+        .save(&registry(), &mut serde_json::Serializer::new(&mut bytes))
+        .unwrap();
+
+    engine.reset();
+    engine
+        .load(
+            &registry(),
+            &mut serde_json::Deserializer::from_slice(&bytes),
+        )
+        .unwrap();
+    assert_eq!(
+        engine
+            .get_resource::<ScoreResource>()
+            .unwrap()
+            .read()
+            .unwrap()
+            .score,
+        5
+    );
+}
+
+#[test]
+fn snapshot_named_registration_success() {
+    let mut entity_manager = EntityManager::new();
+    entity_manager
+        .create_entity(ComponentBundle::new().add_component(PositionComponent { x: 1, y: 2 }));
+    entity_manager.insert_resource(ScoreResource {
+        score: 3,
+        best_player: Entity::DANGLING,
+    });
+    let bytes = save(&entity_manager);
+
+    // Re-registering under a new name saves the new name, but still loads the old one.
+    let named_registry = registry()
+        .register_named_component::<PositionComponent>("Position")
+        .register_named_resource::<ScoreResource>("Score");
+    let mut loaded = EntityManager::new();
+    loaded
+        .load(
+            &named_registry,
+            &mut serde_json::Deserializer::from_slice(&bytes),
+        )
+        .unwrap();
+    let mut named_bytes = Vec::new();
+    loaded
+        .save(
+            &named_registry,
+            &mut serde_json::Serializer::new(&mut named_bytes),
+        )
+        .unwrap();
+    let named_json = String::from_utf8(named_bytes.clone()).unwrap();
+    assert!(named_json.contains("\"Position\""));
+    assert!(named_json.contains("\"Score\""));
+    assert!(!named_json.contains(PositionComponent::property_string()));
+
+    let renamed_registry = TypeRegistry::new()
+        .register_named_component::<PositionComponent>("Position")
+        .register_named_resource::<ScoreResource>("Score");
+    let mut reloaded = EntityManager::new();
+    reloaded
+        .load(
+            &renamed_registry,
+            &mut serde_json::Deserializer::from_slice(&named_bytes),
+        )
+        .unwrap();
+    let results = reloaded.filter(Query::new().with::<PositionComponent>());
+    assert_eq!(
+        *reloaded
+            .get::<PositionComponent>(&results[0].entity_id())
+            .unwrap(),
+        PositionComponent { x: 1, y: 2 }
+    );
+    assert_eq!(
+        reloaded
+            .get_resource::<ScoreResource>()
+            .unwrap()
+            .read()
+            .unwrap()
+            .score,
+        3
+    );
+}
+
+#[test]
+#[should_panic]
+fn snapshot_register_taken_name_panics() {
+    TypeRegistry::new()
+        .register_named_component::<PositionComponent>("Position")
+        .register_named_component::<TargetComponent>("Position");
+}
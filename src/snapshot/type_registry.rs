@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::component::{Component, ComponentBundle, ComponentColumn};
+use crate::entity::EntityManager;
+use crate::hierarchy::{Children, Parent};
+use crate::resource::Resource;
+use crate::util::register_property;
+
+type SerializeComponentFn =
+    for<'a> fn(&'a dyn ComponentColumn, usize) -> &'a dyn erased_serde::Serialize;
+type DeserializeComponentFn = fn(
+    &mut dyn erased_serde::Deserializer<'_>,
+    ComponentBundle,
+) -> Result<ComponentBundle, erased_serde::Error>;
+type SerializeResourceFn = fn(&EntityManager) -> Option<Box<dyn erased_serde::Serialize>>;
+type DeserializeResourceFn = fn(
+    &mut dyn erased_serde::Deserializer<'_>,
+    &mut EntityManager,
+) -> Result<(), erased_serde::Error>;
+
+/// How to (de)serialize a registered `Component` type.
+pub(crate) struct ComponentRegistration {
+    pub name: &'static str,
+    pub serialize: SerializeComponentFn,
+    pub deserialize: DeserializeComponentFn,
+}
+
+/// How to (de)serialize a registered `Resource` type.
+pub(crate) struct ResourceRegistration {
+    pub name: &'static str,
+    pub serialize: SerializeResourceFn,
+    pub deserialize: DeserializeResourceFn,
+}
+
+/// Lists the `Component` and `Resource` types that are saved to and loaded from snapshots (see
+/// `EntityManager::save` / `EntityManager::load`), keyed by name. Types that aren't registered
+/// (e.g. handles to a renderer) are left out when saving.
+///
+/// By default, types are named by their `PropertyId::property_string`, i.e. `std::any::type_name`.
+/// It is not guaranteed to be stable across compiler versions, and changes when a type is moved
+/// to another module, after which snapshots saved before can no longer be loaded. Register types
+/// under an explicit name (`register_named_component` / `register_named_resource`) for snapshots
+/// that outlive a build. Registering a type again under a new name saves it under the new name,
+/// while still loading the old one.
+///
+/// `Parent` and `Children` (as well as the transform `Component`s, if enabled) are registered by
+/// default, under their bare type names (e.g. `"Parent"`).
+///
+/// ```ignore
+/// let registry = TypeRegistry::new()
+///     .register_named_component::<PositionComponent>("Position")
+///     .register_resource::<ScoreResource>();
+/// ```
+pub struct TypeRegistry {
+    components: HashMap<u64, ComponentRegistration>,
+    component_names: HashMap<&'static str, u64>,
+    resources: HashMap<u64, ResourceRegistration>,
+    resource_names: HashMap<&'static str, u64>,
+}
+
+impl Default for TypeRegistry {
+    fn default() -> Self {
+        TypeRegistry::new()
+    }
+}
+
+impl TypeRegistry {
+    pub fn new() -> Self {
+        let registry = TypeRegistry {
+            components: HashMap::new(),
+            component_names: HashMap::new(),
+            resources: HashMap::new(),
+            resource_names: HashMap::new(),
+        }
+        .register_named_component::<Parent>("Parent")
+        .register_named_component::<Children>("Children");
+
+        #[cfg(feature = "transform")]
+        let registry = {
+            use crate::transform::*;
+            registry
+                .register_named_component::<Transform2D>("Transform2D")
+                .register_named_component::<GlobalTransform2D>("GlobalTransform2D")
+                .register_named_component::<Transform3D>("Transform3D")
+                .register_named_component::<GlobalTransform3D>("GlobalTransform3D")
+        };
+
+        registry
+    }
+
+    /// Registers the `Component` of type `T` under its `property_string`, so that it is saved and
+    /// loaded. See `register_named_component` for snapshots that must outlive a build.
+    pub fn register_component<T>(self) -> Self
+    where
+        T: 'static + Component + Serialize + DeserializeOwned,
+    {
+        self.register_named_component::<T>(T::property_string())
+    }
+
+    /// Registers the `Component` of type `T` under `name`, so that it is saved and loaded.
+    ///
+    /// Will panic if `name` is already registered for a different `Component`.
+    pub fn register_named_component<T>(mut self, name: &'static str) -> Self
+    where
+        T: 'static + Component + Serialize + DeserializeOwned,
+    {
+        let component_id = register_property::<T>();
+        check_name(&self.component_names, name, component_id);
+        self.component_names.insert(name, component_id);
+        self.components.insert(
+            component_id,
+            ComponentRegistration {
+                name,
+                serialize: serialize_component::<T>,
+                deserialize: deserialize_component::<T>,
+            },
+        );
+        self
+    }
+
+    /// Registers the `Resource` of type `T` under its `property_string`, so that it is saved and
+    /// loaded. See `register_named_resource` for snapshots that must outlive a build.
+    pub fn register_resource<T>(self) -> Self
+    where
+        T: Resource + Serialize + DeserializeOwned,
+    {
+        self.register_named_resource::<T>(T::property_string())
+    }
+
+    /// Registers the `Resource` of type `T` under `name`, so that it is saved and loaded.
+    ///
+    /// Will panic if `name` is already registered for a different `Resource`.
+    pub fn register_named_resource<T>(mut self, name: &'static str) -> Self
+    where
+        T: Resource + Serialize + DeserializeOwned,
+    {
+        let resource_id = register_property::<T>();
+        check_name(&self.resource_names, name, resource_id);
+        self.resource_names.insert(name, resource_id);
+        self.resources.insert(
+            resource_id,
+            ResourceRegistration {
+                name,
+                serialize: serialize_resource::<T>,
+                deserialize: deserialize_resource::<T>,
+            },
+        );
+        self
+    }
+
+    pub(crate) fn get_component(&self, component_id: &u64) -> Option<&ComponentRegistration> {
+        self.components.get(component_id)
+    }
+
+    #[cfg(feature = "scene")]
+    pub(crate) fn get_component_id_by_name(&self, name: &str) -> Option<u64> {
+        self.component_names.get(name).copied()
+    }
+
+    pub(crate) fn get_component_by_name(&self, name: &str) -> Option<&ComponentRegistration> {
+        self.component_names
+            .get(name)
+            .and_then(|component_id| self.components.get(component_id))
+    }
+
+    pub(crate) fn get_resource_by_name(&self, name: &str) -> Option<&ResourceRegistration> {
+        self.resource_names
+            .get(name)
+            .and_then(|resource_id| self.resources.get(resource_id))
+    }
+
+    pub(crate) fn get_resources(&self) -> impl Iterator<Item = &ResourceRegistration> {
+        self.resources.values()
+    }
+}
+
+/// Will panic if `name` is already registered for a type other than the one with `property_id`.
+fn check_name(names: &HashMap<&'static str, u64>, name: &str, property_id: u64) {
+    if names
+        .get(name)
+        .is_some_and(|registered_id| *registered_id != property_id)
+    {
+        panic!("Name: {} is already registered to another type!", name);
+    }
+}
+
+fn serialize_component<T: 'static + Component + Serialize>(
+    column: &dyn ComponentColumn,
+    row: usize,
+) -> &dyn erased_serde::Serialize {
+    column.typed::<T>().get(row)
+}
+
+fn deserialize_component<T: 'static + Component + DeserializeOwned>(
+    deserializer: &mut dyn erased_serde::Deserializer<'_>,
+    components: ComponentBundle,
+) -> Result<ComponentBundle, erased_serde::Error> {
+    let component: T = erased_serde::deserialize(deserializer)?;
+    components
+        .try_add_component(component)
+        .map_err(serde::de::Error::custom)
+}
+
+/// Serializes a `Resource` by locking it once serialization begins.
+struct ResourceSerializer<T>(Arc<RwLock<T>>);
+
+impl<T: Serialize> Serialize for ResourceSerializer<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.read().unwrap().serialize(serializer)
+    }
+}
+
+fn serialize_resource<T: Resource + Serialize>(
+    entity_manager: &EntityManager,
+) -> Option<Box<dyn erased_serde::Serialize>> {
+    entity_manager
+        .get_resource::<T>()
+        .map(|resource| Box::new(ResourceSerializer(resource)) as Box<dyn erased_serde::Serialize>)
+}
+
+fn deserialize_resource<T: Resource + DeserializeOwned>(
+    deserializer: &mut dyn erased_serde::Deserializer<'_>,
+    entity_manager: &mut EntityManager,
+) -> Result<(), erased_serde::Error> {
+    let resource: T = erased_serde::deserialize(deserializer)?;
+    entity_manager.insert_resource(resource);
+    Ok(())
+}
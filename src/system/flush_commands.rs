@@ -0,0 +1,15 @@
+use std::sync::{Arc, RwLock};
+
+use crate::entity::EntityManager;
+use crate::system::{System, SystemParamAccessor};
+
+/// A built-in `System` that applies all `Commands` submitted so far. The `Engine` already does so
+/// after every executed `SystemGroup`; register this to also do so between `System`s of a group,
+/// e.g. so that Entities spawned by one `System` are visible to the next.
+#[derive(System)]
+pub struct FlushCommandsSystem;
+impl System for FlushCommandsSystem {
+    fn execute(entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {
+        entity_manager.write().unwrap().flush_commands();
+    }
+}
@@ -1,10 +1,26 @@
+mod flush_commands;
+mod run_condition;
+mod schedule;
+#[allow(clippy::module_inception)]
 mod system;
+mod system_access;
+mod system_arg;
+mod system_context;
+mod system_function;
+mod system_group;
 mod system_manager;
 mod system_param;
-mod system_group;
 mod test;
 
+pub use self::flush_commands::*;
+pub(crate) use self::run_condition::*;
+pub(crate) use self::schedule::Schedule;
+pub use self::schedule::{ScheduleReport, SystemConflict};
 pub use self::system::*;
-pub use self::system_param::*;
+pub use self::system_access::*;
+pub use self::system_arg::*;
+pub(crate) use self::system_context::*;
+pub use self::system_function::*;
 pub use self::system_group::*;
 pub(crate) use self::system_manager::*;
+pub use self::system_param::*;
@@ -0,0 +1,35 @@
+use std::sync::{Arc, Mutex};
+
+use crate::entity::EntityManager;
+use crate::system::{SystemAccess, SystemFunction, SystemParamAccessor};
+
+type RunConditionFn = Arc<dyn Fn(&EntityManager, &SystemParamAccessor) -> bool + Send + Sync>;
+
+/// A function or closure returning `bool`, which decides whether a `System` or `SystemGroup` is
+/// executed (see `SystemGroup::run_if` / `SystemGroup::run_group_if`). Its arguments are
+/// extracted like those of a function `System`, e.g. `|state: Res<GameState>| !state.paused`.
+#[derive(Clone)]
+pub(crate) struct RunCondition {
+    access: SystemAccess,
+    evaluate: RunConditionFn,
+}
+
+impl RunCondition {
+    pub fn new<M, C: SystemFunction<M, Output = bool>>(condition: C) -> Self {
+        let system_state = Mutex::new(C::init_system_state());
+        RunCondition {
+            access: C::access(),
+            evaluate: Arc::new(move |entity_manager, params| {
+                condition.run(&mut system_state.lock().unwrap(), entity_manager, params)
+            }),
+        }
+    }
+
+    pub fn get_access(&self) -> &SystemAccess {
+        &self.access
+    }
+
+    pub fn evaluate(&self, entity_manager: &EntityManager, params: &SystemParamAccessor) -> bool {
+        (self.evaluate)(entity_manager, params)
+    }
+}
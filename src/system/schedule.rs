@@ -0,0 +1,118 @@
+use std::fmt;
+
+use crate::system::{AccessConflict, RegisteredSystem};
+
+/// The order in which the `System`s of a `SystemGroup` are executed. `System`s within the same
+/// stage may run at the same time, while stages run one after another.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Schedule {
+    stages: Vec<Vec<usize>>,
+    conflicts: Vec<(usize, usize, AccessConflict)>,
+}
+
+impl Schedule {
+    /// Executes every `System` in its own stage, in order.
+    pub fn new_sequential(systems: &[RegisteredSystem]) -> Self {
+        Schedule {
+            stages: (0..systems.len()).map(|index| vec![index]).collect(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Places every `System` into the earliest stage after all previously registered `System`s it
+    /// conflicts with, so that conflicting `System`s still execute in order of registration.
+    pub fn new_parallel(systems: &[RegisteredSystem]) -> Self {
+        let mut schedule = Schedule::default();
+        let mut system_stages: Vec<usize> = Vec::with_capacity(systems.len());
+        systems.iter().enumerate().for_each(|(index, system)| {
+            let mut stage = 0;
+            systems[..index]
+                .iter()
+                .enumerate()
+                .for_each(|(other_index, other)| {
+                    if let Some(conflict) = system.get_access().get_conflict(other.get_access()) {
+                        stage = stage.max(system_stages[other_index] + 1);
+                        schedule.conflicts.push((index, other_index, conflict));
+                    }
+                });
+            if stage == schedule.stages.len() {
+                schedule.stages.push(Vec::new());
+            }
+            schedule.stages[stage].push(index);
+            system_stages.push(stage);
+        });
+        schedule
+    }
+
+    pub fn get_stages(&self) -> &[Vec<usize>] {
+        &self.stages
+    }
+
+    pub fn get_report(&self, systems: &[RegisteredSystem]) -> ScheduleReport {
+        ScheduleReport {
+            stages: self
+                .stages
+                .iter()
+                .map(|stage| {
+                    stage
+                        .iter()
+                        .map(|index| systems[*index].get_system_name())
+                        .collect()
+                })
+                .collect(),
+            conflicts: self
+                .conflicts
+                .iter()
+                .map(|(index, other_index, conflict)| SystemConflict {
+                    system: systems[*index].get_system_name(),
+                    runs_after: systems[*other_index].get_system_name(),
+                    conflict: conflict.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Describes how the `System`s of a `SystemGroup` will be executed, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleReport {
+    stages: Vec<Vec<&'static str>>,
+    conflicts: Vec<SystemConflict>,
+}
+
+impl ScheduleReport {
+    /// The names of the `System`s in each stage. Stages are executed in order, and the `System`s
+    /// within a stage may run at the same time.
+    pub fn get_stages(&self) -> &[Vec<&'static str>] {
+        &self.stages
+    }
+
+    /// Every pair of `System`s that were kept apart due to conflicting access.
+    pub fn get_conflicts(&self) -> &[SystemConflict] {
+        &self.conflicts
+    }
+}
+
+impl fmt::Display for ScheduleReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, stage) in self.stages.iter().enumerate() {
+            writeln!(f, "Stage {}: {}", index, stage.join(", "))?;
+        }
+        for conflict in &self.conflicts {
+            writeln!(
+                f,
+                "{} runs after {} due to {}",
+                conflict.system, conflict.runs_after, conflict.conflict
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A `System` that was forced to run after another, earlier registered `System`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemConflict {
+    pub system: &'static str,
+    pub runs_after: &'static str,
+    pub conflict: AccessConflict,
+}
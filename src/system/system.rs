@@ -3,7 +3,7 @@ pub use moecs_macros::System;
 use std::sync::{Arc, RwLock};
 
 use crate::entity::EntityManager;
-use crate::system::SystemParamAccessor;
+use crate::system::{SystemAccess, SystemParamAccessor};
 use crate::util::PropertyId;
 
 /// A `System` is a process that operates over a subset of entities, generally with known
@@ -13,4 +13,53 @@ use crate::util::PropertyId;
 /// Note: All user-defined `SystemParam`s must derive this trait via `#[derive(System)]`.
 pub trait System: PropertyId {
     fn execute(entity_manager: Arc<RwLock<EntityManager>>, params: Arc<SystemParamAccessor>);
+
+    /// Declares what this `System` accesses, so that parallel `SystemGroup`s know which `System`s
+    /// may run at the same time. Defaults to exclusive access, i.e. never running alongside another
+    /// `System`.
+    ///
+    /// Note: a `System` must not access anything it does not declare here, e.g. a `System`
+    /// declaring only `Component` access should only ever take `entity_manager.read()`.
+    fn access() -> SystemAccess {
+        SystemAccess::exclusive()
+    }
+}
+
+/// A `System` with state of its own (e.g. a timer, a cache, or configuration), executed via
+/// `&mut self`. Registered as an instance via `SystemGroup::register_instance`, which allows the
+/// same type to be registered more than once:
+///
+/// ```ignore
+/// #[derive(System)]
+/// struct SpawnerSystem {
+///     interval: f32,
+///     cooldown: f32,
+/// }
+/// impl StatefulSystem for SpawnerSystem {
+///     fn execute(
+///         &mut self,
+///         entity_manager: Arc<RwLock<EntityManager>>,
+///         params: Arc<SystemParamAccessor>,
+///     ) {
+///         // Count down `self.cooldown`, spawn once it reaches 0 and reset it to `self.interval`.
+///     }
+/// }
+///
+/// let group = SystemGroup::new_sequential_group()
+///     .register_instance(SpawnerSystem { interval: 1.0, cooldown: 0.0 })
+///     .register_instance(SpawnerSystem { interval: 5.0, cooldown: 5.0 });
+/// ```
+///
+/// Note: `StatefulSystem`s must also use `#[derive(System)]`.
+pub trait StatefulSystem: PropertyId + Send {
+    fn execute(
+        &mut self,
+        entity_manager: Arc<RwLock<EntityManager>>,
+        params: Arc<SystemParamAccessor>,
+    );
+
+    /// Declares what this `System` accesses, like `System::access`. Defaults to exclusive access.
+    fn access(&self) -> SystemAccess {
+        SystemAccess::exclusive()
+    }
 }
@@ -0,0 +1,165 @@
+use std::fmt;
+
+use crate::component::{Component, ComponentAccess};
+use crate::entity::{Query, QueryData, QueryFilter};
+use crate::resource::Resource;
+
+/// Describes what a `System` accesses when executed. Parallel `SystemGroup`s use this to decide
+/// which `System`s may safely run at the same time.
+///
+/// Declared via `System::access`. A `System` that does not declare its access is assumed to be
+/// exclusive, e.g. because it calls `entity_manager.write()`.
+///
+/// Exclusive and pinned `System`s always execute on the thread that executes their `SystemGroup`,
+/// which is required to access `SystemParam`s added via `SystemParamAccessor::add_local_param`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SystemAccess {
+    component_access: ComponentAccess,
+    resource_access: ComponentAccess,
+    exclusive: bool,
+    pinned: bool,
+}
+
+impl SystemAccess {
+    /// Creates a new `SystemAccess` that accesses nothing.
+    pub fn new() -> Self {
+        SystemAccess {
+            component_access: ComponentAccess::new(),
+            resource_access: ComponentAccess::new(),
+            exclusive: false,
+            pinned: false,
+        }
+    }
+
+    /// Creates a new `SystemAccess` that may access anything, and therefore conflicts with every
+    /// other `System`.
+    pub fn exclusive() -> Self {
+        SystemAccess {
+            component_access: ComponentAccess::new(),
+            resource_access: ComponentAccess::new(),
+            exclusive: true,
+            pinned: true,
+        }
+    }
+
+    /// Records shared access to `Component`s of type `T`.
+    pub fn add_read<T: 'static + Component>(mut self) -> Self {
+        self.component_access.add_read::<T>();
+        self
+    }
+
+    /// Records exclusive access to `Component`s of type `T`.
+    pub fn add_write<T: 'static + Component>(mut self) -> Self {
+        self.component_access.add_write::<T>();
+        self
+    }
+
+    /// Records the access of a typed `Query` fetching `D`, e.g. `add_query::<(&mut A, &B)>()`.
+    pub fn add_query<D: QueryData>(mut self) -> Self {
+        let mut query_access = ComponentAccess::new();
+        D::add_access(&mut query_access);
+        self.component_access.extend(&query_access);
+        self
+    }
+
+    /// Records the access of a typed `Query` fetching `D` and filtered by `F`, including the
+    /// `Component`s read by `Added` / `Changed` filters, e.g.
+    /// `add_filtered_query::<&mut A, Changed<B>>()`.
+    pub fn add_filtered_query<D: QueryData, F: QueryFilter>(mut self) -> Self {
+        self.component_access
+            .extend(F::apply(Query::<D>::default()).get_access());
+        self
+    }
+
+    /// Records shared access to the `Resource` of type `T`.
+    pub fn add_resource_read<T: Resource>(mut self) -> Self {
+        self.resource_access
+            .add_read_by_id(T::property_id(), T::property_string());
+        self
+    }
+
+    /// Records exclusive access to the `Resource` of type `T`.
+    pub fn add_resource_write<T: Resource>(mut self) -> Self {
+        self.resource_access
+            .add_write_by_id(T::property_id(), T::property_string());
+        self
+    }
+
+    /// Pins the `System` to the thread that executes its `SystemGroup`, e.g. because it accesses a
+    /// `SystemParam` that isn't thread-safe. Pinned `System`s may still run at the same time as
+    /// others.
+    pub fn pinned(mut self) -> Self {
+        self.pinned = true;
+        self
+    }
+
+    /// Merges all access recorded in `other` into this one.
+    pub(crate) fn extend(&mut self, other: &SystemAccess) {
+        self.component_access.extend(&other.component_access);
+        self.resource_access.extend(&other.resource_access);
+        self.exclusive |= other.exclusive;
+        self.pinned |= other.pinned;
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    pub fn get_component_access(&self) -> &ComponentAccess {
+        &self.component_access
+    }
+
+    /// The `Resource`s accessed, keyed by their `PropertyId`.
+    pub fn get_resource_access(&self) -> &ComponentAccess {
+        &self.resource_access
+    }
+
+    /// Returns why this and `other` may not run at the same time, or `None` if they may.
+    pub fn get_conflict(&self, other: &SystemAccess) -> Option<AccessConflict> {
+        if self.exclusive || other.exclusive {
+            return Some(AccessConflict::Exclusive);
+        }
+        let components = self.component_access.get_conflicts(&other.component_access);
+        if !components.is_empty() {
+            return Some(AccessConflict::Components(components));
+        }
+        let resources = self.resource_access.get_conflicts(&other.resource_access);
+        if !resources.is_empty() {
+            return Some(AccessConflict::Resources(resources));
+        }
+        None
+    }
+}
+
+/// The reason two `System`s may not run at the same time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessConflict {
+    /// At least one of the `System`s has exclusive access.
+    Exclusive,
+    /// The named `Component` types are written by one `System` and read or written by the other.
+    Components(Vec<&'static str>),
+    /// The named `Resource`s are written by one `System` and read or written by the other.
+    Resources(Vec<&'static str>),
+}
+
+impl fmt::Display for AccessConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessConflict::Exclusive => write!(f, "exclusive access"),
+            AccessConflict::Components(components) => write!(
+                f,
+                "conflicting access to Components: {}",
+                components.join(", ")
+            ),
+            AccessConflict::Resources(resources) => write!(
+                f,
+                "conflicting access to Resources: {}",
+                resources.join(", ")
+            ),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::entity::{Commands, EntityManager, Query, QueryBorrow, QueryData, QueryFilter};
+use crate::system::{SystemAccess, SystemParam, SystemParamAccessor};
+
+/// An argument of a function `System` (see `SystemGroup::add_system`), extracted automatically
+/// whenever the `System` is executed.
+///
+/// Implemented for:
+/// *   `QueryBorrow<D>`: the result of executing `Query::<D>::default()`.
+/// *   `Filtered<D, F>`: like `QueryBorrow<D>`, with the `QueryFilter` `F` applied, e.g.
+///     `Filtered<&mut A, (Without<B>, Changed<A>)>`.
+/// *   `Res<T>` / `ResMut<T>`: shared / exclusive access to the `Resource` of type `T`, which
+///     must exist. `Option<Res<T>>` / `Option<ResMut<T>>` are `None` if it doesn't.
+/// *   `Param<T>` / `ParamMut<T>`: shared / exclusive access to the `SystemParam` of type `T`,
+///     which must have been added to the `SystemParamAccessor`.
+/// *   `EventReader<T>` / `EventWriter<T>`: reading / sending `Event`s of type `T`, which must
+///     have been registered via `Engine::add_event`.
+/// *   `Commands`: recording structural changes.
+/// *   `Local<T>`: a value kept by the `System` between executions.
+pub trait SystemArg {
+    /// Data kept by the `System` between executions, e.g. the value of a `Local<T>`.
+    #[doc(hidden)]
+    type SystemState: 'static + Send;
+
+    /// Data retrieved before the argument is fetched, e.g. a handle to a `Resource`.
+    #[doc(hidden)]
+    type State;
+
+    /// The argument passed to the `System`.
+    type Item<'w>;
+
+    /// Creates the `SystemState`, once, when the `System` is registered.
+    #[doc(hidden)]
+    fn init_system_state() -> Self::SystemState;
+
+    /// Records what the argument accesses.
+    fn add_access(access: SystemAccess) -> SystemAccess;
+
+    #[doc(hidden)]
+    fn prepare(entity_manager: &EntityManager, params: &SystemParamAccessor) -> Self::State;
+
+    #[doc(hidden)]
+    fn fetch<'w>(
+        system_state: &'w mut Self::SystemState,
+        state: &'w mut Self::State,
+        entity_manager: &'w EntityManager,
+    ) -> Self::Item<'w>;
+}
+
+impl SystemArg for Commands<'_> {
+    type SystemState = ();
+    type State = ();
+    type Item<'w> = Commands<'w>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access
+    }
+
+    fn prepare(_entity_manager: &EntityManager, _params: &SystemParamAccessor) {}
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        _state: &'w mut (),
+        entity_manager: &'w EntityManager,
+    ) -> Commands<'w> {
+        entity_manager.commands()
+    }
+}
+
+impl<D: 'static + QueryData> SystemArg for QueryBorrow<'_, D> {
+    type SystemState = ();
+    type State = ();
+    type Item<'w> = QueryBorrow<'w, D>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.add_query::<D>()
+    }
+
+    fn prepare(_entity_manager: &EntityManager, _params: &SystemParamAccessor) {}
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        _state: &'w mut (),
+        entity_manager: &'w EntityManager,
+    ) -> QueryBorrow<'w, D> {
+        entity_manager.query(&Query::<D>::default())
+    }
+}
+
+/// The result of executing `Query::<D>::default()` with the `QueryFilter` `F` applied, as an
+/// argument of a function `System`. Dereferences to a `QueryBorrow<D>`, e.g.:
+///
+/// ```ignore
+/// fn log_moves(mut moved: Filtered<(Entity, &PositionComponent), Changed<PositionComponent>>) {
+///     moved.iter().for_each(|(entity, position)| println!("{} moved to {:?}", entity, position));
+/// }
+/// ```
+pub struct Filtered<'w, D: QueryData, F: QueryFilter = ()> {
+    query: QueryBorrow<'w, D>,
+    marker: PhantomData<fn() -> F>,
+}
+
+impl<'w, D: QueryData, F: QueryFilter> Deref for Filtered<'w, D, F> {
+    type Target = QueryBorrow<'w, D>;
+
+    fn deref(&self) -> &QueryBorrow<'w, D> {
+        &self.query
+    }
+}
+
+impl<'w, D: QueryData, F: QueryFilter> DerefMut for Filtered<'w, D, F> {
+    fn deref_mut(&mut self) -> &mut QueryBorrow<'w, D> {
+        &mut self.query
+    }
+}
+
+impl<D: 'static + QueryData, F: QueryFilter> SystemArg for Filtered<'_, D, F> {
+    type SystemState = Query<D>;
+    type State = ();
+    type Item<'w> = Filtered<'w, D, F>;
+
+    fn init_system_state() -> Query<D> {
+        F::apply(Query::<D>::default())
+    }
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.add_filtered_query::<D, F>()
+    }
+
+    fn prepare(_entity_manager: &EntityManager, _params: &SystemParamAccessor) {}
+
+    fn fetch<'w>(
+        system_state: &'w mut Query<D>,
+        _state: &'w mut (),
+        entity_manager: &'w EntityManager,
+    ) -> Filtered<'w, D, F> {
+        Filtered {
+            query: entity_manager.query(system_state),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// A value of type `T` owned by a function `System`, kept between its executions (e.g. a timer or a
+/// cursor). Starts out as `T::default()`. Every registered function `System`, and every `Local`
+/// argument of one, has its own value.
+pub struct Local<'w, T> {
+    value: &'w mut T,
+}
+
+impl<T> Deref for Local<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for Local<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<T: 'static + Default + Send> SystemArg for Local<'_, T> {
+    type SystemState = T;
+    type State = ();
+    type Item<'w> = Local<'w, T>;
+
+    fn init_system_state() -> T {
+        T::default()
+    }
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access
+    }
+
+    fn prepare(_entity_manager: &EntityManager, _params: &SystemParamAccessor) {}
+
+    fn fetch<'w>(
+        system_state: &'w mut T,
+        _state: &'w mut (),
+        _entity_manager: &'w EntityManager,
+    ) -> Local<'w, T> {
+        Local {
+            value: system_state,
+        }
+    }
+}
+
+/// Shared access to the `SystemParam` of type `T`, as an argument of a function `System`.
+///
+/// Note: only `SystemParam`s without lifetimes may be accessed this way. Since `SystemParam`s may
+/// have been added via `SystemParamAccessor::add_local_param`, function `System`s accessing them
+/// are pinned (see `SystemAccess::pinned`).
+pub struct Param<'w, T> {
+    guard: RwLockReadGuard<'w, T>,
+}
+
+impl<T> Deref for Param<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> SystemArg for Param<'_, T>
+where
+    T: 'static + for<'p> SystemParam<Rebind<'p> = T>,
+{
+    type SystemState = ();
+    type State = Arc<RwLock<T>>;
+    type Item<'w> = Param<'w, T>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.pinned()
+    }
+
+    fn prepare(_entity_manager: &EntityManager, params: &SystemParamAccessor) -> Arc<RwLock<T>> {
+        get_param::<T>(params)
+    }
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        state: &'w mut Arc<RwLock<T>>,
+        _entity_manager: &'w EntityManager,
+    ) -> Param<'w, T> {
+        Param {
+            guard: state.read().unwrap(),
+        }
+    }
+}
+
+/// Exclusive access to the `SystemParam` of type `T`, as an argument of a function `System`. See
+/// `Param`.
+pub struct ParamMut<'w, T> {
+    guard: RwLockWriteGuard<'w, T>,
+}
+
+impl<T> Deref for ParamMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for ParamMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<T> SystemArg for ParamMut<'_, T>
+where
+    T: 'static + for<'p> SystemParam<Rebind<'p> = T>,
+{
+    type SystemState = ();
+    type State = Arc<RwLock<T>>;
+    type Item<'w> = ParamMut<'w, T>;
+
+    fn init_system_state() {}
+
+    fn add_access(access: SystemAccess) -> SystemAccess {
+        access.pinned()
+    }
+
+    fn prepare(_entity_manager: &EntityManager, params: &SystemParamAccessor) -> Arc<RwLock<T>> {
+        get_param::<T>(params)
+    }
+
+    fn fetch<'w>(
+        _system_state: &'w mut (),
+        state: &'w mut Arc<RwLock<T>>,
+        _entity_manager: &'w EntityManager,
+    ) -> ParamMut<'w, T> {
+        ParamMut {
+            guard: state.write().unwrap(),
+        }
+    }
+}
+
+fn get_param<T>(params: &SystemParamAccessor) -> Arc<RwLock<T>>
+where
+    T: 'static + for<'p> SystemParam<Rebind<'p> = T>,
+{
+    params
+        .get_param::<T>()
+        .unwrap_or_else(|| panic!("SystemParam: {} not added!", T::property_string()))
+}
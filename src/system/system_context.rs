@@ -0,0 +1,40 @@
+use std::cell::Cell;
+
+thread_local! {
+    static CURRENT_SYSTEM_CONTEXT: Cell<Option<SystemContext>> = const { Cell::new(None) };
+}
+
+/// Describes the `System` currently executing on this thread: `system_id` identifies it, `last_run`
+/// is the change tick of its previous execution, and `this_run` the tick of the current one.
+/// Changes made after `last_run` are reported by change detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SystemContext {
+    pub system_id: u64,
+    pub last_run: u64,
+    pub this_run: u64,
+}
+
+impl SystemContext {
+    /// Returns the context of the `System` currently executing on this thread, if any.
+    pub fn current() -> Option<SystemContext> {
+        CURRENT_SYSTEM_CONTEXT.with(|context| context.get())
+    }
+
+    /// Marks `self` as the context of the `System` executing on this thread, until the returned
+    /// guard is dropped.
+    pub fn enter(self) -> SystemContextGuard {
+        SystemContextGuard {
+            previous: CURRENT_SYSTEM_CONTEXT.with(|context| context.replace(Some(self))),
+        }
+    }
+}
+
+pub(crate) struct SystemContextGuard {
+    previous: Option<SystemContext>,
+}
+
+impl Drop for SystemContextGuard {
+    fn drop(&mut self) {
+        CURRENT_SYSTEM_CONTEXT.with(|context| context.set(self.previous));
+    }
+}
@@ -0,0 +1,122 @@
+use std::any::{type_name, TypeId};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::entity::EntityManager;
+use crate::system::{SystemAccess, SystemArg, SystemParamAccessor};
+
+/// A function or closure usable as a `System`, registered via `SystemGroup::add_system`. Every
+/// argument is a `SystemArg`, extracted whenever the `System` is executed:
+///
+/// ```ignore
+/// fn move_bodies(
+///     bodies: QueryBorrow<(&mut PositionComponent, &VelocityComponent)>,
+///     delta_time: Param<DeltaTime>,
+/// ) {
+///     bodies.for_each(|(position, velocity)| position.x += velocity.x * delta_time.seconds);
+/// }
+///
+/// let group = SystemGroup::new_parallel_group().add_system(move_bodies);
+/// ```
+///
+/// Implemented for functions and closures of up to 8 arguments. `Marker` only serves to tell these
+/// implementations apart. Functions returning `bool` may serve as run conditions instead (see
+/// `SystemGroup::run_if`).
+pub trait SystemFunction<Marker>: 'static + Send + Sync {
+    /// The value returned by the function: `()` for `System`s, `bool` for run conditions.
+    type Output;
+
+    /// The `SystemArg::SystemState` of every argument, kept between executions.
+    #[doc(hidden)]
+    type SystemState: 'static + Send;
+
+    #[doc(hidden)]
+    fn init_system_state() -> Self::SystemState;
+
+    /// The union of the access of every argument. Will panic if two arguments conflict (e.g.
+    /// `Res<T>` and `ResMut<T>`), as fetching both would deadlock.
+    fn access() -> SystemAccess;
+
+    #[doc(hidden)]
+    fn run(
+        &self,
+        system_state: &mut Self::SystemState,
+        entity_manager: &EntityManager,
+        params: &SystemParamAccessor,
+    ) -> Self::Output;
+
+    /// Identifies the function or closure among registered `System`s.
+    #[doc(hidden)]
+    fn system_id() -> u64 {
+        let mut hasher = DefaultHasher::new();
+        TypeId::of::<Self>().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[doc(hidden)]
+    fn system_name() -> &'static str {
+        type_name::<Self>()
+    }
+}
+
+/// Adds the access of `A` to `access`, panicking if it conflicts with that of previous arguments.
+fn add_arg_access<A: SystemArg>(access: &mut SystemAccess, system_name: &str) {
+    let arg_access = A::add_access(SystemAccess::new());
+    if let Some(conflict) = access.get_conflict(&arg_access) {
+        panic!("System: {} has arguments with {}!", system_name, conflict);
+    }
+    access.extend(&arg_access);
+}
+
+macro_rules! impl_system_function {
+    ($($arg:ident),*) => {
+        impl<Out, Func, $($arg: SystemArg),*> SystemFunction<fn($($arg,)*) -> Out> for Func
+        where
+            Func: 'static + Send + Sync + Fn($($arg),*) -> Out + Fn($($arg::Item<'_>),*) -> Out,
+        {
+            type Output = Out;
+
+            type SystemState = ($($arg::SystemState,)*);
+
+            #[allow(clippy::unused_unit)]
+            fn init_system_state() -> Self::SystemState {
+                ($($arg::init_system_state(),)*)
+            }
+
+            fn access() -> SystemAccess {
+                #[allow(unused_mut)]
+                let mut access = SystemAccess::new();
+                $(add_arg_access::<$arg>(&mut access, Self::system_name());)*
+                access
+            }
+
+            #[allow(non_snake_case, unused_variables, clippy::too_many_arguments)]
+            fn run(
+                &self,
+                system_state: &mut Self::SystemState,
+                entity_manager: &EntityManager,
+                params: &SystemParamAccessor,
+            ) -> Out {
+                // Calls `f` through a plain `Fn($($arg),*)` bound, as the compiler can't otherwise
+                // pick between the two `Fn` bounds of `Func`.
+                fn call<Out, $($arg),*>(f: impl Fn($($arg),*) -> Out, $($arg: $arg),*) -> Out {
+                    f($($arg),*)
+                }
+                // Pairs every argument's `SystemState` with the `State` it prepares.
+                let ($($arg,)*) = system_state;
+                $(let mut $arg = ($arg, $arg::prepare(entity_manager, params));)*
+                call(self, $($arg::fetch($arg.0, &mut $arg.1, entity_manager)),*)
+            }
+        }
+    };
+}
+
+impl_system_function!();
+impl_system_function!(A);
+impl_system_function!(A, B);
+impl_system_function!(A, B, C);
+impl_system_function!(A, B, C, D);
+impl_system_function!(A, B, C, D, E);
+impl_system_function!(A, B, C, D, E, F);
+impl_system_function!(A, B, C, D, E, F, G);
+impl_system_function!(A, B, C, D, E, F, G, H);
@@ -1,12 +1,173 @@
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 
 use crate::entity::EntityManager;
-use crate::system::{System, SystemParamAccessor};
+use crate::system::{
+    RunCondition, Schedule, ScheduleReport, StatefulSystem, System, SystemAccess, SystemContext,
+    SystemFunction, SystemParamAccessor,
+};
 use crate::util::ExecutionMode::{self, *};
+use crate::util::{register_property, PropertyId};
+use crate::{Error, Result};
 
 type SystemExecuteFn =
-    fn(entity_manager: Arc<RwLock<EntityManager>>, params: Arc<SystemParamAccessor>);
+    Arc<dyn Fn(Arc<RwLock<EntityManager>>, Arc<SystemParamAccessor>) + Send + Sync>;
+
+/// Returns a new id for an instance of `T`, distinct from that of every other registered `System`
+/// (including other instances of `T`), so that e.g. each has its own `Events` cursor.
+fn new_instance_id<T: StatefulSystem>() -> u64 {
+    static NEXT_INSTANCE: AtomicU64 = AtomicU64::new(0);
+    let mut hasher = DefaultHasher::new();
+    T::property_id().hash(&mut hasher);
+    NEXT_INSTANCE
+        .fetch_add(1, Ordering::Relaxed)
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A `System` registered to a `SystemGroup`, alongside the change tick of its last execution.
+pub(crate) struct RegisteredSystem {
+    system_id: u64,
+    property_id: u64,
+    system_name: &'static str,
+    access: SystemAccess,
+    execute: SystemExecuteFn,
+    run_conditions: Vec<RunCondition>,
+    enabled: bool,
+    last_run: AtomicU64,
+}
+
+impl Clone for RegisteredSystem {
+    fn clone(&self) -> Self {
+        RegisteredSystem {
+            system_id: self.system_id,
+            property_id: self.property_id,
+            system_name: self.system_name,
+            access: self.access.clone(),
+            execute: self.execute.clone(),
+            run_conditions: self.run_conditions.clone(),
+            enabled: self.enabled,
+            last_run: AtomicU64::new(self.get_last_run()),
+        }
+    }
+}
+
+impl RegisteredSystem {
+    fn new<T: 'static + System>() -> Self {
+        let system_id = register_property::<T>();
+        RegisteredSystem::with_execute(
+            system_id,
+            system_id,
+            T::property_string(),
+            T::access(),
+            Arc::new(T::execute),
+        )
+    }
+
+    fn from_function<M, F: SystemFunction<M>>(system: F) -> Self {
+        let system_state = Mutex::new(F::init_system_state());
+        RegisteredSystem::with_execute(
+            F::system_id(),
+            F::system_id(),
+            F::system_name(),
+            F::access(),
+            Arc::new(move |entity_manager, params| {
+                system.run(
+                    &mut system_state.lock().unwrap(),
+                    &entity_manager.read().unwrap(),
+                    &params,
+                );
+            }),
+        )
+    }
+
+    fn from_instance<T: 'static + StatefulSystem>(system: T) -> Self {
+        let access = system.access();
+        let system = Mutex::new(system);
+        RegisteredSystem::with_execute(
+            new_instance_id::<T>(),
+            register_property::<T>(),
+            T::property_string(),
+            access,
+            Arc::new(move |entity_manager, params| {
+                system.lock().unwrap().execute(entity_manager, params);
+            }),
+        )
+    }
+
+    fn with_execute(
+        system_id: u64,
+        property_id: u64,
+        system_name: &'static str,
+        access: SystemAccess,
+        execute: SystemExecuteFn,
+    ) -> Self {
+        RegisteredSystem {
+            system_id,
+            property_id,
+            system_name,
+            access,
+            execute,
+            run_conditions: Vec::new(),
+            enabled: true,
+            last_run: AtomicU64::new(0),
+        }
+    }
+
+    pub fn get_system_id(&self) -> u64 {
+        self.system_id
+    }
+
+    pub fn get_system_name(&self) -> &'static str {
+        self.system_name
+    }
+
+    pub fn get_access(&self) -> &SystemAccess {
+        &self.access
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// The change tick of the last execution, or `0` if the `System` has not been executed yet.
+    pub fn get_last_run(&self) -> u64 {
+        self.last_run.load(Ordering::Acquire)
+    }
+
+    /// Executes the `System`, unless it is disabled or one of its run conditions is not met. Change
+    /// detection within the `System` reports changes made since its last execution.
+    pub fn execute(
+        &self,
+        entity_manager: Arc<RwLock<EntityManager>>,
+        params: Arc<SystemParamAccessor>,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let this_run = entity_manager.read().unwrap().increment_change_tick();
+        let _context = SystemContext {
+            system_id: self.system_id,
+            last_run: self.get_last_run(),
+            this_run,
+        }
+        .enter();
+        if !self.run_conditions.is_empty() {
+            let entity_manager = entity_manager.read().unwrap();
+            if !self
+                .run_conditions
+                .iter()
+                .all(|condition| condition.evaluate(&entity_manager, &params))
+            {
+                return;
+            }
+        }
+        (self.execute)(entity_manager, params);
+        self.last_run.store(this_run, Ordering::Release);
+    }
+}
 
 /// A group of `System`s, defined by the user. This is useful to group and execute similar
 /// `System`s together, and also to provide some freedom in establishing an execution order.
@@ -15,45 +176,358 @@ type SystemExecuteFn =
 ///
 /// The user can define whether a new group should be executed in sequence via
 /// `SystemGroup::new_sequential_group()`, or in parallel via
-/// `SystemGroup::new_parallel_group()`.
+/// `SystemGroup::new_parallel_group()`. Sequential groups execute their `System`s in order of
+/// registration, which can be adjusted via `register_before` / `register_after`, or after the fact
+/// via `move_before` / `move_after`.
+///
+/// Parallel groups run `System`s at the same time only if their declared `SystemAccess` does not
+/// conflict. Conflicting `System`s are executed in order of registration instead; see
+/// `get_schedule_report`.
+///
+/// Individual `System`s, as well as the whole group, may be skipped via run conditions (see
+/// `run_if` / `run_group_if`), or disabled until enabled again (see `set_system_enabled` /
+/// `set_enabled`).
+///
+/// Note: a clone of a `SystemGroup` shares the state of its `System`s with the original, i.e. the
+/// instances registered via `register_instance` and the `Local`s of function `System`s and run
+/// conditions. Everything else, such as the order of the `System`s, whether they are enabled and
+/// when they last ran, is copied.
 #[derive(Clone)]
 pub struct SystemGroup {
-    system_id_to_system: HashMap<u64, SystemExecuteFn>,
+    systems: Vec<RegisteredSystem>,
     execution_mode: ExecutionMode,
+    schedule: Schedule,
+    run_conditions: Vec<RunCondition>,
+    enabled: bool,
+    last_registered: Option<u64>,
 }
 
 impl SystemGroup {
     pub fn new_sequential_group() -> Self {
         SystemGroup {
-            system_id_to_system: HashMap::new(),
+            systems: Vec::new(),
             execution_mode: Sequential,
+            schedule: Schedule::default(),
+            run_conditions: Vec::new(),
+            enabled: true,
+            last_registered: None,
         }
     }
 
     pub fn new_parallel_group() -> Self {
         SystemGroup {
-            system_id_to_system: HashMap::new(),
+            systems: Vec::new(),
             execution_mode: Parallel,
+            schedule: Schedule::default(),
+            run_conditions: Vec::new(),
+            enabled: true,
+            last_registered: None,
         }
     }
 
-    pub fn register<T: 'static + System>(mut self) -> SystemGroup {
-        let system_id = T::property_id();
-        if self.system_id_to_system.contains_key(&system_id) {
-            panic!(
-                "System: {} already registered for SystemGroup!",
-                T::property_string()
-            );
+    /// Registers `T` to execute after all previously registered `System`s.
+    ///
+    /// Note: will panic if `T` is already registered.
+    pub fn register<T: 'static + System>(self) -> SystemGroup {
+        self.try_register::<T>()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Registers `T` to execute after all previously registered `System`s. Fails with
+    /// `Error::SystemAlreadyRegistered` if `T` is already registered.
+    pub fn try_register<T: 'static + System>(mut self) -> Result<SystemGroup> {
+        self.check_not_registered::<T>()?;
+        self.insert_system(self.systems.len(), RegisteredSystem::new::<T>());
+        Ok(self)
+    }
+
+    /// Registers the `StatefulSystem` instance `system` to execute after all previously registered
+    /// `System`s. The same type may be registered any number of times, e.g. with different
+    /// configurations; every instance keeps its own state.
+    ///
+    /// Note: clones of the `SystemGroup` share the registered instances.
+    pub fn register_instance<T: 'static + StatefulSystem>(mut self, system: T) -> SystemGroup {
+        self.insert_system(self.systems.len(), RegisteredSystem::from_instance(system));
+        self
+    }
+
+    /// Registers the function or closure `system` to execute after all previously registered
+    /// `System`s. Its arguments are extracted automatically, and its `SystemAccess` is derived from
+    /// them; see `SystemFunction`.
+    ///
+    /// Note: will panic if `system` is already registered. Clones of the `SystemGroup` share the
+    /// state of `system`'s arguments (e.g. its `Local`s).
+    pub fn add_system<M, F: SystemFunction<M>>(self, system: F) -> SystemGroup {
+        self.try_add_system(system)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Registers the function or closure `system` to execute after all previously registered
+    /// `System`s. Fails with `Error::SystemAlreadyRegistered` if `system` is already registered.
+    pub fn try_add_system<M, F: SystemFunction<M>>(mut self, system: F) -> Result<SystemGroup> {
+        if self
+            .systems
+            .iter()
+            .any(|registered| registered.get_system_id() == F::system_id())
+        {
+            return Err(Error::SystemAlreadyRegistered(F::system_name()));
         }
-        self.system_id_to_system.insert(system_id, T::execute);
+        self.insert_system(self.systems.len(), RegisteredSystem::from_function(system));
+        Ok(self)
+    }
+
+    /// Adds a run condition to the most recently registered `System`, which is then only executed
+    /// if `condition` (and any other run condition of the `System`) returns `true`. `condition` is
+    /// a function or closure returning `bool`, with arguments like a function `System` (see
+    /// `SystemFunction`), e.g.:
+    ///
+    /// ```ignore
+    /// let group = SystemGroup::new_sequential_group()
+    ///     .register::<PhysicsSystem>()
+    ///     .run_if(|state: Res<GameStateResource>| !state.paused);
+    /// ```
+    ///
+    /// The access of `condition` is added to that of the `System`.
+    ///
+    /// Note: will panic if no `System` has been registered yet, or the most recently registered
+    /// one was removed since.
+    pub fn run_if<M, C: SystemFunction<M, Output = bool>>(mut self, condition: C) -> SystemGroup {
+        let condition = RunCondition::new(condition);
+        let system = self
+            .last_registered
+            .and_then(|system_id| {
+                self.systems
+                    .iter_mut()
+                    .find(|system| system.get_system_id() == system_id)
+            })
+            .unwrap_or_else(|| panic!("No System registered to add the run condition to!"));
+        system.access.extend(condition.get_access());
+        system.run_conditions.push(condition);
+        self.rebuild_schedule();
+        self
+    }
+
+    /// Adds a run condition to the whole group, which is then only executed if `condition` (and
+    /// any other run condition of the group) returns `true`. `condition` is evaluated once per
+    /// execution, before any `System` (see `run_if`).
+    pub fn run_group_if<M, C: SystemFunction<M, Output = bool>>(
+        mut self,
+        condition: C,
+    ) -> SystemGroup {
+        self.run_conditions.push(RunCondition::new(condition));
         self
     }
 
-    pub(crate) fn get_registered_systems(&self) -> &HashMap<u64, SystemExecuteFn> {
-        &self.system_id_to_system
+    /// Enables or disables the whole group. A disabled group is not executed at all, until it is
+    /// enabled again.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enables or disables every registered `System` of type `T`, including all registered
+    /// instances of a `StatefulSystem`. Disabled `System`s are skipped until enabled again.
+    /// Returns `true` if any `System` of type `T` is registered.
+    pub fn set_system_enabled<T: 'static + PropertyId>(&mut self, enabled: bool) -> bool {
+        self.set_enabled_by_property_id(T::property_id(), enabled)
+    }
+
+    /// Enables or disables the function `System` `system`, like `set_system_enabled`. Returns
+    /// `true` if `system` is registered.
+    pub fn set_function_enabled<M, F: SystemFunction<M>>(
+        &mut self,
+        _system: F,
+        enabled: bool,
+    ) -> bool {
+        self.set_enabled_by_property_id(F::system_id(), enabled)
+    }
+
+    /// Registers `T` to execute immediately before the already registered `Other`.
+    ///
+    /// Note: will panic if `T` is already registered, or `Other` is not.
+    pub fn register_before<T: 'static + System, Other: 'static + System>(self) -> SystemGroup {
+        self.try_register_before::<T, Other>()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Registers `T` to execute immediately before the already registered `Other`. Fails with
+    /// `Error::SystemAlreadyRegistered` if `T` is already registered, or with
+    /// `Error::SystemNotRegistered` if `Other` is not.
+    pub fn try_register_before<T: 'static + System, Other: 'static + System>(
+        mut self,
+    ) -> Result<SystemGroup> {
+        self.check_not_registered::<T>()?;
+        let index = self.get_registered_index::<Other>()?;
+        self.insert_system(index, RegisteredSystem::new::<T>());
+        Ok(self)
+    }
+
+    /// Registers `T` to execute immediately after the already registered `Other`.
+    ///
+    /// Note: will panic if `T` is already registered, or `Other` is not.
+    pub fn register_after<T: 'static + System, Other: 'static + System>(self) -> SystemGroup {
+        self.try_register_after::<T, Other>()
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Registers `T` to execute immediately after the already registered `Other`. Fails with
+    /// `Error::SystemAlreadyRegistered` if `T` is already registered, or with
+    /// `Error::SystemNotRegistered` if `Other` is not.
+    pub fn try_register_after<T: 'static + System, Other: 'static + System>(
+        mut self,
+    ) -> Result<SystemGroup> {
+        self.check_not_registered::<T>()?;
+        let index = self.get_registered_index::<Other>()?;
+        self.insert_system(index + 1, RegisteredSystem::new::<T>());
+        Ok(self)
+    }
+
+    /// Removes `T` from the group. Returns `true` if it was registered.
+    pub fn remove<T: 'static + System>(&mut self) -> bool {
+        match self.get_index::<T>() {
+            None => false,
+            Some(index) => {
+                self.systems.remove(index);
+                self.rebuild_schedule();
+                true
+            }
+        }
+    }
+
+    /// Moves the already registered `T` to execute immediately before `Other`.
+    ///
+    /// Note: will panic if either `System` is not registered.
+    pub fn move_before<T: 'static + System, Other: 'static + System>(&mut self) {
+        self.try_move_before::<T, Other>()
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Moves the already registered `T` to execute immediately before `Other`. Fails with
+    /// `Error::SystemNotRegistered` if either `System` is not registered, leaving the group as is.
+    pub fn try_move_before<T: 'static + System, Other: 'static + System>(&mut self) -> Result<()> {
+        let index = self.get_registered_index::<T>()?;
+        let other_index = self.get_registered_index::<Other>()?;
+        self.move_system(index, other_index);
+        Ok(())
+    }
+
+    /// Moves the already registered `T` to execute immediately after `Other`.
+    ///
+    /// Note: will panic if either `System` is not registered.
+    pub fn move_after<T: 'static + System, Other: 'static + System>(&mut self) {
+        self.try_move_after::<T, Other>()
+            .unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    /// Moves the already registered `T` to execute immediately after `Other`. Fails with
+    /// `Error::SystemNotRegistered` if either `System` is not registered, leaving the group as is.
+    pub fn try_move_after<T: 'static + System, Other: 'static + System>(&mut self) -> Result<()> {
+        let index = self.get_registered_index::<T>()?;
+        let other_index = self.get_registered_index::<Other>()?;
+        self.move_system(index, other_index + 1);
+        Ok(())
+    }
+
+    pub fn contains<T: 'static + System>(&self) -> bool {
+        self.get_index::<T>().is_some()
+    }
+
+    /// Returns the names of all registered `System`s, in execution order.
+    pub fn get_system_names(&self) -> Vec<&'static str> {
+        self.systems
+            .iter()
+            .map(|system| system.get_system_name())
+            .collect()
+    }
+
+    /// Describes which `System`s will be executed at the same time, and which were forced to run
+    /// after others due to conflicting access.
+    pub fn get_schedule_report(&self) -> ScheduleReport {
+        self.schedule.get_report(&self.systems)
+    }
+
+    /// Returns `true` if the group is enabled, and all of its run conditions are met.
+    pub(crate) fn should_run(
+        &self,
+        entity_manager: &EntityManager,
+        params: &SystemParamAccessor,
+    ) -> bool {
+        self.enabled
+            && self
+                .run_conditions
+                .iter()
+                .all(|condition| condition.evaluate(entity_manager, params))
+    }
+
+    pub(crate) fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub(crate) fn get_registered_systems(&self) -> &[RegisteredSystem] {
+        &self.systems
     }
 
     pub(crate) fn get_execution_mode(&self) -> &ExecutionMode {
         &self.execution_mode
     }
+
+    fn insert_system(&mut self, index: usize, system: RegisteredSystem) {
+        self.last_registered = Some(system.get_system_id());
+        self.systems.insert(index, system);
+        self.rebuild_schedule();
+    }
+
+    fn set_enabled_by_property_id(&mut self, property_id: u64, enabled: bool) -> bool {
+        let mut found = false;
+        self.systems
+            .iter_mut()
+            .filter(|system| system.property_id == property_id)
+            .for_each(|system| {
+                system.enabled = enabled;
+                found = true;
+            });
+        found
+    }
+
+    fn rebuild_schedule(&mut self) {
+        self.schedule = match self.execution_mode {
+            Sequential => Schedule::new_sequential(&self.systems),
+            Parallel => Schedule::new_parallel(&self.systems),
+        };
+    }
+
+    fn get_index<T: 'static + System>(&self) -> Option<usize> {
+        let system_id = T::property_id();
+        self.systems
+            .iter()
+            .position(|system| system.get_system_id() == system_id)
+    }
+
+    fn get_registered_index<T: 'static + System>(&self) -> Result<usize> {
+        self.get_index::<T>()
+            .ok_or(Error::SystemNotRegistered(T::property_string()))
+    }
+
+    fn check_not_registered<T: 'static + System>(&self) -> Result<()> {
+        match self.contains::<T>() {
+            true => Err(Error::SystemAlreadyRegistered(T::property_string())),
+            false => Ok(()),
+        }
+    }
+
+    /// Moves the system at `index` so that it ends up at `destination`, an index into `systems`
+    /// from before the move.
+    fn move_system(&mut self, index: usize, destination: usize) {
+        let system = self.systems.remove(index);
+        let destination = if destination > index {
+            destination - 1
+        } else {
+            destination
+        };
+        self.systems.insert(destination, system);
+        self.rebuild_schedule();
+    }
 }
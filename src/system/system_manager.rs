@@ -1,40 +1,62 @@
-use rayon::prelude::*;
 use std::sync::{Arc, RwLock};
 
 use crate::entity::EntityManager;
-use crate::system::{SystemGroup, SystemParamAccessor};
+use crate::system::{RegisteredSystem, SystemGroup, SystemParamAccessor};
 use crate::util::ExecutionMode::*;
 
 #[derive(Default)]
 pub struct SystemManager;
 
 /// The `SystemManager` is simply responsible for executing all of the `System`s in the provided
-/// `SystemGroup`.
+/// `SystemGroup`, unless the group is disabled or one of its run conditions is not met.
 impl SystemManager {
     pub fn new() -> Self {
         SystemManager
     }
 
+    /// Returns `false` if the group was skipped.
     pub(crate) fn execute_group(
         &self,
         group: &SystemGroup,
         entity_manager: Arc<RwLock<EntityManager>>,
         params: Arc<SystemParamAccessor>,
-    ) {
+    ) -> bool {
+        if !group.should_run(&entity_manager.read().unwrap(), &params) {
+            return false;
+        }
         match *group.get_execution_mode() {
             Sequential => {
-                group.get_registered_systems().values().for_each(|system| {
-                    system(entity_manager.clone(), params.clone());
+                group.get_registered_systems().iter().for_each(|system| {
+                    system.execute(entity_manager.clone(), params.clone());
                 });
             }
             Parallel => {
-                group
-                    .get_registered_systems()
-                    .par_iter()
-                    .for_each(|(_, system)| {
-                        system(entity_manager.clone(), params.clone());
+                let systems = group.get_registered_systems();
+                group.get_schedule().get_stages().iter().for_each(|stage| {
+                    if let [index] = stage.as_slice() {
+                        systems[*index].execute(entity_manager.clone(), params.clone());
+                        return;
+                    }
+                    // Pinned systems run on the current thread, while the rest of the stage is
+                    // spread across the thread pool.
+                    rayon::in_place_scope(|scope| {
+                        let (pinned, unpinned): (Vec<&RegisteredSystem>, Vec<&RegisteredSystem>) =
+                            stage
+                                .iter()
+                                .map(|index| &systems[*index])
+                                .partition(|system| system.get_access().is_pinned());
+                        unpinned.into_iter().for_each(|system| {
+                            let entity_manager = entity_manager.clone();
+                            let params = params.clone();
+                            scope.spawn(move |_| system.execute(entity_manager, params));
+                        });
+                        pinned.into_iter().for_each(|system| {
+                            system.execute(entity_manager.clone(), params.clone());
+                        });
                     });
+                });
             }
         }
+        true
     }
 }
@@ -1,7 +1,9 @@
 pub use moecs_macros::SystemParam;
 
+use std::any::TypeId;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::thread::{self, ThreadId};
 
 use crate::util::PropertyId;
 
@@ -9,37 +11,125 @@ use crate::util::PropertyId;
 /// canvas, delta time, input processing information, etc.
 ///
 /// Note: All user-defined `SystemParam`s must derive this trait via `#[derive(SystemParam)]`.
-pub trait SystemParam: PropertyId {}
+///
+/// # Safety
+///
+/// `SystemParamAccessor` relies on `static_type_id` to uniquely identify the type family
+/// `Rebind`, so implementing this trait by hand is unsafe. The derive guarantees that `Rebind<'p>`
+/// is `Self` with every lifetime replaced by `'p`, and fails to compile if `Self` is not covariant
+/// in its lifetimes.
+pub unsafe trait SystemParam: PropertyId {
+    /// `Self`, with every lifetime replaced by `'p`.
+    #[doc(hidden)]
+    type Rebind<'p>: 'p
+    where
+        Self: 'p;
+
+    /// Shortens the lifetimes of `self` to `'p`.
+    #[doc(hidden)]
+    fn rebind<'p>(self) -> Self::Rebind<'p>
+    where
+        Self: 'p + Sized;
+
+    /// The `TypeId` of `Rebind<'static>`, shared by every lifetime instantiation of `Self`.
+    #[doc(hidden)]
+    fn static_type_id() -> TypeId
+    where
+        Self: Sized;
+}
+
+/// Used to type-erase stored `SystemParam`s.
+trait Erased {}
+
+impl<T: ?Sized> Erased for T {}
 
 /// Stores and provides access to a collection of `SystemParam`s.
-#[derive(Default)]
+///
+/// `SystemParam`s added via `add_param` must be thread-safe, and are accessible from any `System`.
+/// Those that aren't (e.g. a handle to a renderer) must be added via `add_local_param` instead, and
+/// are only accessible from the thread that created the `SystemParamAccessor`, i.e. from `System`s
+/// in sequential `SystemGroup`s, or with exclusive or pinned `SystemAccess`.
 pub struct SystemParamAccessor<'a> {
-    param_id_to_param: HashMap<u64, Arc<RwLock<dyn 'a + SystemParam>>>,
+    owner_thread_id: ThreadId,
+    params: HashMap<TypeId, Arc<RwLock<dyn 'a + Erased + Send + Sync>>>,
+    local_params: HashMap<TypeId, Arc<RwLock<dyn 'a + Erased>>>,
 }
 
+// SAFETY: `local_params` are the only members that aren't thread-safe. They are only handed out
+// on `owner_thread_id` (see `get_param`), the handed out `Arc`s are neither `Send` nor `Sync`
+// unless the `SystemParam` itself is, and they are leaked rather than dropped on any other thread.
 unsafe impl Send for SystemParamAccessor<'_> {}
 
 unsafe impl Sync for SystemParamAccessor<'_> {}
 
+impl Default for SystemParamAccessor<'_> {
+    fn default() -> Self {
+        SystemParamAccessor::new()
+    }
+}
+
 impl<'a> SystemParamAccessor<'a> {
     pub fn new() -> Self {
         SystemParamAccessor {
-            param_id_to_param: HashMap::new(),
+            owner_thread_id: thread::current().id(),
+            params: HashMap::new(),
+            local_params: HashMap::new(),
         }
     }
 
-    pub fn add_param<T: 'a + SystemParam>(
-        mut self,
-        param: T,
-    ) -> SystemParamAccessor<'a> {
-        self.param_id_to_param
-            .insert(param.self_property_id(), Arc::new(RwLock::new(param)));
+    /// Adds a thread-safe `SystemParam`, accessible from any `System`.
+    pub fn add_param<T: 'a + SystemParam>(mut self, param: T) -> Self
+    where
+        T::Rebind<'a>: Send + Sync,
+    {
+        self.local_params.remove(&T::static_type_id());
+        self.params.insert(
+            T::static_type_id(),
+            Arc::new(RwLock::new(param.rebind::<'a>())),
+        );
         self
     }
 
-    pub fn get_param<T: 'a + SystemParam>(&self) -> Option<Arc<RwLock<T>>> {
-        self.param_id_to_param
-            .get(&T::property_id())
-            .map(|param| unsafe { Arc::from_raw(Arc::into_raw(param.clone()) as *const RwLock<T>) })
+    /// Adds a `SystemParam` that may only be accessed from the thread that created this
+    /// `SystemParamAccessor`.
+    pub fn add_local_param<T: 'a + SystemParam>(mut self, param: T) -> Self {
+        self.params.remove(&T::static_type_id());
+        self.local_params.insert(
+            T::static_type_id(),
+            Arc::new(RwLock::new(param.rebind::<'a>())),
+        );
+        self
+    }
+
+    /// Retrieves access to the `SystemParam` of type `T`. Returns `None` if it was not added.
+    ///
+    /// Will panic if `T` was added via `add_local_param`, and this is called from a thread other
+    /// than the one that created this `SystemParamAccessor`.
+    pub fn get_param<T: SystemParam>(&self) -> Option<Arc<RwLock<T::Rebind<'a>>>> {
+        let type_id = T::static_type_id();
+        if let Some(param) = self.params.get(&type_id) {
+            // SAFETY: params are keyed by `static_type_id`, so the erased type is `T::Rebind<'a>`.
+            return Some(unsafe {
+                Arc::from_raw(Arc::into_raw(param.clone()) as *const RwLock<T::Rebind<'a>>)
+            });
+        }
+        let param = self.local_params.get(&type_id)?;
+        if thread::current().id() != self.owner_thread_id {
+            panic!(
+                "SystemParam: {} may only be accessed from the thread that created the \
+                 SystemParamAccessor!",
+                T::property_string()
+            );
+        }
+        // SAFETY: as above. The returned `Arc` is only `Send` if `T` is thread-safe.
+        Some(unsafe { Arc::from_raw(Arc::into_raw(param.clone()) as *const RwLock<T::Rebind<'a>>) })
+    }
+}
+
+impl Drop for SystemParamAccessor<'_> {
+    fn drop(&mut self) {
+        if thread::current().id() != self.owner_thread_id {
+            std::mem::forget(std::mem::take(&mut self.local_params));
+        }
     }
 }
@@ -1,11 +1,18 @@
 #![cfg(test)]
 
+use std::cell::Cell;
+use std::rc::Rc;
 use std::sync::{Arc, RwLock};
+use std::thread;
 
-use crate::entity::EntityManager;
+use crate::component::{Component, ComponentBundle};
+use crate::entity::{Changed, Commands, EntityManager, Query, QueryBorrow, Without};
+use crate::event::{Event, EventReader, EventWriter, Events};
+use crate::resource::{Res, ResMut, Resource};
 use crate::system::*;
 use crate::util::ExecutionMode::*;
 use crate::util::PropertyId;
+use crate::Error;
 
 #[derive(System)]
 struct TestSystem;
@@ -13,25 +20,165 @@ impl System for TestSystem {
     fn execute(_entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {}
 }
 
+macro_rules! define_noop_system {
+    ($name:ident, $access:expr) => {
+        #[derive(System)]
+        struct $name;
+        impl System for $name {
+            fn execute(
+                _entity_manager: Arc<RwLock<EntityManager>>,
+                _params: Arc<SystemParamAccessor>,
+            ) {
+            }
+
+            fn access() -> SystemAccess {
+                $access
+            }
+        }
+    };
+}
+
+define_noop_system!(NoAccessSystem, SystemAccess::new());
+define_noop_system!(
+    ReadVelocitySystem,
+    SystemAccess::new().add_read::<VelocityComponent>()
+);
+define_noop_system!(
+    OtherReadVelocitySystem,
+    SystemAccess::new().add_read::<VelocityComponent>()
+);
+define_noop_system!(
+    WriteVelocitySystem,
+    SystemAccess::new().add_write::<VelocityComponent>()
+);
+
 #[derive(SystemParam)]
 struct TestParam;
 
+#[derive(SystemParam)]
+struct LocalParam {
+    count: Rc<Cell<u32>>,
+}
+
+#[derive(SystemParam)]
+struct BorrowedParam<'a> {
+    value: &'a mut u32,
+}
+
+#[derive(SystemParam)]
+struct GenericParam<T> {
+    value: T,
+}
+
+#[derive(Resource)]
+struct TestResource;
+
+#[derive(Component, Default)]
+struct ExecutionOrderComponent {
+    system_names: Vec<&'static str>,
+}
+
+fn record_execution(entity_manager: Arc<RwLock<EntityManager>>, system_name: &'static str) {
+    entity_manager
+        .read()
+        .unwrap()
+        .query(&Query::<&mut ExecutionOrderComponent>::default())
+        .for_each(|order| order.system_names.push(system_name));
+}
+
+fn get_execution_order(entity_manager: &Arc<RwLock<EntityManager>>) -> Vec<&'static str> {
+    let mut order = Vec::new();
+    entity_manager
+        .read()
+        .unwrap()
+        .query(&Query::<&ExecutionOrderComponent>::default())
+        .for_each(|component| order.extend(component.system_names.iter()));
+    order
+}
+
+macro_rules! define_recording_system {
+    ($name:ident) => {
+        #[derive(System)]
+        struct $name;
+        impl System for $name {
+            fn execute(
+                entity_manager: Arc<RwLock<EntityManager>>,
+                _params: Arc<SystemParamAccessor>,
+            ) {
+                record_execution(entity_manager, stringify!($name));
+            }
+        }
+    };
+}
+
+#[derive(Component)]
+struct PositionComponent;
+
+#[derive(Component)]
+struct VelocityComponent;
+
+macro_rules! define_access_system {
+    ($name:ident, $access:expr) => {
+        #[derive(System)]
+        struct $name;
+        impl System for $name {
+            fn execute(
+                entity_manager: Arc<RwLock<EntityManager>>,
+                _params: Arc<SystemParamAccessor>,
+            ) {
+                record_execution(entity_manager, stringify!($name));
+            }
+
+            fn access() -> SystemAccess {
+                $access.add_write::<ExecutionOrderComponent>()
+            }
+        }
+    };
+}
+
+define_access_system!(
+    WritePositionSystem,
+    SystemAccess::new().add_write::<PositionComponent>()
+);
+define_access_system!(
+    ReadPositionSystem,
+    SystemAccess::new().add_read::<PositionComponent>()
+);
+define_access_system!(
+    MoveSystem,
+    SystemAccess::new().add_query::<(&mut PositionComponent, &VelocityComponent)>()
+);
+
+define_recording_system!(InputSystem);
+define_recording_system!(MovementSystem);
+define_recording_system!(CollisionSystem);
+define_recording_system!(RenderSystem);
+
+fn execute_and_get_order(group: &SystemGroup) -> Vec<&'static str> {
+    let entity_manager = Arc::new(RwLock::new(EntityManager::new()));
+    entity_manager
+        .write()
+        .unwrap()
+        .create_entity(ComponentBundle::new().add_component(ExecutionOrderComponent::default()));
+    SystemManager.execute_group(
+        group,
+        entity_manager.clone(),
+        Arc::new(SystemParamAccessor::new()),
+    );
+    get_execution_order(&entity_manager)
+}
+
 #[test]
 fn system_group_sequential_success() {
     let group = SystemGroup::new_sequential_group().register::<TestSystem>();
-    assert!(group
-        .get_registered_systems()
-        .contains_key(&TestSystem::property_id()));
+    assert!(group.contains::<TestSystem>());
     assert_eq!(group.get_execution_mode(), &Sequential);
 }
 
 #[test]
 fn system_group_parallel_success() {
     let group = SystemGroup::new_parallel_group().register::<TestSystem>();
-    assert!(group
-        .get_registered_systems()
-        .get(&TestSystem::property_id())
-        .is_some());
+    assert!(group.contains::<TestSystem>());
     assert_eq!(group.get_execution_mode(), &Parallel);
 }
 
@@ -58,3 +205,912 @@ fn system_manager_parallel_success() {
         Arc::new(SystemParamAccessor::new()),
     );
 }
+
+#[test]
+fn system_group_sequential_registration_order_success() {
+    let group = SystemGroup::new_sequential_group()
+        .register::<InputSystem>()
+        .register::<MovementSystem>()
+        .register::<CollisionSystem>()
+        .register::<RenderSystem>();
+    assert_eq!(
+        execute_and_get_order(&group),
+        vec![
+            "InputSystem",
+            "MovementSystem",
+            "CollisionSystem",
+            "RenderSystem"
+        ]
+    );
+}
+
+#[test]
+fn system_group_register_before_after_success() {
+    let group = SystemGroup::new_sequential_group()
+        .register::<CollisionSystem>()
+        .register_before::<InputSystem, CollisionSystem>()
+        .register_after::<MovementSystem, InputSystem>()
+        .register_after::<RenderSystem, CollisionSystem>();
+    assert_eq!(
+        execute_and_get_order(&group),
+        vec![
+            "InputSystem",
+            "MovementSystem",
+            "CollisionSystem",
+            "RenderSystem"
+        ]
+    );
+}
+
+#[test]
+#[should_panic]
+fn system_group_register_before_unregistered_panics() {
+    SystemGroup::new_sequential_group().register_before::<InputSystem, MovementSystem>();
+}
+
+#[test]
+#[should_panic]
+fn system_group_register_duplicate_panics() {
+    SystemGroup::new_sequential_group()
+        .register::<InputSystem>()
+        .register::<MovementSystem>()
+        .register_after::<InputSystem, MovementSystem>();
+}
+
+#[test]
+fn system_group_move_and_remove_success() {
+    let mut group = SystemGroup::new_sequential_group()
+        .register::<RenderSystem>()
+        .register::<CollisionSystem>()
+        .register::<MovementSystem>()
+        .register::<InputSystem>();
+
+    group.move_after::<RenderSystem, InputSystem>();
+    group.move_before::<InputSystem, CollisionSystem>();
+    group.move_before::<MovementSystem, CollisionSystem>();
+    assert_eq!(
+        execute_and_get_order(&group),
+        vec![
+            "InputSystem",
+            "MovementSystem",
+            "CollisionSystem",
+            "RenderSystem"
+        ]
+    );
+
+    assert!(group.remove::<MovementSystem>());
+    assert!(!group.remove::<MovementSystem>());
+    assert!(!group.contains::<MovementSystem>());
+    assert_eq!(
+        execute_and_get_order(&group),
+        vec!["InputSystem", "CollisionSystem", "RenderSystem"]
+    );
+}
+
+#[test]
+fn system_group_try_operations_fail_success() {
+    let group = SystemGroup::new_sequential_group()
+        .try_register::<InputSystem>()
+        .unwrap();
+    assert!(matches!(
+        group.clone().try_register::<InputSystem>(),
+        Err(Error::SystemAlreadyRegistered(_))
+    ));
+    assert!(matches!(
+        group
+            .clone()
+            .try_register_before::<MovementSystem, RenderSystem>(),
+        Err(Error::SystemNotRegistered(name)) if name == RenderSystem::property_string()
+    ));
+
+    let mut group = group
+        .try_register_after::<MovementSystem, InputSystem>()
+        .unwrap();
+    assert_eq!(
+        group.try_move_after::<InputSystem, RenderSystem>(),
+        Err(Error::SystemNotRegistered(RenderSystem::property_string()))
+    );
+    assert_eq!(
+        group.get_system_names(),
+        vec![
+            InputSystem::property_string(),
+            MovementSystem::property_string()
+        ]
+    );
+    group
+        .try_move_after::<InputSystem, MovementSystem>()
+        .unwrap();
+    group.try_move_before::<InputSystem, InputSystem>().unwrap();
+    assert_eq!(
+        execute_and_get_order(&group),
+        vec!["MovementSystem", "InputSystem"]
+    );
+}
+
+#[test]
+fn system_access_conflict_success() {
+    let read_position = SystemAccess::new().add_read::<PositionComponent>();
+    let write_position = SystemAccess::new().add_write::<PositionComponent>();
+    let read_velocity = SystemAccess::new().add_read::<VelocityComponent>();
+    let move_access =
+        SystemAccess::new().add_query::<(&mut PositionComponent, &VelocityComponent)>();
+
+    assert_eq!(read_position.get_conflict(&read_position), None);
+    assert_eq!(read_position.get_conflict(&read_velocity), None);
+    assert_eq!(move_access.get_conflict(&read_velocity), None);
+    assert_eq!(
+        read_position.get_conflict(&write_position),
+        Some(AccessConflict::Components(vec![
+            PositionComponent::property_string()
+        ]))
+    );
+    assert_eq!(
+        move_access.get_conflict(&read_position),
+        Some(AccessConflict::Components(vec![
+            PositionComponent::property_string()
+        ]))
+    );
+    assert_eq!(
+        SystemAccess::new()
+            .add_resource_read::<TestResource>()
+            .get_conflict(&SystemAccess::new().add_resource_read::<TestResource>()),
+        None
+    );
+    assert_eq!(
+        SystemAccess::new()
+            .add_resource_write::<TestResource>()
+            .get_conflict(&SystemAccess::new().add_resource_read::<TestResource>()),
+        Some(AccessConflict::Resources(vec![
+            TestResource::property_string()
+        ]))
+    );
+    assert_eq!(
+        SystemAccess::exclusive().get_conflict(&SystemAccess::new()),
+        Some(AccessConflict::Exclusive)
+    );
+}
+
+#[test]
+fn system_group_parallel_schedule_success() {
+    let group = SystemGroup::new_parallel_group()
+        .register::<TestSystem>()
+        .register::<NoAccessSystem>()
+        .register::<ReadVelocitySystem>()
+        .register::<WriteVelocitySystem>()
+        .register::<OtherReadVelocitySystem>();
+    let report = group.get_schedule_report();
+    assert_eq!(
+        report.get_stages(),
+        &[
+            vec![TestSystem::property_string()],
+            vec![
+                NoAccessSystem::property_string(),
+                ReadVelocitySystem::property_string()
+            ],
+            vec![WriteVelocitySystem::property_string()],
+            vec![OtherReadVelocitySystem::property_string()],
+        ]
+    );
+    assert_eq!(
+        report.get_conflicts(),
+        &[
+            SystemConflict {
+                system: NoAccessSystem::property_string(),
+                runs_after: TestSystem::property_string(),
+                conflict: AccessConflict::Exclusive,
+            },
+            SystemConflict {
+                system: ReadVelocitySystem::property_string(),
+                runs_after: TestSystem::property_string(),
+                conflict: AccessConflict::Exclusive,
+            },
+            SystemConflict {
+                system: WriteVelocitySystem::property_string(),
+                runs_after: TestSystem::property_string(),
+                conflict: AccessConflict::Exclusive,
+            },
+            SystemConflict {
+                system: WriteVelocitySystem::property_string(),
+                runs_after: ReadVelocitySystem::property_string(),
+                conflict: AccessConflict::Components(vec![VelocityComponent::property_string()]),
+            },
+            SystemConflict {
+                system: OtherReadVelocitySystem::property_string(),
+                runs_after: TestSystem::property_string(),
+                conflict: AccessConflict::Exclusive,
+            },
+            SystemConflict {
+                system: OtherReadVelocitySystem::property_string(),
+                runs_after: WriteVelocitySystem::property_string(),
+                conflict: AccessConflict::Components(vec![VelocityComponent::property_string()]),
+            },
+        ]
+    );
+}
+
+#[test]
+fn system_group_sequential_schedule_success() {
+    let group = SystemGroup::new_sequential_group()
+        .register::<NoAccessSystem>()
+        .register::<ReadVelocitySystem>();
+    let report = group.get_schedule_report();
+    assert_eq!(
+        report.get_stages(),
+        &[
+            vec![NoAccessSystem::property_string()],
+            vec![ReadVelocitySystem::property_string()],
+        ]
+    );
+    assert!(report.get_conflicts().is_empty());
+}
+
+#[test]
+fn system_group_parallel_conflicting_registration_order_success() {
+    let group = SystemGroup::new_parallel_group()
+        .register::<ReadPositionSystem>()
+        .register::<MoveSystem>()
+        .register::<WritePositionSystem>()
+        .register::<InputSystem>();
+    assert_eq!(
+        execute_and_get_order(&group),
+        vec![
+            "ReadPositionSystem",
+            "MoveSystem",
+            "WritePositionSystem",
+            "InputSystem"
+        ]
+    );
+}
+
+#[derive(System)]
+struct SpawnSystem;
+impl System for SpawnSystem {
+    fn execute(entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {
+        entity_manager
+            .read()
+            .unwrap()
+            .commands()
+            .create_entity(ComponentBundle::new().add_component(PositionComponent));
+    }
+
+    fn access() -> SystemAccess {
+        SystemAccess::new()
+    }
+}
+
+#[derive(System)]
+struct CountPositionsSystem;
+impl System for CountPositionsSystem {
+    fn execute(entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {
+        let entity_manager = entity_manager.read().unwrap();
+        let count = entity_manager
+            .query(&Query::<&PositionComponent>::default())
+            .len();
+        entity_manager
+            .query(&Query::<&mut ExecutionOrderComponent>::default())
+            .for_each(|order| {
+                order
+                    .system_names
+                    .push(if count == 0 { "None" } else { "Some" })
+            });
+    }
+}
+
+#[test]
+fn system_group_flush_commands_success() {
+    let group = SystemGroup::new_sequential_group()
+        .register::<SpawnSystem>()
+        .register::<FlushCommandsSystem>()
+        .register::<CountPositionsSystem>();
+    assert_eq!(execute_and_get_order(&group), vec!["Some"]);
+
+    let mut group = group;
+    group.move_before::<CountPositionsSystem, FlushCommandsSystem>();
+    assert_eq!(execute_and_get_order(&group), vec!["None"]);
+}
+
+#[test]
+fn system_param_accessor_type_identity_success() {
+    let accessor = SystemParamAccessor::new()
+        .add_param(GenericParam::<u32> { value: 1 })
+        .add_param(GenericParam::<u64> { value: 2 });
+    assert_eq!(
+        accessor
+            .get_param::<GenericParam<u32>>()
+            .unwrap()
+            .read()
+            .unwrap()
+            .value,
+        1
+    );
+    assert_eq!(
+        accessor
+            .get_param::<GenericParam<u64>>()
+            .unwrap()
+            .read()
+            .unwrap()
+            .value,
+        2
+    );
+    assert!(accessor.get_param::<GenericParam<i32>>().is_none());
+    assert!(accessor.get_param::<TestParam>().is_none());
+}
+
+#[test]
+fn system_param_accessor_borrowed_param_success() {
+    let mut value = 1;
+    {
+        let accessor = SystemParamAccessor::new().add_param(BorrowedParam { value: &mut value });
+        let param = accessor.get_param::<BorrowedParam>().unwrap();
+        *param.write().unwrap().value += 1;
+    }
+    assert_eq!(value, 2);
+}
+
+#[test]
+fn system_param_accessor_local_param_success() {
+    let count = Rc::new(Cell::new(0));
+    let accessor = SystemParamAccessor::new().add_local_param(LocalParam {
+        count: count.clone(),
+    });
+    let param = accessor.get_param::<LocalParam>().unwrap();
+    param.read().unwrap().count.set(1);
+    assert_eq!(count.get(), 1);
+}
+
+#[test]
+fn system_param_accessor_local_param_other_thread_panics() {
+    let accessor = SystemParamAccessor::new()
+        .add_param(TestParam)
+        .add_local_param(LocalParam {
+            count: Rc::new(Cell::new(0)),
+        });
+    thread::scope(|scope| {
+        assert!(scope
+            .spawn(|| accessor.get_param::<TestParam>().is_some())
+            .join()
+            .unwrap());
+        assert!(scope
+            .spawn(|| accessor.get_param::<LocalParam>().is_some())
+            .join()
+            .is_err());
+    });
+}
+
+#[derive(System)]
+struct PinnedLocalParamSystem;
+impl System for PinnedLocalParamSystem {
+    fn execute(_entity_manager: Arc<RwLock<EntityManager>>, params: Arc<SystemParamAccessor>) {
+        let param = params.get_param::<LocalParam>().unwrap();
+        let count = &param.read().unwrap().count;
+        count.set(count.get() + 1);
+    }
+
+    fn access() -> SystemAccess {
+        SystemAccess::new().pinned()
+    }
+}
+
+#[derive(System)]
+struct UnpinnedLocalParamSystem;
+impl System for UnpinnedLocalParamSystem {
+    fn execute(_entity_manager: Arc<RwLock<EntityManager>>, params: Arc<SystemParamAccessor>) {
+        params.get_param::<LocalParam>();
+    }
+
+    fn access() -> SystemAccess {
+        SystemAccess::new()
+    }
+}
+
+#[test]
+fn system_manager_parallel_pinned_local_param_success() {
+    let count = Rc::new(Cell::new(0));
+    SystemManager.execute_group(
+        &SystemGroup::new_parallel_group()
+            .register::<NoAccessSystem>()
+            .register::<PinnedLocalParamSystem>()
+            .register::<ReadVelocitySystem>(),
+        Arc::new(RwLock::new(EntityManager::new())),
+        Arc::new(SystemParamAccessor::new().add_local_param(LocalParam {
+            count: count.clone(),
+        })),
+    );
+    assert_eq!(count.get(), 1);
+}
+
+#[test]
+#[should_panic]
+fn system_manager_parallel_unpinned_local_param_panics() {
+    SystemManager.execute_group(
+        &SystemGroup::new_parallel_group()
+            .register::<NoAccessSystem>()
+            .register::<UnpinnedLocalParamSystem>(),
+        Arc::new(RwLock::new(EntityManager::new())),
+        Arc::new(SystemParamAccessor::new().add_local_param(LocalParam {
+            count: Rc::new(Cell::new(0)),
+        })),
+    );
+}
+
+#[derive(Resource, Default)]
+struct ChangeCountResource {
+    changed: Vec<usize>,
+    removed: Vec<usize>,
+}
+
+#[derive(System)]
+struct CountChangesSystem;
+impl System for CountChangesSystem {
+    fn execute(entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {
+        let entity_manager = entity_manager.read().unwrap();
+        let changed = entity_manager
+            .query(&Query::<&VelocityComponent>::default().changed::<VelocityComponent>())
+            .len();
+        let removed = entity_manager
+            .get_removed_components::<VelocityComponent>()
+            .len();
+        let resource = entity_manager
+            .get_resource::<ChangeCountResource>()
+            .unwrap();
+        let mut resource = resource.write().unwrap();
+        resource.changed.push(changed);
+        resource.removed.push(removed);
+    }
+}
+
+#[test]
+fn system_change_detection_since_last_run_success() {
+    let mut entity_manager = EntityManager::new();
+    entity_manager.insert_resource(ChangeCountResource::default());
+    let entity_id =
+        entity_manager.create_entity(ComponentBundle::new().add_component(VelocityComponent));
+    entity_manager.create_entity(ComponentBundle::new().add_component(VelocityComponent));
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group().register::<CountChangesSystem>();
+    let execute = || {
+        SystemManager.execute_group(
+            &group,
+            entity_manager.clone(),
+            Arc::new(SystemParamAccessor::new()),
+        )
+    };
+
+    execute();
+    execute();
+    entity_manager
+        .read()
+        .unwrap()
+        .query(&Query::<&mut VelocityComponent>::default())
+        .iter()
+        .take(1)
+        .for_each(|_| {});
+    execute();
+    entity_manager
+        .write()
+        .unwrap()
+        .remove_component_from_entity::<VelocityComponent>(&entity_id);
+    execute();
+    execute();
+
+    let resource = entity_manager
+        .read()
+        .unwrap()
+        .get_resource::<ChangeCountResource>()
+        .unwrap();
+    let resource = resource.read().unwrap();
+    assert_eq!(resource.changed, vec![2, 0, 1, 0, 0]);
+    assert_eq!(resource.removed, vec![0, 0, 0, 1, 0]);
+}
+
+#[derive(Component)]
+struct CountComponent {
+    count: u32,
+}
+
+#[derive(SystemParam)]
+struct StepParam {
+    step: u32,
+}
+
+#[derive(Resource, Default)]
+struct TotalResource {
+    total: u32,
+    events: Vec<u32>,
+}
+
+#[derive(Event)]
+struct CountEvent(u32);
+
+fn step_counts(
+    mut counts: QueryBorrow<&mut CountComponent>,
+    step: Param<StepParam>,
+    mut events: EventWriter<CountEvent>,
+) {
+    counts.iter().for_each(|count| {
+        count.count += step.step;
+        events.send(CountEvent(count.count));
+    });
+}
+
+fn sum_counts(
+    mut counts: QueryBorrow<&CountComponent>,
+    events: EventReader<CountEvent>,
+    mut total: ResMut<TotalResource>,
+    mut commands: Commands,
+) {
+    total.total = counts.iter().map(|count| count.count).sum();
+    total.events = events.read().map(|event| event.0).collect();
+    commands.create_entity(ComponentBundle::new().add_component(PositionComponent));
+}
+
+fn read_total(_total: Res<TotalResource>) {}
+
+fn write_total(_total: ResMut<TotalResource>) {}
+
+#[test]
+fn function_system_success() {
+    let mut entity_manager = EntityManager::new();
+    entity_manager.insert_resource(TotalResource::default());
+    entity_manager.insert_resource(Events::<CountEvent>::new());
+    (0..3).for_each(|count| {
+        entity_manager
+            .create_entity(ComponentBundle::new().add_component(CountComponent { count }));
+    });
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group()
+        .add_system(step_counts)
+        .add_system(sum_counts)
+        .add_system(
+            |total: Option<Res<TotalResource>>, missing: Option<Res<TestResource>>| {
+                assert!(total.is_some() && missing.is_none());
+            },
+        );
+    assert_eq!(
+        group.get_system_names()[..2],
+        [
+            "moecs::system::test::step_counts",
+            "moecs::system::test::sum_counts"
+        ]
+    );
+
+    SystemManager.execute_group(
+        &group,
+        entity_manager.clone(),
+        Arc::new(SystemParamAccessor::new().add_param(StepParam { step: 2 })),
+    );
+    entity_manager.write().unwrap().flush_commands();
+
+    let entity_manager = entity_manager.read().unwrap();
+    let total = entity_manager.get_resource::<TotalResource>().unwrap();
+    let total = total.read().unwrap();
+    assert_eq!(total.total, 9);
+    assert_eq!(total.events.len(), 3);
+    assert_eq!(
+        entity_manager
+            .query(&Query::<&PositionComponent>::default())
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn function_system_access_success() {
+    let group = SystemGroup::new_parallel_group()
+        .add_system(read_total)
+        .add_system(|_total: Res<TotalResource>| {})
+        .add_system(write_total)
+        .add_system(step_counts);
+    let report = group.get_schedule_report();
+    assert_eq!(report.get_stages().len(), 2);
+    assert_eq!(report.get_stages()[0].len(), 3);
+    assert_eq!(
+        report.get_stages()[1],
+        vec!["moecs::system::test::write_total"]
+    );
+    assert!(SystemAccess::new()
+        .add_query::<&mut CountComponent>()
+        .add_resource_write::<Events<CountEvent>>()
+        .pinned()
+        .eq(group.get_registered_systems()[3].get_access()));
+
+    assert!(matches!(
+        group.try_add_system(read_total),
+        Err(Error::SystemAlreadyRegistered(
+            "moecs::system::test::read_total"
+        ))
+    ));
+}
+
+fn record_changed_counts(
+    mut changed: Filtered<&CountComponent, (Changed<CountComponent>, Without<PositionComponent>)>,
+    mut total: ResMut<TotalResource>,
+) {
+    let counts: Vec<u32> = changed.iter().map(|count| count.count).collect();
+    total.events.push(counts.len() as u32);
+    total.total += counts.iter().sum::<u32>();
+}
+
+#[test]
+fn function_system_filtered_query_success() {
+    let mut entity_manager = EntityManager::new();
+    entity_manager.insert_resource(TotalResource::default());
+    let entity_ids: Vec<_> = (1..=2)
+        .map(|count| {
+            entity_manager
+                .create_entity(ComponentBundle::new().add_component(CountComponent { count }))
+        })
+        .collect();
+    entity_manager.create_entity(
+        ComponentBundle::new()
+            .add_component(CountComponent { count: 3 })
+            .add_component(PositionComponent),
+    );
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group().add_system(record_changed_counts);
+    assert!(SystemAccess::new()
+        .add_query::<&CountComponent>()
+        .add_resource_write::<TotalResource>()
+        .eq(group.get_registered_systems()[0].get_access()));
+    // Change detection filters read the filtered `Component`.
+    assert_eq!(
+        SystemAccess::new().add_filtered_query::<&CountComponent, Changed<PositionComponent>>(),
+        SystemAccess::new()
+            .add_query::<&CountComponent>()
+            .add_read::<PositionComponent>()
+    );
+    let execute = || {
+        SystemManager.execute_group(
+            &group,
+            entity_manager.clone(),
+            Arc::new(SystemParamAccessor::new()),
+        );
+    };
+
+    execute();
+    entity_manager
+        .read()
+        .unwrap()
+        .get_mut::<CountComponent>(&entity_ids[1])
+        .unwrap()
+        .count = 10;
+    execute();
+    execute();
+
+    let entity_manager = entity_manager.read().unwrap();
+    let total = entity_manager.get_resource::<TotalResource>().unwrap();
+    let total = total.read().unwrap();
+    // Only Entities without a position, and changed since the previous execution, are seen.
+    assert_eq!(total.events, vec![2, 1, 0]);
+    assert_eq!(total.total, 13);
+}
+
+#[test]
+#[should_panic]
+fn function_system_conflicting_arguments_panics() {
+    SystemGroup::new_sequential_group()
+        .add_system(|_read: Res<TotalResource>, _write: ResMut<TotalResource>| {});
+}
+
+#[derive(System)]
+struct CooldownSystem {
+    interval: u32,
+    cooldown: u32,
+}
+impl StatefulSystem for CooldownSystem {
+    fn execute(
+        &mut self,
+        entity_manager: Arc<RwLock<EntityManager>>,
+        _params: Arc<SystemParamAccessor>,
+    ) {
+        let entity_manager = entity_manager.read().unwrap();
+        let events = entity_manager.get_resource::<Events<CountEvent>>().unwrap();
+        let events = events.read().unwrap();
+        let total = entity_manager.get_resource::<TotalResource>().unwrap();
+        let mut total = total.write().unwrap();
+        total
+            .events
+            .extend(events.read().map(|event| event.0 * self.interval));
+        match self.cooldown {
+            0 => {
+                total.total += self.interval;
+                self.cooldown = self.interval - 1;
+            }
+            _ => self.cooldown -= 1,
+        }
+    }
+
+    fn access(&self) -> SystemAccess {
+        SystemAccess::new()
+            .add_resource_read::<Events<CountEvent>>()
+            .add_resource_write::<TotalResource>()
+    }
+}
+
+#[test]
+fn stateful_system_instances_success() {
+    let mut entity_manager = EntityManager::new();
+    entity_manager.insert_resource(TotalResource::default());
+    let mut events = Events::new();
+    events.send(CountEvent(1));
+    entity_manager.insert_resource(events);
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group()
+        .register_instance(CooldownSystem {
+            interval: 1,
+            cooldown: 0,
+        })
+        .register_instance(CooldownSystem {
+            interval: 10,
+            cooldown: 2,
+        });
+    assert_eq!(
+        group.get_system_names(),
+        vec![CooldownSystem::property_string(); 2]
+    );
+    assert!(!group.contains::<TestSystem>());
+
+    (0..4).for_each(|_| {
+        SystemManager.execute_group(
+            &group,
+            entity_manager.clone(),
+            Arc::new(SystemParamAccessor::new()),
+        );
+    });
+
+    let total = entity_manager
+        .read()
+        .unwrap()
+        .get_resource::<TotalResource>()
+        .unwrap();
+    let total = total.read().unwrap();
+    // The first instance fires every execution, the second on the third.
+    assert_eq!(total.total, 4 + 10);
+    // Each instance reads the `Event` once.
+    assert_eq!(total.events, vec![1, 10]);
+}
+
+fn count_executions(
+    mut count: Local<u32>,
+    mut other: Local<u32>,
+    mut total: ResMut<TotalResource>,
+) {
+    *count += 1;
+    *other += 2;
+    total.events.push(*count + *other);
+}
+
+#[test]
+fn function_system_local_success() {
+    let mut entity_manager = EntityManager::new();
+    entity_manager.insert_resource(TotalResource::default());
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group()
+        .add_system(count_executions)
+        .add_system(|mut count: Local<u32>, mut total: ResMut<TotalResource>| {
+            *count += 10;
+            total.events.push(*count);
+        });
+
+    (0..2).for_each(|_| {
+        SystemManager.execute_group(
+            &group,
+            entity_manager.clone(),
+            Arc::new(SystemParamAccessor::new()),
+        );
+    });
+
+    let total = entity_manager
+        .read()
+        .unwrap()
+        .get_resource::<TotalResource>()
+        .unwrap();
+    assert_eq!(total.read().unwrap().events, vec![3, 10, 6, 20]);
+}
+
+#[derive(Resource)]
+struct PausedResource {
+    paused: bool,
+}
+
+fn is_running(paused: Res<PausedResource>) -> bool {
+    !paused.paused
+}
+
+#[test]
+fn system_group_run_conditions_success() {
+    let mut entity_manager = EntityManager::new();
+    entity_manager.insert_resource(TotalResource::default());
+    entity_manager.insert_resource(PausedResource { paused: false });
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_parallel_group()
+        .add_system(|mut total: ResMut<TotalResource>| total.total += 1)
+        .run_if(is_running)
+        .add_system(|mut total: ResMut<TotalResource>| total.events.push(0))
+        .run_if(|mut executions: Local<u32>| {
+            *executions += 1;
+            *executions % 2 == 1
+        })
+        .run_group_if(|total: Res<TotalResource>| total.events.len() < 3);
+    assert!(group.get_registered_systems()[0]
+        .get_access()
+        .get_resource_access()
+        .reads(&PausedResource::property_id()));
+
+    let execute = || {
+        SystemManager.execute_group(
+            &group,
+            entity_manager.clone(),
+            Arc::new(SystemParamAccessor::new()),
+        )
+    };
+    let get_total = || {
+        let total = entity_manager
+            .read()
+            .unwrap()
+            .get_resource::<TotalResource>()
+            .unwrap();
+        let total = total.read().unwrap();
+        (total.total, total.events.len())
+    };
+
+    assert!(execute());
+    assert_eq!(get_total(), (1, 1));
+    entity_manager
+        .read()
+        .unwrap()
+        .get_resource::<PausedResource>()
+        .unwrap()
+        .write()
+        .unwrap()
+        .paused = true;
+    assert!(execute());
+    assert_eq!(get_total(), (1, 1));
+    (0..4).for_each(|_| {
+        execute();
+    });
+    // The group stops once 3 events were recorded, on every other execution.
+    assert_eq!(get_total(), (1, 3));
+    assert!(!execute());
+}
+
+#[test]
+#[should_panic]
+fn system_group_run_if_without_system_panics() {
+    SystemGroup::new_sequential_group().run_if(is_running);
+}
+
+#[test]
+fn system_group_enabled_success() {
+    let mut group = SystemGroup::new_sequential_group()
+        .register::<TestSystem>()
+        .register_instance(CooldownSystem {
+            interval: 1,
+            cooldown: 0,
+        })
+        .register_instance(CooldownSystem {
+            interval: 2,
+            cooldown: 0,
+        })
+        .add_system(read_total);
+    assert!(group.set_system_enabled::<CooldownSystem>(false));
+    assert!(group.set_function_enabled(read_total, false));
+    assert!(!group.set_function_enabled(write_total, false));
+    assert!(!group.set_system_enabled::<NoAccessSystem>(false));
+    assert_eq!(
+        group
+            .get_registered_systems()
+            .iter()
+            .map(|system| system.is_enabled())
+            .collect::<Vec<_>>(),
+        vec![true, false, false, false]
+    );
+
+    group.set_enabled(false);
+    assert!(!group.is_enabled());
+    assert!(!SystemManager.execute_group(
+        &group,
+        Arc::new(RwLock::new(EntityManager::new())),
+        Arc::new(SystemParamAccessor::new()),
+    ));
+}
@@ -1,7 +1,15 @@
 #![cfg(test)]
 
-use crate::system::{SystemGroup, SystemParamAccessor};
-use crate::Engine;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::component::{Component, ComponentBundle};
+use crate::entity::{Commands, Entity, EntityManager, QueryBorrow};
+use crate::event::{Event, Events};
+use crate::resource::{Res, ResMut, Resource};
+use crate::system::{Local, System, SystemAccess, SystemGroup, SystemParamAccessor};
+use crate::time::{RunControl, Time};
+use crate::{Engine, Error, UpdateRate};
 
 #[test]
 fn engine_register_system_group_unique_id_success() {
@@ -28,6 +36,15 @@ fn engine_execute_unregistered_group_panics() {
     Engine::new().execute_group(unregistered_group_id, SystemParamAccessor::new());
 }
 
+#[test]
+fn engine_try_execute_unregistered_group_fails() {
+    let unregistered_group_id = 100;
+    assert_eq!(
+        Engine::new().try_execute_group(unregistered_group_id, SystemParamAccessor::new()),
+        Err(Error::SystemGroupNotRegistered(unregistered_group_id))
+    );
+}
+
 #[test]
 #[should_panic]
 fn engine_deregister_success() {
@@ -36,3 +53,399 @@ fn engine_deregister_success() {
     engine.deregister_system_group(group_id);
     engine.execute_group(group_id, SystemParamAccessor::new());
 }
+
+#[test]
+fn engine_get_system_group_mut_success() {
+    let mut engine = Engine::new();
+    let group_id = engine.register_system_group(SystemGroup::new_sequential_group());
+    assert!(engine.get_system_group_mut(group_id).is_some());
+    assert!(engine.get_system_group_mut(group_id + 1).is_none());
+}
+
+#[derive(Resource)]
+struct CounterResource {
+    count: u32,
+}
+
+#[derive(System)]
+struct IncrementCounterSystem;
+impl System for IncrementCounterSystem {
+    fn execute(entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {
+        let counter = entity_manager
+            .read()
+            .unwrap()
+            .get_resource::<CounterResource>()
+            .unwrap();
+        counter.write().unwrap().count += 1;
+    }
+
+    fn access() -> SystemAccess {
+        SystemAccess::new().add_resource_write::<CounterResource>()
+    }
+}
+
+#[test]
+fn engine_resource_success() {
+    let mut engine = Engine::new();
+    assert!(engine.get_resource::<CounterResource>().is_none());
+
+    engine.insert_resource(CounterResource { count: 0 });
+    let group_id = engine.register_system_group(
+        SystemGroup::new_parallel_group().register::<IncrementCounterSystem>(),
+    );
+    for _ in 0..3 {
+        engine.execute_group(group_id, SystemParamAccessor::new());
+    }
+    assert_eq!(
+        engine
+            .get_resource::<CounterResource>()
+            .unwrap()
+            .read()
+            .unwrap()
+            .count,
+        3
+    );
+
+    assert!(engine.remove_resource::<CounterResource>().is_some());
+    assert!(engine.get_resource::<CounterResource>().is_none());
+}
+
+#[derive(Event)]
+struct DamageEvent {
+    damage: u32,
+}
+
+#[derive(Resource, Default)]
+struct TotalDamageResource {
+    damage: u32,
+}
+
+#[derive(System)]
+struct SendDamageSystem;
+impl System for SendDamageSystem {
+    fn execute(entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {
+        entity_manager
+            .read()
+            .unwrap()
+            .send_event(DamageEvent { damage: 2 });
+    }
+}
+
+#[derive(System)]
+struct ReceiveDamageSystem;
+impl System for ReceiveDamageSystem {
+    fn execute(entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {
+        let entity_manager = entity_manager.read().unwrap();
+        let events = entity_manager
+            .get_resource::<Events<DamageEvent>>()
+            .unwrap();
+        let total = entity_manager
+            .get_resource::<TotalDamageResource>()
+            .unwrap();
+        events
+            .read()
+            .unwrap()
+            .read()
+            .for_each(|event| total.write().unwrap().damage += event.damage);
+    }
+}
+
+#[test]
+fn engine_events_between_groups_success() {
+    let mut engine = Engine::new();
+    engine.add_event::<DamageEvent>();
+    engine.insert_resource(TotalDamageResource::default());
+    let send_group_id = engine
+        .register_system_group(SystemGroup::new_sequential_group().register::<SendDamageSystem>());
+    let receive_group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group().register::<ReceiveDamageSystem>(),
+    );
+
+    engine.execute_group(send_group_id, SystemParamAccessor::new());
+    engine.finish_frame();
+    engine.execute_group(receive_group_id, SystemParamAccessor::new());
+    engine.execute_group(receive_group_id, SystemParamAccessor::new());
+    let total = engine.get_resource::<TotalDamageResource>().unwrap();
+    assert_eq!(total.read().unwrap().damage, 2);
+
+    // Events are dropped at the end of the frame after the one they were sent in.
+    engine.execute_group(send_group_id, SystemParamAccessor::new());
+    engine.execute_group(send_group_id, SystemParamAccessor::new());
+    engine.finish_frame();
+    engine.finish_frame();
+    engine.execute_group(send_group_id, SystemParamAccessor::new());
+    engine.execute_group(receive_group_id, SystemParamAccessor::new());
+    assert_eq!(total.read().unwrap().damage, 4);
+}
+
+#[test]
+fn engine_events_sent_after_reader_success() {
+    let mut engine = Engine::new();
+    engine.add_event::<DamageEvent>();
+    engine.insert_resource(TotalDamageResource::default());
+    let empty_group_id = engine.register_system_group(SystemGroup::new_sequential_group());
+    let receive_group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group().register::<ReceiveDamageSystem>(),
+    );
+    let send_group_id = engine
+        .register_system_group(SystemGroup::new_sequential_group().register::<SendDamageSystem>());
+    for group_id in [empty_group_id, receive_group_id, send_group_id] {
+        engine.schedule_group(group_id, UpdateRate::Variable);
+    }
+
+    // Events sent after the reader executed are read during the next frame.
+    for _ in 0..10 {
+        engine.update(Duration::ZERO, SystemParamAccessor::new());
+    }
+    engine.execute_group(receive_group_id, SystemParamAccessor::new());
+    let total = engine.get_resource::<TotalDamageResource>().unwrap();
+    assert_eq!(total.read().unwrap().damage, 20);
+}
+
+#[test]
+#[should_panic]
+fn engine_send_unregistered_event_panics() {
+    EntityManager::new().send_event(DamageEvent { damage: 1 });
+}
+
+fn add_ten(mut counter: ResMut<CounterResource>) {
+    counter.count += 10;
+}
+
+#[test]
+fn engine_enable_disable_success() {
+    let mut engine = Engine::new();
+    engine.insert_resource(CounterResource { count: 0 });
+    let group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group()
+            .register::<IncrementCounterSystem>()
+            .add_system(add_ten),
+    );
+    let execute_and_get_count = |engine: &mut Engine| {
+        engine.execute_group(group_id, SystemParamAccessor::new());
+        let counter = engine.get_resource::<CounterResource>().unwrap();
+        let count = counter.read().unwrap().count;
+        count
+    };
+    assert_eq!(execute_and_get_count(&mut engine), 11);
+
+    assert!(engine.set_system_enabled::<IncrementCounterSystem>(false));
+    assert_eq!(execute_and_get_count(&mut engine), 21);
+    assert!(engine.set_function_enabled(add_ten, false));
+    assert_eq!(execute_and_get_count(&mut engine), 21);
+    assert!(engine.set_system_enabled::<IncrementCounterSystem>(true));
+    assert_eq!(execute_and_get_count(&mut engine), 22);
+
+    engine.set_group_enabled(group_id, false);
+    assert_eq!(execute_and_get_count(&mut engine), 22);
+    engine.set_group_enabled(group_id, true);
+    assert_eq!(execute_and_get_count(&mut engine), 23);
+
+    assert!(!engine.set_system_enabled::<SendDamageSystem>(false));
+    assert_eq!(
+        engine.try_set_group_enabled(group_id + 1, false),
+        Err(Error::SystemGroupNotRegistered(group_id + 1))
+    );
+}
+
+#[derive(Resource, Default)]
+struct FrameResource {
+    frames: Vec<(u64, f32)>,
+}
+
+fn record_frame(time: Res<Time>, mut frame: ResMut<FrameResource>) {
+    frame
+        .frames
+        .push((time.get_frame_count(), time.get_alpha()));
+}
+
+#[test]
+fn engine_update_success() {
+    let mut engine = Engine::new();
+    engine.set_fixed_timestep(Duration::from_millis(10));
+    engine.insert_resource(CounterResource { count: 0 });
+    engine.insert_resource(FrameResource::default());
+    let fixed_group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group().register::<IncrementCounterSystem>(),
+    );
+    let variable_group_id =
+        engine.register_system_group(SystemGroup::new_sequential_group().add_system(record_frame));
+    engine.schedule_group(variable_group_id, UpdateRate::Variable);
+    engine.schedule_group(fixed_group_id, UpdateRate::Fixed);
+
+    engine.update(Duration::from_millis(25), SystemParamAccessor::new());
+    engine.update(Duration::from_millis(3), SystemParamAccessor::new());
+    engine.update(Duration::from_millis(2), SystemParamAccessor::new());
+
+    let counter = engine.get_resource::<CounterResource>().unwrap();
+    assert_eq!(counter.read().unwrap().count, 3);
+    let frame = engine.get_resource::<FrameResource>().unwrap();
+    let frames = frame.read().unwrap().frames.clone();
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0].0, 1);
+    assert!((frames[0].1 - 0.5).abs() < 1e-4);
+    assert!((frames[1].1 - 0.8).abs() < 1e-4);
+    assert!(frames[2].1.abs() < 1e-4);
+
+    let time = engine.get_resource::<Time>().unwrap();
+    let time = time.read().unwrap();
+    assert_eq!(time.get_frame_count(), 3);
+    assert_eq!(time.get_delta(), Duration::from_millis(2));
+    assert_eq!(time.get_elapsed(), Duration::from_millis(30));
+    assert_eq!(time.get_fixed_elapsed(), Duration::from_millis(30));
+}
+
+#[test]
+fn engine_update_max_fixed_steps_success() {
+    let mut engine = Engine::new();
+    engine.set_fixed_timestep(Duration::from_millis(10));
+    engine.set_max_fixed_steps(4);
+    engine.insert_resource(CounterResource { count: 0 });
+    let group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group().register::<IncrementCounterSystem>(),
+    );
+    engine.schedule_group(group_id, UpdateRate::Fixed);
+
+    // Time beyond the maximum number of fixed steps is dropped, rather than caught up on later.
+    engine.update(Duration::from_millis(1005), SystemParamAccessor::new());
+    let counter = engine.get_resource::<CounterResource>().unwrap();
+    assert_eq!(counter.read().unwrap().count, 4);
+    engine.update(Duration::from_millis(5), SystemParamAccessor::new());
+    assert_eq!(counter.read().unwrap().count, 5);
+
+    engine.unschedule_group(group_id);
+    engine.update(Duration::from_millis(100), SystemParamAccessor::new());
+    assert_eq!(counter.read().unwrap().count, 5);
+}
+
+#[test]
+fn engine_try_schedule_unregistered_group_fails() {
+    let unregistered_group_id = 100;
+    assert_eq!(
+        Engine::new().try_schedule_group(unregistered_group_id, UpdateRate::Fixed),
+        Err(Error::SystemGroupNotRegistered(unregistered_group_id))
+    );
+}
+
+fn exit_after_three_frames(mut frames: Local<u32>, mut run_control: ResMut<RunControl>) {
+    *frames += 1;
+    if *frames == 3 {
+        run_control.request_exit();
+    }
+}
+
+#[test]
+fn engine_run_headless_success() {
+    let mut engine = Engine::new();
+    let group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group().add_system(exit_after_three_frames),
+    );
+    engine.schedule_group(group_id, UpdateRate::Variable);
+
+    engine.run(Some(Duration::from_millis(1)));
+    let time = engine.get_resource::<Time>().unwrap();
+    assert_eq!(time.read().unwrap().get_frame_count(), 3);
+    assert!(time.read().unwrap().get_elapsed() >= Duration::from_millis(2));
+}
+
+#[derive(Component)]
+struct SpawnedComponent;
+
+fn respawn(mut spawned: QueryBorrow<(Entity, &SpawnedComponent)>, mut commands: Commands) {
+    spawned
+        .iter()
+        .for_each(|(entity_id, _)| commands.delete_entity(&entity_id));
+    for _ in 0..10 {
+        commands.create_entity(ComponentBundle::new().add_component(SpawnedComponent));
+    }
+}
+
+#[derive(System)]
+struct CountDeletedSystem;
+impl System for CountDeletedSystem {
+    fn execute(entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {
+        let entity_manager = entity_manager.read().unwrap();
+        let counter = entity_manager.get_resource::<CounterResource>().unwrap();
+        counter.write().unwrap().count = entity_manager.get_deleted_entities().len() as u32;
+    }
+
+    fn access() -> SystemAccess {
+        SystemAccess::new().add_resource_write::<CounterResource>()
+    }
+}
+
+#[test]
+fn engine_removal_log_bounded_success() {
+    let mut engine = Engine::new();
+    engine.insert_resource(CounterResource { count: 0 });
+    let startup_group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group().register::<IncrementCounterSystem>(),
+    );
+    // Never executes until the end, so it would observe every logged deletion.
+    let count_group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group().register::<CountDeletedSystem>(),
+    );
+    let respawn_group_id =
+        engine.register_system_group(SystemGroup::new_sequential_group().add_system(respawn));
+    engine.schedule_group(respawn_group_id, UpdateRate::Variable);
+
+    engine.execute_group(startup_group_id, SystemParamAccessor::new());
+    for _ in 0..1000 {
+        engine.update(Duration::ZERO, SystemParamAccessor::new());
+    }
+
+    // Only the deletions of the last frame are still logged.
+    engine.execute_group(count_group_id, SystemParamAccessor::new());
+    let counter = engine.get_resource::<CounterResource>().unwrap();
+    assert_eq!(counter.read().unwrap().count, 10);
+}
+
+#[test]
+fn engine_removal_log_bounded_without_finish_frame_success() {
+    let mut engine = Engine::new();
+    engine.insert_resource(CounterResource { count: 0 });
+    let count_group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group().register::<CountDeletedSystem>(),
+    );
+    let respawn_group_id =
+        engine.register_system_group(SystemGroup::new_sequential_group().add_system(respawn));
+
+    // Executing a group again implicitly finishes the frame.
+    for _ in 0..1000 {
+        engine.execute_group(respawn_group_id, SystemParamAccessor::new());
+    }
+
+    // Only the deletions of the previous and the current frame are still logged.
+    engine.execute_group(count_group_id, SystemParamAccessor::new());
+    let counter = engine.get_resource::<CounterResource>().unwrap();
+    assert_eq!(counter.read().unwrap().count, 20);
+}
+
+#[test]
+fn engine_events_bounded_without_finish_frame_success() {
+    let mut engine = Engine::new();
+    engine.add_event::<DamageEvent>();
+    engine.insert_resource(TotalDamageResource::default());
+    let empty_group_id = engine.register_system_group(SystemGroup::new_sequential_group());
+    let receive_group_id = engine.register_system_group(
+        SystemGroup::new_sequential_group().register::<ReceiveDamageSystem>(),
+    );
+    let send_group_id = engine
+        .register_system_group(SystemGroup::new_sequential_group().register::<SendDamageSystem>());
+
+    // Executing a group again implicitly finishes the frame, which updates the Events.
+    for _ in 0..10 {
+        for group_id in [empty_group_id, receive_group_id, send_group_id] {
+            engine.execute_group(group_id, SystemParamAccessor::new());
+        }
+        let events = engine.get_resource::<Events<DamageEvent>>().unwrap();
+        assert!(events.read().unwrap().len() <= 2);
+    }
+
+    // The Event sent during the last frame is still read during the next one.
+    let total = engine.get_resource::<TotalDamageResource>().unwrap();
+    assert_eq!(total.read().unwrap().damage, 18);
+    engine.execute_group(empty_group_id, SystemParamAccessor::new());
+    engine.execute_group(receive_group_id, SystemParamAccessor::new());
+    assert_eq!(total.read().unwrap().damage, 20);
+}
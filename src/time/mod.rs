@@ -0,0 +1,7 @@
+mod run_control;
+mod test;
+#[allow(clippy::module_inception)]
+mod time;
+
+pub use self::run_control::*;
+pub use self::time::*;
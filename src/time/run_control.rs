@@ -0,0 +1,35 @@
+use crate::resource::Resource;
+
+/// Controls the headless loop started via `Engine::run`. A `Resource`, inserted by `Engine::new`.
+///
+/// From within a `System`:
+///
+/// ```ignore
+/// fn shutdown(mut run_control: ResMut<RunControl>) {
+///     run_control.request_exit();
+/// }
+/// ```
+#[derive(Resource, Debug, Default)]
+pub struct RunControl {
+    exit_requested: bool,
+}
+
+impl RunControl {
+    pub fn new() -> Self {
+        RunControl::default()
+    }
+
+    /// Requests `Engine::run` to return once the current update has finished.
+    pub fn request_exit(&mut self) {
+        self.exit_requested = true;
+    }
+
+    /// Returns `true` if an exit has been requested.
+    pub fn is_exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.exit_requested = false;
+    }
+}
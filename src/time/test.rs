@@ -0,0 +1,36 @@
+#![cfg(test)]
+
+use std::time::Duration;
+
+use crate::time::*;
+
+#[test]
+fn time_advance_success() {
+    let mut time = Time::new(Duration::from_millis(10));
+    assert_eq!(time.get_frame_count(), 0);
+    assert_eq!(time.get_delta(), Duration::ZERO);
+
+    time.advance(Duration::from_millis(16));
+    time.advance(Duration::from_millis(25));
+    assert_eq!(time.get_frame_count(), 2);
+    assert_eq!(time.get_delta(), Duration::from_millis(25));
+    assert_eq!(time.get_delta_seconds(), 0.025);
+    assert_eq!(time.get_elapsed(), Duration::from_millis(41));
+
+    time.advance_fixed();
+    time.advance_fixed();
+    assert_eq!(time.get_fixed_delta(), Duration::from_millis(10));
+    assert_eq!(time.get_fixed_elapsed(), Duration::from_millis(20));
+}
+
+#[test]
+fn run_control_request_exit_success() {
+    let mut run_control = RunControl::new();
+    assert!(!run_control.is_exit_requested());
+
+    run_control.request_exit();
+    assert!(run_control.is_exit_requested());
+
+    run_control.clear();
+    assert!(!run_control.is_exit_requested());
+}
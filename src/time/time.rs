@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use crate::resource::Resource;
+
+/// Tracks the passage of time across `Engine::update`s. A `Resource`, inserted by `Engine::new`
+/// and advanced by the `Engine` before executing the scheduled `SystemGroup`s.
+///
+/// `System`s in variable-rate groups should scale by `get_delta`, while `System`s in fixed-timestep
+/// groups should scale by `get_fixed_delta`. Rendering may use `get_alpha` to interpolate between
+/// the previous and the current fixed step.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Time {
+    delta: Duration,
+    elapsed: Duration,
+    frame_count: u64,
+    fixed_delta: Duration,
+    fixed_elapsed: Duration,
+    alpha: f32,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Time::new(Duration::from_secs_f64(1.0 / 60.0))
+    }
+}
+
+impl Time {
+    pub fn new(fixed_delta: Duration) -> Self {
+        Time {
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            frame_count: 0,
+            fixed_delta,
+            fixed_elapsed: Duration::ZERO,
+            alpha: 0.0,
+        }
+    }
+
+    /// Returns the time passed since the previous update.
+    pub fn get_delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Returns the time passed since the previous update, in seconds.
+    pub fn get_delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Returns the total time passed across all updates.
+    pub fn get_elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the number of updates so far, including the current one.
+    pub fn get_frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Returns the duration of a single fixed step.
+    pub fn get_fixed_delta(&self) -> Duration {
+        self.fixed_delta
+    }
+
+    /// Returns the duration of a single fixed step, in seconds.
+    pub fn get_fixed_delta_seconds(&self) -> f32 {
+        self.fixed_delta.as_secs_f32()
+    }
+
+    /// Returns the total time simulated by fixed steps.
+    pub fn get_fixed_elapsed(&self) -> Duration {
+        self.fixed_elapsed
+    }
+
+    /// Returns how far the time left over after the last fixed step reaches into the next one, in
+    /// the range `0.0..1.0`.
+    pub fn get_alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    pub(crate) fn advance(&mut self, delta: Duration) {
+        self.delta = delta;
+        self.elapsed += delta;
+        self.frame_count += 1;
+    }
+
+    pub(crate) fn advance_fixed(&mut self) {
+        self.fixed_elapsed += self.fixed_delta;
+    }
+
+    pub(crate) fn set_fixed_delta(&mut self, fixed_delta: Duration) {
+        self.fixed_delta = fixed_delta;
+    }
+
+    pub(crate) fn set_alpha(&mut self, alpha: f32) {
+        self.alpha = alpha;
+    }
+}
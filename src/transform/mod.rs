@@ -0,0 +1,9 @@
+mod propagation;
+mod test;
+mod transform_2d;
+mod transform_3d;
+
+pub use self::propagation::*;
+pub use self::transform_2d::*;
+pub use self::transform_3d::*;
+pub use glam;
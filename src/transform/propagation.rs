@@ -0,0 +1,175 @@
+use std::collections::HashSet;
+use std::ops::Mul;
+use std::sync::{Arc, RwLock};
+
+use glam::{Affine2, Affine3A};
+
+use crate::component::Component;
+use crate::entity::{Entity, EntityManager, Query};
+use crate::hierarchy::{Children, Parent};
+use crate::system::{System, SystemAccess, SystemParamAccessor};
+use crate::transform::{GlobalTransform2D, GlobalTransform3D, Transform2D, Transform3D};
+
+/// A built-in `System` that updates the `GlobalTransform2D` / `GlobalTransform3D` of every Entity
+/// that has one alongside a `Transform2D` / `Transform3D`, by combining its transform with those of
+/// its ancestors. Entities whose parent has no such transform are treated as roots.
+///
+/// Only dirty subtrees are recomputed: those rooted at an Entity whose transform changed, whose
+/// global transform was added, or whose `Parent` was added, changed or removed since this `System`
+/// last ran.
+///
+/// Register this after the `System`s moving Entities, e.g.:
+///
+/// ```ignore
+/// SystemGroup::new_sequential_group()
+///     .register::<MovementSystem>()
+///     .register::<TransformPropagationSystem>()
+/// ```
+///
+/// Note: an Entity needs both its transform and a (default) global transform to take part, e.g.
+/// `.add_component(Transform2D::from_xy(1.0, 2.0)).add_component(GlobalTransform2D::default())`.
+#[derive(System)]
+pub struct TransformPropagationSystem;
+impl System for TransformPropagationSystem {
+    fn execute(entity_manager: Arc<RwLock<EntityManager>>, _params: Arc<SystemParamAccessor>) {
+        let entity_manager = entity_manager.read().unwrap();
+        propagate::<Transform2D>(&entity_manager);
+        propagate::<Transform3D>(&entity_manager);
+    }
+
+    fn access() -> SystemAccess {
+        SystemAccess::new()
+            .add_read::<Parent>()
+            .add_read::<Children>()
+            .add_read::<Transform2D>()
+            .add_write::<GlobalTransform2D>()
+            .add_read::<Transform3D>()
+            .add_write::<GlobalTransform3D>()
+    }
+}
+
+/// A local transform `Component`, alongside the global transform `Component` computed from it.
+trait LocalTransform: 'static + Component {
+    type Global: 'static + Component;
+    type Affine: Copy + Mul<Output = Self::Affine>;
+
+    fn compute_affine(&self) -> Self::Affine;
+
+    fn get_global(global: &Self::Global) -> Self::Affine;
+
+    fn set_global(global: &mut Self::Global, affine: Self::Affine);
+}
+
+impl LocalTransform for Transform2D {
+    type Global = GlobalTransform2D;
+    type Affine = Affine2;
+
+    fn compute_affine(&self) -> Affine2 {
+        Transform2D::compute_affine(self)
+    }
+
+    fn get_global(global: &GlobalTransform2D) -> Affine2 {
+        global.affine()
+    }
+
+    fn set_global(global: &mut GlobalTransform2D, affine: Affine2) {
+        global.set_affine(affine);
+    }
+}
+
+impl LocalTransform for Transform3D {
+    type Global = GlobalTransform3D;
+    type Affine = Affine3A;
+
+    fn compute_affine(&self) -> Affine3A {
+        Transform3D::compute_affine(self)
+    }
+
+    fn get_global(global: &GlobalTransform3D) -> Affine3A {
+        global.affine()
+    }
+
+    fn set_global(global: &mut GlobalTransform3D, affine: Affine3A) {
+        global.set_affine(affine);
+    }
+}
+
+fn propagate<L: LocalTransform>(entity_manager: &EntityManager) {
+    let collect = |query: Query| -> Vec<Entity> {
+        entity_manager
+            .filter_iter(&query.with::<L>().with::<L::Global>())
+            .map(|result| result.entity_id())
+            .collect()
+    };
+    let mut dirty: HashSet<Entity> = collect(Query::new().changed::<L>())
+        .into_iter()
+        .chain(collect(Query::new().added::<L::Global>()))
+        .chain(collect(Query::new().changed::<Parent>()))
+        .collect();
+    dirty.extend(
+        entity_manager
+            .get_removed_components::<Parent>()
+            .into_iter()
+            .filter(|entity_id| is_transformed::<L>(entity_manager, entity_id)),
+    );
+
+    // Subtrees of dirty Entities are recomputed as a whole, so dirty descendants are skipped.
+    dirty
+        .iter()
+        .filter(|entity_id| {
+            !get_transformed_ancestors::<L>(entity_manager, entity_id)
+                .iter()
+                .any(|ancestor| dirty.contains(ancestor))
+        })
+        .for_each(|entity_id| {
+            let parent_affine = entity_manager
+                .get_parent(entity_id)
+                .filter(|parent| is_transformed::<L>(entity_manager, parent))
+                .and_then(|parent| entity_manager.get::<L::Global>(&parent))
+                .map(|global| L::get_global(&global));
+            recompute::<L>(entity_manager, entity_id, parent_affine);
+        });
+}
+
+/// Recomputes the global transform of an Entity and all of its transformed descendants.
+fn recompute<L: LocalTransform>(
+    entity_manager: &EntityManager,
+    entity_id: &Entity,
+    parent_affine: Option<L::Affine>,
+) {
+    let local_affine = entity_manager.get::<L>(entity_id).unwrap().compute_affine();
+    let affine = match parent_affine {
+        Some(parent_affine) => parent_affine * local_affine,
+        None => local_affine,
+    };
+    L::set_global(
+        &mut entity_manager.get_mut::<L::Global>(entity_id).unwrap(),
+        affine,
+    );
+    entity_manager
+        .get_children(entity_id)
+        .iter()
+        .filter(|child| is_transformed::<L>(entity_manager, child))
+        .for_each(|child| recompute::<L>(entity_manager, child, Some(affine)));
+}
+
+fn is_transformed<L: LocalTransform>(entity_manager: &EntityManager, entity_id: &Entity) -> bool {
+    entity_manager.has::<L>(entity_id) && entity_manager.has::<L::Global>(entity_id)
+}
+
+/// Returns the ancestors of an Entity up to (excluding) the first one that isn't transformed.
+fn get_transformed_ancestors<L: LocalTransform>(
+    entity_manager: &EntityManager,
+    entity_id: &Entity,
+) -> Vec<Entity> {
+    let mut ancestors = Vec::new();
+    let mut current = *entity_id;
+    while let Some(parent) = entity_manager.get_parent(&current) {
+        if !is_transformed::<L>(entity_manager, &parent) {
+            break;
+        }
+        ancestors.push(parent);
+        current = parent;
+    }
+    ancestors
+}
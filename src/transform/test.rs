@@ -0,0 +1,284 @@
+#![cfg(test)]
+
+use std::f32::consts::FRAC_PI_2;
+use std::sync::{Arc, RwLock};
+
+use glam::{Quat, Vec2, Vec3};
+
+use crate::component::ComponentBundle;
+use crate::entity::{Entity, EntityManager};
+use crate::system::{SystemGroup, SystemManager, SystemParamAccessor};
+use crate::transform::*;
+
+fn create_2d(entity_manager: &mut EntityManager, transform: Transform2D) -> Entity {
+    entity_manager.create_entity(
+        ComponentBundle::new()
+            .add_component(transform)
+            .add_component(GlobalTransform2D::default()),
+    )
+}
+
+fn propagate(group: &SystemGroup, entity_manager: &Arc<RwLock<EntityManager>>) {
+    SystemManager.execute_group(
+        group,
+        entity_manager.clone(),
+        Arc::new(SystemParamAccessor::new()),
+    );
+}
+
+fn global_translation(entity_manager: &Arc<RwLock<EntityManager>>, entity_id: &Entity) -> Vec2 {
+    entity_manager
+        .read()
+        .unwrap()
+        .get::<GlobalTransform2D>(entity_id)
+        .unwrap()
+        .translation()
+}
+
+#[test]
+fn transform_propagation_2d_success() {
+    let mut entity_manager = EntityManager::new();
+    let tank = create_2d(&mut entity_manager, Transform2D::from_xy(10.0, 0.0));
+    let turret = create_2d(
+        &mut entity_manager,
+        Transform2D::from_xy(1.0, 0.0).with_rotation(FRAC_PI_2),
+    );
+    let barrel = create_2d(&mut entity_manager, Transform2D::from_xy(2.0, 0.0));
+    entity_manager.set_parent(&turret, &tank);
+    entity_manager.set_parent(&barrel, &turret);
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group().register::<TransformPropagationSystem>();
+
+    propagate(&group, &entity_manager);
+    assert_eq!(
+        global_translation(&entity_manager, &tank),
+        Vec2::new(10.0, 0.0)
+    );
+    assert_eq!(
+        global_translation(&entity_manager, &turret),
+        Vec2::new(11.0, 0.0)
+    );
+    assert!(global_translation(&entity_manager, &barrel).abs_diff_eq(Vec2::new(11.0, 2.0), 1e-5));
+
+    entity_manager
+        .read()
+        .unwrap()
+        .get_mut::<Transform2D>(&tank)
+        .unwrap()
+        .translation = Vec2::new(0.0, 5.0);
+    propagate(&group, &entity_manager);
+    assert!(global_translation(&entity_manager, &barrel).abs_diff_eq(Vec2::new(1.0, 7.0), 1e-5));
+
+    // Detached Entities become roots.
+    entity_manager.write().unwrap().remove_parent(&turret);
+    propagate(&group, &entity_manager);
+    assert_eq!(
+        global_translation(&entity_manager, &turret),
+        Vec2::new(1.0, 0.0)
+    );
+    assert!(global_translation(&entity_manager, &barrel).abs_diff_eq(Vec2::new(1.0, 2.0), 1e-5));
+}
+
+#[test]
+fn transform_propagation_only_recomputes_dirty_subtrees_success() {
+    let mut entity_manager = EntityManager::new();
+    let parent = create_2d(&mut entity_manager, Transform2D::from_xy(1.0, 0.0));
+    let child = create_2d(&mut entity_manager, Transform2D::from_xy(1.0, 0.0));
+    let other = create_2d(&mut entity_manager, Transform2D::from_xy(5.0, 0.0));
+    entity_manager.set_parent(&child, &parent);
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group().register::<TransformPropagationSystem>();
+    propagate(&group, &entity_manager);
+
+    // Tamper with the computed global transforms, which are only corrected once dirty again.
+    [child, other].iter().for_each(|entity_id| {
+        *entity_manager
+            .read()
+            .unwrap()
+            .get_mut::<GlobalTransform2D>(entity_id)
+            .unwrap() = GlobalTransform2D::default();
+    });
+    propagate(&group, &entity_manager);
+    assert_eq!(global_translation(&entity_manager, &child), Vec2::ZERO);
+    assert_eq!(global_translation(&entity_manager, &other), Vec2::ZERO);
+
+    entity_manager
+        .read()
+        .unwrap()
+        .get_mut::<Transform2D>(&parent)
+        .unwrap()
+        .scale = Vec2::splat(2.0);
+    propagate(&group, &entity_manager);
+    assert_eq!(
+        global_translation(&entity_manager, &child),
+        Vec2::new(3.0, 0.0)
+    );
+    assert_eq!(global_translation(&entity_manager, &other), Vec2::ZERO);
+}
+
+#[test]
+fn transform_propagation_3d_success() {
+    let mut entity_manager = EntityManager::new();
+    let parent = entity_manager.create_entity(
+        ComponentBundle::new()
+            .add_component(
+                Transform3D::from_xyz(0.0, 1.0, 0.0)
+                    .with_rotation(Quat::from_rotation_y(FRAC_PI_2)),
+            )
+            .add_component(GlobalTransform3D::default()),
+    );
+    let child = entity_manager.create_entity(
+        ComponentBundle::new()
+            .add_component(Transform3D::from_xyz(0.0, 0.0, 1.0))
+            .add_component(GlobalTransform3D::default()),
+    );
+    entity_manager.set_parent(&child, &parent);
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group().register::<TransformPropagationSystem>();
+
+    propagate(&group, &entity_manager);
+    let translation = entity_manager
+        .read()
+        .unwrap()
+        .get::<GlobalTransform3D>(&child)
+        .unwrap()
+        .translation();
+    assert!(translation.abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), 1e-5));
+}
+
+#[test]
+fn transform_propagation_child_change_only_recomputes_child_subtree_success() {
+    let mut entity_manager = EntityManager::new();
+    let parent = create_2d(&mut entity_manager, Transform2D::from_xy(10.0, 0.0));
+    let child = create_2d(&mut entity_manager, Transform2D::from_xy(1.0, 0.0));
+    let grandchild = create_2d(&mut entity_manager, Transform2D::from_xy(1.0, 0.0));
+    let sibling = create_2d(&mut entity_manager, Transform2D::from_xy(0.0, 1.0));
+    entity_manager.set_parent(&child, &parent);
+    entity_manager.set_parent(&grandchild, &child);
+    entity_manager.set_parent(&sibling, &parent);
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group().register::<TransformPropagationSystem>();
+    propagate(&group, &entity_manager);
+
+    // Tamper with the sibling, which must not be recomputed.
+    *entity_manager
+        .read()
+        .unwrap()
+        .get_mut::<GlobalTransform2D>(&sibling)
+        .unwrap() = GlobalTransform2D::default();
+    entity_manager
+        .read()
+        .unwrap()
+        .get_mut::<Transform2D>(&child)
+        .unwrap()
+        .translation = Vec2::new(3.0, 0.0);
+    propagate(&group, &entity_manager);
+
+    assert_eq!(
+        global_translation(&entity_manager, &parent),
+        Vec2::new(10.0, 0.0)
+    );
+    assert_eq!(global_translation(&entity_manager, &sibling), Vec2::ZERO);
+    assert_eq!(
+        global_translation(&entity_manager, &child),
+        Vec2::new(13.0, 0.0)
+    );
+    assert_eq!(
+        global_translation(&entity_manager, &grandchild),
+        Vec2::new(14.0, 0.0)
+    );
+}
+
+#[test]
+fn transform_propagation_remove_parent_becomes_root_success() {
+    let mut entity_manager = EntityManager::new();
+    let parent = create_2d(
+        &mut entity_manager,
+        Transform2D::from_xy(5.0, 5.0)
+            .with_rotation(FRAC_PI_2)
+            .with_scale(Vec2::splat(2.0)),
+    );
+    let child = create_2d(&mut entity_manager, Transform2D::from_xy(1.0, 0.0));
+    let grandchild = create_2d(&mut entity_manager, Transform2D::from_xy(0.0, 1.0));
+    entity_manager.set_parent(&child, &parent);
+    entity_manager.set_parent(&grandchild, &child);
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group().register::<TransformPropagationSystem>();
+    propagate(&group, &entity_manager);
+    assert!(global_translation(&entity_manager, &child).abs_diff_eq(Vec2::new(5.0, 7.0), 1e-5));
+
+    entity_manager.write().unwrap().remove_parent(&child);
+    propagate(&group, &entity_manager);
+    assert_eq!(
+        global_translation(&entity_manager, &child),
+        Vec2::new(1.0, 0.0)
+    );
+    assert_eq!(
+        global_translation(&entity_manager, &grandchild),
+        Vec2::new(1.0, 1.0)
+    );
+
+    // Moving the former parent no longer affects the new root.
+    entity_manager
+        .read()
+        .unwrap()
+        .get_mut::<Transform2D>(&parent)
+        .unwrap()
+        .translation = Vec2::new(-5.0, 0.0);
+    propagate(&group, &entity_manager);
+    assert_eq!(
+        global_translation(&entity_manager, &child),
+        Vec2::new(1.0, 0.0)
+    );
+}
+
+#[test]
+fn transform_propagation_reparent_success() {
+    let mut entity_manager = EntityManager::new();
+    let first_parent = create_2d(&mut entity_manager, Transform2D::from_xy(10.0, 0.0));
+    let second_parent = create_2d(&mut entity_manager, Transform2D::from_xy(0.0, 20.0));
+    let child = create_2d(&mut entity_manager, Transform2D::from_xy(1.0, 0.0));
+    let grandchild = create_2d(&mut entity_manager, Transform2D::from_xy(1.0, 0.0));
+    entity_manager.set_parent(&child, &first_parent);
+    entity_manager.set_parent(&grandchild, &child);
+    let entity_manager = Arc::new(RwLock::new(entity_manager));
+    let group = SystemGroup::new_sequential_group().register::<TransformPropagationSystem>();
+    propagate(&group, &entity_manager);
+    assert_eq!(
+        global_translation(&entity_manager, &grandchild),
+        Vec2::new(12.0, 0.0)
+    );
+
+    entity_manager
+        .write()
+        .unwrap()
+        .set_parent(&child, &second_parent);
+    propagate(&group, &entity_manager);
+    assert_eq!(
+        global_translation(&entity_manager, &child),
+        Vec2::new(1.0, 20.0)
+    );
+    assert_eq!(
+        global_translation(&entity_manager, &grandchild),
+        Vec2::new(2.0, 20.0)
+    );
+
+    // Only the new parent's movement propagates.
+    entity_manager
+        .read()
+        .unwrap()
+        .get_mut::<Transform2D>(&first_parent)
+        .unwrap()
+        .translation = Vec2::ZERO;
+    entity_manager
+        .read()
+        .unwrap()
+        .get_mut::<Transform2D>(&second_parent)
+        .unwrap()
+        .translation = Vec2::new(0.0, 30.0);
+    propagate(&group, &entity_manager);
+    assert_eq!(
+        global_translation(&entity_manager, &grandchild),
+        Vec2::new(2.0, 30.0)
+    );
+}
@@ -0,0 +1,89 @@
+use glam::{Affine2, Vec2};
+
+use crate::component::Component;
+
+/// The position, rotation (in radians) and scale of an Entity in 2D, relative to its `Parent`, or
+/// to the world if it has none. See `GlobalTransform2D` for the resulting world-space transform.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform2D {
+    pub translation: Vec2,
+    pub rotation: f32,
+    pub scale: Vec2,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Transform2D::IDENTITY
+    }
+}
+
+impl Transform2D {
+    pub const IDENTITY: Transform2D = Transform2D {
+        translation: Vec2::ZERO,
+        rotation: 0.0,
+        scale: Vec2::ONE,
+    };
+
+    pub fn from_translation(translation: Vec2) -> Self {
+        Transform2D {
+            translation,
+            ..Transform2D::IDENTITY
+        }
+    }
+
+    pub fn from_xy(x: f32, y: f32) -> Self {
+        Transform2D::from_translation(Vec2::new(x, y))
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec2) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Returns this transform as an affine transformation, applying scale, then rotation, then
+    /// translation.
+    pub fn compute_affine(&self) -> Affine2 {
+        Affine2::from_scale_angle_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+/// The world-space transform of an Entity in 2D, i.e. its `Transform2D` combined with those of
+/// all of its ancestors. Computed by `TransformPropagationSystem`.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlobalTransform2D {
+    affine: Affine2,
+}
+
+impl Default for GlobalTransform2D {
+    fn default() -> Self {
+        GlobalTransform2D {
+            affine: Affine2::IDENTITY,
+        }
+    }
+}
+
+impl GlobalTransform2D {
+    pub fn affine(&self) -> Affine2 {
+        self.affine
+    }
+
+    pub fn translation(&self) -> Vec2 {
+        self.affine.translation
+    }
+
+    /// Transforms a point from the Entity's local space into world space.
+    pub fn transform_point(&self, point: Vec2) -> Vec2 {
+        self.affine.transform_point2(point)
+    }
+
+    pub(crate) fn set_affine(&mut self, affine: Affine2) {
+        self.affine = affine;
+    }
+}
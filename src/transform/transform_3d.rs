@@ -0,0 +1,89 @@
+use glam::{Affine3A, Quat, Vec3};
+
+use crate::component::Component;
+
+/// The position, rotation and scale of an Entity in 3D, relative to its `Parent`, or to the world
+/// if it has none. See `GlobalTransform3D` for the resulting world-space transform.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform3D {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform3D {
+    fn default() -> Self {
+        Transform3D::IDENTITY
+    }
+}
+
+impl Transform3D {
+    pub const IDENTITY: Transform3D = Transform3D {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3::ONE,
+    };
+
+    pub fn from_translation(translation: Vec3) -> Self {
+        Transform3D {
+            translation,
+            ..Transform3D::IDENTITY
+        }
+    }
+
+    pub fn from_xyz(x: f32, y: f32, z: f32) -> Self {
+        Transform3D::from_translation(Vec3::new(x, y, z))
+    }
+
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Returns this transform as an affine transformation, applying scale, then rotation, then
+    /// translation.
+    pub fn compute_affine(&self) -> Affine3A {
+        Affine3A::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+}
+
+/// The world-space transform of an Entity in 3D, i.e. its `Transform3D` combined with those of
+/// all of its ancestors. Computed by `TransformPropagationSystem`.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlobalTransform3D {
+    affine: Affine3A,
+}
+
+impl Default for GlobalTransform3D {
+    fn default() -> Self {
+        GlobalTransform3D {
+            affine: Affine3A::IDENTITY,
+        }
+    }
+}
+
+impl GlobalTransform3D {
+    pub fn affine(&self) -> Affine3A {
+        self.affine
+    }
+
+    pub fn translation(&self) -> Vec3 {
+        self.affine.translation.into()
+    }
+
+    /// Transforms a point from the Entity's local space into world space.
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.affine.transform_point3(point)
+    }
+
+    pub(crate) fn set_affine(&mut self, affine: Affine3A) {
+        self.affine = affine;
+    }
+}
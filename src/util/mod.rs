@@ -1,5 +1,9 @@
-mod property_id;
 mod execution_mode;
+mod property_id;
+mod property_registry;
+mod test;
 
-pub use self::property_id::*;
 pub(crate) use self::execution_mode::*;
+pub use self::property_id::*;
+pub(crate) use self::property_registry::register_property;
+pub use self::property_registry::{cached_hash_property_string, hash_property_string};
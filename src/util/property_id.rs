@@ -1,5 +1,15 @@
 /// `PropertyId` is essentially a stand-in for `TypeId` that's usable on non-`static` traits.
 /// Useful when a given implementation does not have a `static` lifetime (e.g. `SystemParam`s).
+///
+/// `property_string` is the full type name, including generic arguments (so e.g. `Health<Player>`
+/// and `Health<Enemy>` are distinct), and `property_id` is its hash. Types are checked against
+/// hash collisions when registered as a `Component`, `System` or `Resource`.
+///
+/// Note: `property_string` is `std::any::type_name`, which is documented as neither unique nor
+/// stable. Within a build, registration compares `TypeId`s, so two distinct types sharing a name
+/// panic rather than being silently merged. Across builds (e.g. compiler upgrades or moved
+/// modules), names may change, which affects the default names of snapshots and scenes (see
+/// `TypeRegistry::register_named_component`).
 pub trait PropertyId {
     fn property_id() -> u64
     where
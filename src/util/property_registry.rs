@@ -0,0 +1,68 @@
+use std::any::TypeId;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{OnceLock, RwLock};
+
+use crate::util::PropertyId;
+
+/// Computes the `PropertyId::property_id` of a type given its `PropertyId::property_string`.
+#[doc(hidden)]
+pub fn hash_property_string(property_string: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    property_string.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Computes the `PropertyId::property_id` of a generic type like `hash_property_string`, caching
+/// the result. Statics are shared between all instantiations of a generic type, so instead of a
+/// per-type static, hashes are cached by the address and length of the `'static`
+/// `property_string` (constant strings may share a start address, e.g. `a::Foo` and
+/// `a::Foo<u32>`).
+#[doc(hidden)]
+pub fn cached_hash_property_string(property_string: &'static str) -> u64 {
+    static CACHE: OnceLock<RwLock<HashMap<(usize, usize), u64>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| RwLock::new(HashMap::new()));
+    let key = (property_string.as_ptr() as usize, property_string.len());
+    if let Some(property_id) = cache.read().unwrap().get(&key) {
+        return *property_id;
+    }
+    *cache
+        .write()
+        .unwrap()
+        .entry(key)
+        .or_insert_with(|| hash_property_string(property_string))
+}
+
+/// Every type registered so far (as a `Component`, `System`, `Resource`, etc.), by `property_id`.
+fn get_registry() -> &'static RwLock<HashMap<u64, (TypeId, &'static str)>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<u64, (TypeId, &'static str)>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Registers `T`, returning its `property_id`. Since `property_id`s are hashes, two distinct types
+/// may share one, in which case they would be indistinguishable to `moecs`.
+///
+/// Will panic if a different type with the same `property_id` was registered before.
+pub(crate) fn register_property<T: 'static + PropertyId>() -> u64 {
+    let property_id = T::property_id();
+    let type_id = TypeId::of::<T>();
+    let registered = get_registry().read().unwrap().get(&property_id).copied();
+    let (registered_type_id, registered_name) = match registered {
+        Some(registered) => registered,
+        None => *get_registry()
+            .write()
+            .unwrap()
+            .entry(property_id)
+            .or_insert((type_id, T::property_string())),
+    };
+    if registered_type_id != type_id {
+        panic!(
+            "{} and {} share the same PropertyId: {}!",
+            registered_name,
+            T::property_string(),
+            property_id
+        );
+    }
+    property_id
+}
@@ -0,0 +1,132 @@
+#![cfg(test)]
+
+use std::marker::PhantomData;
+
+use crate::component::Component;
+use crate::util::*;
+
+struct Player;
+
+struct Enemy;
+
+#[derive(Component)]
+struct HealthComponent<T: 'static + Send + Sync> {
+    health: u32,
+    marker: PhantomData<T>,
+}
+
+#[derive(Component)]
+struct TestComponent;
+
+/// Manually implements `PropertyId` with a fixed id, to force a collision.
+macro_rules! define_colliding_property {
+    ($name:ident) => {
+        struct $name;
+        impl PropertyId for $name {
+            fn property_id() -> u64 {
+                7
+            }
+
+            fn property_string() -> &'static str {
+                stringify!($name)
+            }
+
+            fn self_property_id(&self) -> u64 {
+                Self::property_id()
+            }
+
+            fn self_property_string(&self) -> &'static str {
+                Self::property_string()
+            }
+        }
+    };
+}
+
+define_colliding_property!(CollidingProperty);
+define_colliding_property!(OtherCollidingProperty);
+
+#[test]
+fn property_id_generic_success() {
+    assert_ne!(
+        HealthComponent::<Player>::property_id(),
+        HealthComponent::<Enemy>::property_id()
+    );
+    assert_ne!(
+        HealthComponent::<Player>::property_string(),
+        HealthComponent::<Enemy>::property_string()
+    );
+    assert!(HealthComponent::<Player>::property_string().contains("Player"));
+    assert_eq!(
+        HealthComponent::<Player>::property_id(),
+        hash_property_string(HealthComponent::<Player>::property_string())
+    );
+    // Cached ids of generic types remain distinct per instantiation.
+    assert_eq!(
+        HealthComponent::<Enemy>::property_id(),
+        hash_property_string(HealthComponent::<Enemy>::property_string())
+    );
+    assert_eq!(
+        HealthComponent::<Player>::property_id(),
+        HealthComponent::<Player>::property_id()
+    );
+}
+
+#[test]
+fn cached_hash_property_string_shared_address_success() {
+    // A prefix of a `'static` string shares its start address.
+    let generic: &'static str = "a::Foo<u32>";
+    let plain: &'static str = &generic[..6];
+    assert_eq!(generic.as_ptr(), plain.as_ptr());
+    assert_eq!(
+        cached_hash_property_string(generic),
+        hash_property_string(generic)
+    );
+    assert_eq!(
+        cached_hash_property_string(plain),
+        hash_property_string(plain)
+    );
+}
+
+#[test]
+fn property_id_matches_self_property_id_success() {
+    let component = HealthComponent::<Enemy> {
+        health: 1,
+        marker: PhantomData,
+    };
+    assert_eq!(component.health, 1);
+    assert_eq!(
+        component.self_property_id(),
+        HealthComponent::<Enemy>::property_id()
+    );
+    assert_eq!(
+        TestComponent.self_property_string(),
+        TestComponent::property_string()
+    );
+}
+
+#[test]
+fn register_property_success() {
+    assert_eq!(
+        register_property::<TestComponent>(),
+        TestComponent::property_id()
+    );
+    assert_eq!(
+        register_property::<TestComponent>(),
+        TestComponent::property_id()
+    );
+    assert_eq!(
+        register_property::<HealthComponent<Player>>(),
+        HealthComponent::<Player>::property_id()
+    );
+    assert_eq!(
+        register_property::<HealthComponent<Enemy>>(),
+        HealthComponent::<Enemy>::property_id()
+    );
+}
+
+#[test]
+#[should_panic(expected = "share the same PropertyId")]
+fn register_property_collision_panics() {
+    register_property::<CollidingProperty>();
+    register_property::<OtherCollidingProperty>();
+}